@@ -9,29 +9,56 @@
     PackageVersion,
     installer::{Architecture, Installer},
     locale::{Copyright, PackageName, Publisher},
+    url::PackageUrl,
 };
 
-use super::extensions::{APPX, APPX_BUNDLE, EXE, MSI, MSIX, MSIX_BUNDLE, ZIP};
+use super::extensions::{APP_INSTALLER, APPX, APPX_BUNDLE, EXE, MSI, MSIX, MSIX_BUNDLE, ZIP};
 use crate::analysis::{
     Installers,
+    icon::Icon,
     installers::{
-        Exe, Msi, Zip,
+        Classification, DetectionReport, Exe, Msi, Zip,
         msix_family::{Msix, bundle::MsixBundle},
     },
 };
 
+/// What to gather about a file besides its installers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnalyzeOptions {
+    /// Record how the installer type of an exe was determined.
+    pub explain: bool,
+    /// Extract the largest icon of an exe or MSIX.
+    pub extract_icon: bool,
+}
+
 pub struct Analyzer<'reader, R: Read + Seek> {
     pub file_name: String,
     pub copyright: Option<Copyright>,
     pub package_name: Option<PackageName>,
     pub package_version: Option<PackageVersion>,
     pub publisher: Option<Publisher>,
+    pub package_url: Option<PackageUrl>,
     pub installers: Vec<Installer>,
     pub zip: Option<Zip<&'reader mut R>>,
+    /// How an exe that matched no known installer framework was classified.
+    pub classification: Option<Classification>,
+    /// How the installer type of an exe was determined, if the analysis was explained.
+    pub report: Option<DetectionReport>,
+    /// The largest icon found in an exe or MSIX, if icons were extracted.
+    pub icon: Option<Icon>,
 }
 
 impl<'reader, R: Read + Seek> Analyzer<'reader, R> {
     pub fn new(reader: &'reader mut R, file_name: &str) -> Result<Self> {
+        Self::with_options(reader, file_name, AnalyzeOptions::default())
+    }
+
+    /// Analyzes the file while also gathering what the options ask for.
+    pub fn with_options(
+        reader: &'reader mut R,
+        file_name: &str,
+        options: AnalyzeOptions,
+    ) -> Result<Self> {
         let extension = Utf8Path::new(file_name)
             .extension()
             .unwrap_or_default()
@@ -39,7 +66,14 @@ pub fn new(reader: &'reader mut R, file_name: &str) -> Result<Self> {
 
         let installers = match extension.as_str() {
             MSI => Msi::new(reader)?.installers(),
-            MSIX | APPX => Msix::new(reader)?.installers(),
+            MSIX | APPX => {
+                let mut msix = Msix::new(reader, options.extract_icon)?;
+                return Ok(Self {
+                    installers: msix.installers(),
+                    icon: msix.icon.take(),
+                    ..Self::default()
+                });
+            }
             MSIX_BUNDLE | APPX_BUNDLE => MsixBundle::new(reader)?.installers(),
             ZIP => {
                 let mut scoped_zip = Zip::new(reader)?;
@@ -51,7 +85,7 @@ pub fn new(reader: &'reader mut R, file_name: &str) -> Result<Self> {
                 });
             }
             EXE => {
-                let mut exe = Exe::new(reader)?;
+                let mut exe = Exe::new(reader, options)?;
                 let file_name_lower = file_name.to_lowercase();
                 let installers = exe
                     .installers()
@@ -85,9 +119,18 @@ pub fn new(reader: &'reader mut R, file_name: &str) -> Result<Self> {
                         .company_name
                         .take()
                         .and_then(|company_name| Publisher::new(company_name).ok()),
+                    package_url: exe
+                        .product_url()
+                        .and_then(|product_url| product_url.parse().ok()),
+                    classification: exe.classification.take(),
+                    report: exe.report.take(),
+                    icon: exe.icon.take(),
                     ..Self::default()
                 });
             }
+            APP_INSTALLER => bail!(
+                "{file_name} is an App Installer file. Use its URL instead so that the package it points to is downloaded"
+            ),
             _ => bail!(r#"Unsupported file extension: "{extension}""#),
         };
         Ok(Self {
@@ -105,8 +148,12 @@ fn default() -> Self {
             package_name: None,
             package_version: None,
             publisher: None,
+            package_url: None,
             installers: Vec::default(),
             zip: None,
+            classification: None,
+            report: None,
+            icon: None,
         }
     }
 }
@@ -4,4 +4,8 @@
 pub const APPX: &str = "appx";
 pub const MSIX_BUNDLE: &str = "msixbundle";
 pub const APPX_BUNDLE: &str = "appxbundle";
+pub const APP_INSTALLER: &str = "appinstaller";
 pub const ZIP: &str = "zip";
+
+/// The extensions of files that can be analyzed locally.
+pub const ANALYZABLE: [&str; 7] = [EXE, MSI, MSIX, APPX, MSIX_BUNDLE, APPX_BUNDLE, ZIP];
@@ -0,0 +1,170 @@
+use std::{fs, io, process, thread};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use winget_types::Sha256String;
+
+/// The resolutions that are allowed by the `IconResolution` manifest field, other than `custom`.
+const RESOLUTIONS: [u32; 14] = [16, 20, 24, 30, 32, 36, 40, 48, 60, 64, 72, 80, 96, 256];
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconFileType {
+    Png,
+    Ico,
+}
+
+impl IconFileType {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Ico => "ico",
+        }
+    }
+}
+
+/// An icon extracted from an installer, with the values for the `Icons` field of a default locale
+/// manifest once the file is hosted.
+#[derive(Clone, Debug, Serialize)]
+pub struct Icon {
+    #[serde(rename = "IconFileType")]
+    pub file_type: IconFileType,
+    #[serde(rename = "IconResolution")]
+    pub resolution: String,
+    #[serde(rename = "IconSha256")]
+    pub sha_256: Sha256String,
+    #[serde(skip)]
+    pub width: u32,
+    #[serde(skip)]
+    pub height: u32,
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+impl Icon {
+    pub fn new(file_type: IconFileType, width: u32, height: u32, data: Vec<u8>) -> Self {
+        Self {
+            file_type,
+            resolution: if width == height && RESOLUTIONS.contains(&width) {
+                format!("{width}x{height}")
+            } else {
+                String::from("custom")
+            },
+            sha_256: Sha256String::from_digest(&Sha256::digest(&data)),
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Creates an icon from PNG data, reading its dimensions from the IHDR chunk.
+    pub fn from_png(data: Vec<u8>) -> Option<Self> {
+        let (width, height) = png_dimensions(&data)?;
+        Some(Self::new(IconFileType::Png, width, height, data))
+    }
+
+    /// Writes the icon to a directory as `{sha_256}.{extension}`, returning the path it was written
+    /// to.
+    ///
+    /// Naming the file after its hash means identical icons share a file while installers with the
+    /// same file name in different directories can't overwrite each other's icons. An existing file
+    /// with different contents is never overwritten.
+    pub fn write_to(&self, directory: &Utf8Path) -> io::Result<Utf8PathBuf> {
+        fs::create_dir_all(directory)?;
+        let file_name = format!("{}.{}", self.sha_256, self.file_type.extension());
+        let path = directory.join(&file_name);
+
+        match fs::read(&path) {
+            Ok(existing) if existing == self.data => return Ok(path),
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{path} already exists with different contents"),
+                ));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        // Write to a file unique to this thread first so that a concurrent writer of the same icon
+        // never sees a partially written file
+        let temp_path = directory.join(format!(
+            ".{file_name}.{}.{:?}.tmp",
+            process::id(),
+            thread::current().id()
+        ));
+        fs::write(&temp_path, &self.data)?;
+        fs::rename(&temp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })?;
+        Ok(path)
+    }
+}
+
+#[inline]
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(PNG_SIGNATURE)
+}
+
+/// Returns the width and height of a PNG image.
+pub fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    const IHDR: &[u8; 4] = b"IHDR";
+
+    if !is_png(data) || data.get(12..16)? != IHDR {
+        return None;
+    }
+
+    let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io};
+
+    use camino::Utf8Path;
+    use rstest::rstest;
+
+    use super::{Icon, IconFileType, png_dimensions};
+
+    const PNG_HEADER: &[u8] =
+        b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x01\x00\x00\x00\x01\x00\x08\x06\x00\x00\x00";
+
+    #[test]
+    fn png() {
+        assert_eq!(png_dimensions(PNG_HEADER), Some((256, 256)));
+        assert_eq!(png_dimensions(b"not a png"), None);
+    }
+
+    #[rstest]
+    #[case(256, 256, "256x256")]
+    #[case(48, 48, "48x48")]
+    #[case(44, 44, "custom")]
+    #[case(64, 32, "custom")]
+    fn resolution(#[case] width: u32, #[case] height: u32, #[case] resolution: &str) {
+        assert_eq!(
+            Icon::new(IconFileType::Ico, width, height, Vec::new()).resolution,
+            resolution
+        );
+    }
+
+    #[test]
+    fn write_to_never_overwrites_a_different_icon() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = Utf8Path::from_path(directory.path()).unwrap();
+        let icon = Icon::new(IconFileType::Ico, 32, 32, vec![1, 2, 3]);
+
+        let path = icon.write_to(directory).unwrap();
+        assert_eq!(path, icon.write_to(directory).unwrap());
+
+        fs::write(&path, [4, 5, 6]).unwrap();
+        assert_eq!(
+            icon.write_to(directory).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+    }
+}
@@ -45,7 +45,7 @@ pub enum BurnError {
     Io(#[from] io::Error),
 }
 
-const WIXBURN: [u8; 8] = *b".wixburn";
+pub const WIXBURN: [u8; 8] = *b".wixburn";
 
 pub struct Burn {
     architecture: Architecture,
@@ -0,0 +1,73 @@
+use std::io::{self, Read, Seek};
+
+use thiserror::Error;
+use winget_types::installer::{
+    Architecture, InstallModes, Installer, InstallerSwitches, InstallerType,
+};
+
+use crate::{
+    analysis::{Installers, installers::pe::PE},
+    traits::FromMachine,
+};
+
+/// The signature of the first data block in the overlay of a Clickteam Install Creator setup.
+const SIGNATURE: [u8; 6] = *b"wwgT)H";
+
+#[derive(Error, Debug)]
+pub enum ClickteamError {
+    #[error("File is not a Clickteam Install Creator installer")]
+    NotClickteamFile,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub struct Clickteam {
+    architecture: Architecture,
+}
+
+impl Clickteam {
+    pub fn new<R: Read + Seek>(reader: R, pe: &PE) -> Result<Self, ClickteamError> {
+        if !Self::has_signature(&pe.overlay_prefix(reader, SIGNATURE.len())?) {
+            return Err(ClickteamError::NotClickteamFile);
+        }
+
+        Ok(Self {
+            architecture: Architecture::from_machine(pe.machine()),
+        })
+    }
+
+    fn has_signature(overlay: &[u8]) -> bool {
+        overlay.starts_with(&SIGNATURE)
+    }
+}
+
+impl Installers for Clickteam {
+    fn installers(&self) -> Vec<Installer> {
+        vec![Installer {
+            architecture: self.architecture,
+            r#type: Some(InstallerType::Exe),
+            install_modes: InstallModes::all(),
+            switches: InstallerSwitches::builder()
+                .silent("/S".parse().unwrap())
+                .silent_with_progress("/S".parse().unwrap())
+                .build(),
+            ..Installer::default()
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::Clickteam;
+
+    #[rstest]
+    #[case(b"wwgT)H\x01\0", true)]
+    #[case(b"wwgT)H", true)]
+    #[case(b"wwgT", false)]
+    #[case(b"\xE0\xE1\xE2\xE3\xE4\xE5\xE6\xE7", false)]
+    fn signature(#[case] overlay: &[u8], #[case] expected: bool) {
+        assert_eq!(Clickteam::has_signature(overlay), expected);
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+use winget_types::installer::InstallerType;
+
+/// A signal that suggests whether an exe that matched no known installer framework is an
+/// installer or a portable app.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    /// `OriginalFilename` or `FileDescription` contains "installer" or "setup".
+    InstallerKeyword,
+    /// The `InternalName` is that of a known self-extracting stub.
+    SfxStub,
+    /// The exe imports `MsiInstallProduct` from msi.dll.
+    MsiImports,
+    /// The exe carries an overlay and imports both `CreateProcess` and `ShellExecute`, suggesting
+    /// that it extracts and runs its payload.
+    ExtractsAndRuns,
+    /// At least half of the file is overlay data appended after the PE sections.
+    LargeOverlay,
+    /// The embedded manifest requests administrator privileges.
+    RequiresAdministrator,
+    /// The exe uses the console subsystem.
+    ConsoleSubsystem,
+    /// The exe has no overlay data.
+    NoOverlay,
+}
+
+impl Signal {
+    /// Returns the weight of the signal. Positive weights suggest an installer and negative weights
+    /// suggest a portable app.
+    pub const fn weight(self) -> i8 {
+        match self {
+            Self::SfxStub => 4,
+            Self::InstallerKeyword | Self::MsiImports => 3,
+            Self::ExtractsAndRuns | Self::LargeOverlay | Self::RequiresAdministrator => 2,
+            Self::NoOverlay => -1,
+            Self::ConsoleSubsystem => -2,
+        }
+    }
+}
+
+/// The result of scoring the [`Signal`]s found in a generic exe.
+#[derive(Clone, Debug, Serialize)]
+pub struct Classification {
+    pub installer_type: InstallerType,
+    /// How confident the classification is, from 0 to 1.
+    pub confidence: f32,
+    pub signals: Vec<Signal>,
+}
+
+impl Classification {
+    /// The absolute score at which a classification is fully confident.
+    const CONFIDENT_SCORE: f32 = 4.0;
+
+    /// The confidence at or above which a classification can be trusted without asking the user.
+    pub const CONFIDENCE_THRESHOLD: f32 = 0.75;
+
+    pub fn new(signals: Vec<Signal>) -> Self {
+        let score = signals
+            .iter()
+            .map(|signal| i32::from(signal.weight()))
+            .sum::<i32>();
+
+        Self {
+            installer_type: if score > 0 {
+                InstallerType::Exe
+            } else {
+                InstallerType::Portable
+            },
+            confidence: (score.unsigned_abs() as f32 / Self::CONFIDENT_SCORE).min(1.0),
+            signals,
+        }
+    }
+
+    /// Returns `true` if the classification is confident enough to skip asking the user.
+    #[inline]
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= Self::CONFIDENCE_THRESHOLD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winget_types::installer::InstallerType;
+
+    use super::{Classification, Signal};
+
+    #[rstest]
+    #[case(&[], InstallerType::Portable, 0.0)]
+    #[case(&[Signal::SfxStub], InstallerType::Exe, 1.0)]
+    #[case(&[Signal::LargeOverlay], InstallerType::Exe, 0.5)]
+    #[case(&[Signal::ConsoleSubsystem, Signal::NoOverlay], InstallerType::Portable, 0.75)]
+    #[case(&[Signal::InstallerKeyword, Signal::ConsoleSubsystem], InstallerType::Exe, 0.25)]
+    #[case(
+        &[Signal::MsiImports, Signal::RequiresAdministrator, Signal::LargeOverlay],
+        InstallerType::Exe,
+        1.0
+    )]
+    fn classification(
+        #[case] signals: &[Signal],
+        #[case] installer_type: InstallerType,
+        #[case] confidence: f32,
+    ) {
+        let classification = Classification::new(signals.to_vec());
+
+        assert_eq!(classification.installer_type, installer_type);
+        assert!((classification.confidence - confidence).abs() < f32::EPSILON);
+    }
+}
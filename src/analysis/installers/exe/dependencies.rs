@@ -0,0 +1,291 @@
+use std::collections::BTreeSet;
+
+use winget_types::{
+    PackageIdentifier,
+    installer::{Architecture, MinimumOSVersion, PackageDependencies},
+};
+
+use super::runtime_config::RuntimeConfig;
+use crate::analysis::installers::pe::{
+    Import,
+    clr::{ClrMetadata, FrameworkIdentifier},
+};
+
+/// Visual C++ runtime DLLs and the year of the redistributable that provides them.
+const VC_RUNTIMES: [(&str, &str); 9] = [
+    ("vcruntime140.dll", "2015+"),
+    ("vcruntime140_1.dll", "2015+"),
+    ("msvcp140.dll", "2015+"),
+    ("msvcp120.dll", "2013"),
+    ("msvcr120.dll", "2013"),
+    ("msvcp110.dll", "2012"),
+    ("msvcr110.dll", "2012"),
+    ("msvcp100.dll", "2010"),
+    ("msvcr100.dll", "2010"),
+];
+
+/// Functions that were introduced in a specific version of Windows 10 or 11.
+const VERSIONED_FUNCTIONS: [(&str, MinimumOSVersion); 6] = [
+    (
+        "CreatePseudoConsole",
+        MinimumOSVersion::new(10, 0, 17763, 0),
+    ),
+    ("GetDpiForWindow", MinimumOSVersion::new(10, 0, 14393, 0)),
+    (
+        "GetSystemMetricsForDpi",
+        MinimumOSVersion::new(10, 0, 14393, 0),
+    ),
+    (
+        "SetThreadDescription",
+        MinimumOSVersion::new(10, 0, 14393, 0),
+    ),
+    (
+        "SetThreadDpiAwarenessContext",
+        MinimumOSVersion::new(10, 0, 14393, 0),
+    ),
+    ("VirtualAlloc2", MinimumOSVersion::new(10, 0, 17134, 0)),
+];
+
+/// API sets that were introduced in a specific version of Windows 10 or 11.
+const VERSIONED_API_SETS: [(&str, MinimumOSVersion); 1] = [(
+    "api-ms-win-core-memory-l1-1-6",
+    MinimumOSVersion::new(10, 0, 17134, 0),
+)];
+
+/// The shared frameworks in a `runtimeconfig.json` and the runtime packages that provide them.
+const SHARED_FRAMEWORKS: [(&str, &str); 3] = [
+    ("Microsoft.NETCore.App", "Runtime"),
+    ("Microsoft.WindowsDesktop.App", "DesktopRuntime"),
+    ("Microsoft.AspNetCore.App", "AspNetCore"),
+];
+
+/// Infers the packages that a portable exe depends on from its imports and .NET metadata.
+///
+/// The runtime config of a .NET apphost takes precedence over the CLR metadata, as an apphost is
+/// a native exe.
+pub fn package_dependencies(
+    imports: &[Import],
+    clr_metadata: Option<&ClrMetadata>,
+    runtime_config: Option<&RuntimeConfig>,
+    architecture: Architecture,
+    is_gui: bool,
+) -> BTreeSet<PackageDependencies> {
+    let redist_architecture = match architecture {
+        Architecture::X64 => "x64",
+        Architecture::Arm64 => "arm64",
+        _ => "x86",
+    };
+
+    let vc_redists = imports.iter().filter_map(|import| {
+        VC_RUNTIMES
+            .iter()
+            .find(|(dll, _)| import.is_dll(dll))
+            .map(|(_, year)| format!("Microsoft.VCRedist.{year}.{redist_architecture}"))
+    });
+
+    // .NET Framework 4.8 ships with every supported version of Windows, so only .NET (Core)
+    // runtimes need to be installed
+    let dotnet_runtimes = match runtime_config {
+        Some(runtime_config) => dotnet_runtime_dependencies(runtime_config),
+        None => clr_metadata
+            .and_then(|metadata| metadata.target_framework.as_ref())
+            .filter(|framework| framework.identifier == FrameworkIdentifier::NetCoreApp)
+            .and_then(|framework| {
+                let runtime = if is_gui { "DesktopRuntime" } else { "Runtime" };
+                dotnet_runtime(runtime, framework.major, framework.minor)
+            })
+            .into_iter()
+            .collect(),
+    };
+
+    vc_redists
+        .filter_map(|identifier| identifier.parse::<PackageIdentifier>().ok())
+        .map(|package_identifier| PackageDependencies {
+            package_identifier,
+            minimum_version: None,
+        })
+        .chain(dotnet_runtimes)
+        .collect()
+}
+
+/// Returns the .NET runtime packages that provide the shared frameworks in a runtime config.
+pub fn dotnet_runtime_dependencies(
+    runtime_config: &RuntimeConfig,
+) -> BTreeSet<PackageDependencies> {
+    let frameworks = runtime_config
+        .frameworks()
+        .filter_map(|framework| {
+            let (_, runtime) = SHARED_FRAMEWORKS
+                .iter()
+                .find(|(name, _)| framework.name.eq_ignore_ascii_case(name))?;
+            let mut version = framework.version.split('.');
+            let major = version.next()?.parse::<u16>().ok()?;
+            let minor = version.next().and_then(|minor| minor.parse::<u16>().ok());
+            Some((*runtime, major, minor.unwrap_or_default()))
+        })
+        .collect::<Vec<_>>();
+
+    // The desktop runtime includes the .NET runtime
+    let has_desktop_runtime = frameworks
+        .iter()
+        .any(|&(runtime, _, _)| runtime == "DesktopRuntime");
+
+    frameworks
+        .into_iter()
+        .filter(|&(runtime, _, _)| !(has_desktop_runtime && runtime == "Runtime"))
+        .filter_map(|(runtime, major, minor)| dotnet_runtime(runtime, major, minor))
+        .filter_map(|identifier| identifier.parse::<PackageIdentifier>().ok())
+        .map(|package_identifier| PackageDependencies {
+            package_identifier,
+            minimum_version: None,
+        })
+        .collect()
+}
+
+/// Returns the identifier of a .NET runtime package, which only exist for .NET Core 3.0 onwards.
+fn dotnet_runtime(runtime: &str, major: u16, minor: u16) -> Option<String> {
+    match major {
+        5.. => Some(format!("Microsoft.DotNet.{runtime}.{major}")),
+        3 => Some(format!("Microsoft.DotNet.{runtime}.{major}_{minor}")),
+        _ => None,
+    }
+}
+
+/// Infers the minimum version of Windows from the API sets and functions that an exe imports.
+pub fn minimum_os_version(imports: &[Import]) -> Option<MinimumOSVersion> {
+    let from_api_sets = imports.iter().filter_map(|import| {
+        VERSIONED_API_SETS
+            .iter()
+            .find(|(api_set, _)| import.dll.to_ascii_lowercase().starts_with(api_set))
+            .map(|&(_, version)| version)
+    });
+
+    let from_functions = VERSIONED_FUNCTIONS
+        .iter()
+        .filter(|(function, _)| imports.iter().any(|import| import.imports(function)))
+        .map(|&(_, version)| version);
+
+    from_api_sets.chain(from_functions).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use winget_types::installer::{Architecture, MinimumOSVersion};
+
+    use super::{
+        super::runtime_config::RuntimeConfig, dotnet_runtime_dependencies, minimum_os_version,
+        package_dependencies,
+    };
+    use crate::analysis::installers::pe::{
+        Import,
+        clr::{ClrMetadata, FrameworkIdentifier, TargetFramework},
+    };
+
+    fn import(dll: &str, functions: &[&str]) -> Import {
+        Import {
+            dll: dll.to_owned(),
+            functions: functions
+                .iter()
+                .map(|&function| function.to_owned())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn vc_redist_and_dotnet() {
+        let imports = [
+            import("KERNEL32.dll", &["CreateFileW"]),
+            import("VCRUNTIME140.dll", &["memcpy"]),
+            import("MSVCP140.dll", &[]),
+        ];
+        let clr_metadata = ClrMetadata {
+            runtime_version: "v4.0.30319".to_owned(),
+            target_framework: Some(TargetFramework {
+                identifier: FrameworkIdentifier::NetCoreApp,
+                major: 8,
+                minor: 0,
+            }),
+        };
+
+        let dependencies =
+            package_dependencies(&imports, Some(&clr_metadata), None, Architecture::X64, true);
+
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| dependency.package_identifier.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "Microsoft.DotNet.DesktopRuntime.8",
+                "Microsoft.VCRedist.2015+.x64"
+            ]
+        );
+    }
+
+    #[test]
+    fn dotnet_from_apphost_runtime_config() {
+        let runtime_config = RuntimeConfig::from_slice(
+            br#"{
+                "runtimeOptions": {
+                    "tfm": "net8.0",
+                    "frameworks": [
+                        { "name": "Microsoft.NETCore.App", "version": "8.0.0" },
+                        { "name": "Microsoft.WindowsDesktop.App", "version": "8.0.0" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        // An apphost is a native exe without a CLR header
+        let dependencies = package_dependencies(
+            &[import("KERNEL32.dll", &["CreateFileW"])],
+            None,
+            Some(&runtime_config),
+            Architecture::X64,
+            false,
+        );
+
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|dependency| dependency.package_identifier.as_str())
+                .collect::<Vec<_>>(),
+            ["Microsoft.DotNet.DesktopRuntime.8"]
+        );
+    }
+
+    #[test]
+    fn self_contained_runtime_config() {
+        let runtime_config = RuntimeConfig::from_slice(
+            br#"{
+                "runtimeOptions": {
+                    "tfm": "netcoreapp3.1",
+                    "includedFrameworks": [
+                        { "name": "Microsoft.NETCore.App", "version": "3.1.32" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(dotnet_runtime_dependencies(&runtime_config).is_empty());
+    }
+
+    #[test]
+    fn minimum_os_version_from_imports() {
+        let imports = [
+            import(
+                "KERNEL32.dll",
+                &["SetThreadDescription", "CreatePseudoConsole"],
+            ),
+            import("USER32.dll", &["GetDpiForWindow"]),
+        ];
+
+        assert_eq!(
+            minimum_os_version(&imports),
+            Some(MinimumOSVersion::new(10, 0, 17763, 0))
+        );
+        assert_eq!(minimum_os_version(&[import("KERNEL32.dll", &[])]), None);
+    }
+}
@@ -0,0 +1,519 @@
+mod classification;
+mod dependencies;
+mod report;
+mod runtime_config;
+
+use std::{
+    collections::BTreeSet,
+    io,
+    io::{Read, Seek, SeekFrom},
+};
+
+pub use classification::Classification;
+use classification::Signal;
+use color_eyre::Result;
+pub use dependencies::dotnet_runtime_dependencies;
+use indexmap::IndexMap;
+use inno::{Inno, error::InnoError};
+pub use report::DetectionReport;
+use report::{Detection, Detector, Machine, Subsystem, Verdict, format_magic};
+pub use runtime_config::RuntimeConfig;
+use tracing::debug;
+use winget_types::installer::{Dependencies, Installer, InstallerSwitches, InstallerType};
+
+use super::{
+    super::Installers, AdvancedInstaller, Burn, Clickteam, InstallAware, InstallShield, Nsis, Qt,
+    SetupFactory, SevenZipSfx, SmartInstallMaker, Squirrel, Wise,
+};
+use crate::{
+    analysis::{
+        AnalyzeOptions,
+        icon::Icon,
+        installers::{
+            advanced::AdvancedInstallerError,
+            burn::{BurnError, WIXBURN},
+            clickteam::ClickteamError,
+            installaware::InstallAwareError,
+            installshield::InstallShieldError,
+            nsis::NsisError,
+            pe::{PE, VSVersionInfo},
+            qt::QtError,
+            setup_factory::SetupFactoryError,
+            sevenzip_sfx::SevenZipSfxError,
+            smart_install_maker::SmartInstallMakerError,
+            squirrel::SquirrelError,
+            utils::ProductInfo,
+            wise::{WISE, WiseError},
+        },
+    },
+    traits::IntoWingetArchitecture,
+};
+
+const IMAGE_SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+const IMAGE_SUBSYSTEM_WINDOWS_CUI: u16 = 3;
+const ORIGINAL_FILENAME: &str = "OriginalFilename";
+const FILE_DESCRIPTION: &str = "FileDescription";
+const BASIC_INSTALLER_KEYWORDS: [&str; 4] = ["installer", "setup", "7zs.sfx", "7zsd.sfx"];
+
+pub struct Exe {
+    r#type: ExeType,
+    pub legal_copyright: Option<String>,
+    pub product_name: Option<String>,
+    pub company_name: Option<String>,
+    /// How an exe that matched no known installer framework was classified.
+    pub classification: Option<Classification>,
+    /// How the installer type was determined. This is only populated when explaining.
+    pub report: Option<DetectionReport>,
+    /// The largest image from the exe's icon resources, if icons were extracted.
+    pub icon: Option<Icon>,
+    product_info: ProductInfo,
+}
+
+pub enum ExeType {
+    AdvancedInstaller(AdvancedInstaller),
+    Burn(Box<Burn>),
+    Inno(Box<Inno>),
+    InstallShield(InstallShield),
+    Nsis(Nsis),
+    Qt(Qt),
+    SevenZipSfx(SevenZipSfx),
+    Squirrel(Squirrel),
+    InstallAware(InstallAware),
+    SetupFactory(SetupFactory),
+    Wise(Wise),
+    SmartInstallMaker(SmartInstallMaker),
+    Clickteam(Clickteam),
+    Generic(Box<Installer>),
+}
+
+impl Exe {
+    /// Analyzes the exe, also gathering what the options ask for.
+    ///
+    /// When explaining, each detector's verdict and the evidence it found are recorded and every
+    /// detector is run. A detector that fails to parse the file stops the analysis in both modes,
+    /// with the report so far attached to the error.
+    pub fn new<R: Read + Seek>(mut reader: R, options: AnalyzeOptions) -> Result<Self> {
+        let pe = PE::read_from(&mut reader)?;
+
+        let vs_version_info_bytes = pe.vs_version_info(&mut reader).ok();
+        let vs_version_info = vs_version_info_bytes
+            .as_deref()
+            .and_then(|version_info_bytes| VSVersionInfo::read_from(version_info_bytes).ok());
+        let mut string_table = vs_version_info.as_ref().map(VSVersionInfo::string_table);
+
+        // Rebuilding and hashing the icon is only worth it when it's going to be written out
+        let icon = options
+            .extract_icon
+            .then(|| pe.largest_icon(&mut reader).ok().flatten())
+            .flatten();
+
+        let mut report = options
+            .explain
+            .then(|| DetectionReport::new(&pe, &mut reader, string_table.as_ref()));
+
+        let product_info = ProductInfo::new(string_table.as_ref(), pe.manifest(&mut reader).ok());
+
+        let legal_copyright = string_table
+            .as_mut()
+            .and_then(|table| table.swap_remove("LegalCopyright"))
+            .map(str::to_owned);
+        let product_name = string_table
+            .as_mut()
+            .and_then(|table| table.swap_remove("ProductName"))
+            .map(str::to_owned);
+        let company_name = string_table
+            .as_mut()
+            .and_then(|table| table.swap_remove("CompanyName"))
+            .map(str::to_owned);
+
+        let mut r#type = None;
+        for detector in Detector::ALL {
+            if r#type.is_some() && report.is_none() {
+                break;
+            }
+
+            let result = detector.detect(&mut reader, &pe, &product_info);
+
+            let Some(report) = report.as_mut() else {
+                r#type = result?;
+                continue;
+            };
+
+            let (verdict, error) = match result {
+                Ok(Some(detected)) => {
+                    if r#type.is_none() {
+                        report.detected = detector.as_str();
+                        r#type = Some(detected);
+                    }
+                    (Verdict::Detected, None)
+                }
+                Ok(None) => (Verdict::NotDetected, None),
+                Err(error) => (Verdict::Failed(format!("{error:#}")), Some(error)),
+            };
+            report.detectors.push(Detection {
+                detector,
+                looks_for: detector.looks_for(),
+                evidence: detector.evidence(&pe),
+                verdict,
+            });
+
+            if let Some(error) = error {
+                return Err(error.wrap_err(report.to_text()));
+            }
+        }
+
+        if let Some(r#type) = r#type {
+            return Ok(Self {
+                r#type,
+                legal_copyright,
+                product_name,
+                company_name,
+                classification: None,
+                report,
+                icon,
+                product_info,
+            });
+        }
+
+        let internal_name = string_table
+            .as_ref()
+            .and_then(|table| table.get("InternalName").copied())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        let silent = match internal_name.as_str() {
+            // Setup.exe is used by several installer types, so we can't determine its args
+            "sfxcab.exe" => "/quiet",
+            "7zs.sfx" | "7z.sfx" | "7zsd.sfx" => "/s",
+            "setup launcher" => "/s",
+            "wextract" => "/Q",
+            _ => "",
+        };
+
+        if pe.find_section(*b"UPX0\0\0\0\0").is_some() {
+            debug!("Detected UPX packed exe");
+        }
+
+        let mut signals = Vec::new();
+
+        if !silent.is_empty() {
+            signals.push(Signal::SfxStub);
+        }
+
+        if string_table.is_some_and(|mut table| {
+            let original_filename = table.swap_remove(ORIGINAL_FILENAME);
+            let file_description = table.swap_remove(FILE_DESCRIPTION);
+
+            BASIC_INSTALLER_KEYWORDS.iter().any(|keyword| {
+                original_filename.is_some_and(|filename| filename.contains(keyword))
+                    || file_description.is_some_and(|description| description.contains(keyword))
+            })
+        }) {
+            signals.push(Signal::InstallerKeyword);
+        }
+
+        if pe.optional_header.windows_fields.subsystem() == IMAGE_SUBSYSTEM_WINDOWS_CUI {
+            signals.push(Signal::ConsoleSubsystem);
+        }
+
+        let overlay_size = overlay_size(&pe, &mut reader)?;
+        let file_size = reader.seek(SeekFrom::End(0))?;
+        if overlay_size == 0 {
+            signals.push(Signal::NoOverlay);
+        } else if overlay_size >= file_size / 2 {
+            signals.push(Signal::LargeOverlay);
+        }
+
+        let imports = pe.imports(&mut reader).unwrap_or_default();
+        if imports
+            .iter()
+            .any(|import| import.is_dll("msi.dll") && import.imports("MsiInstallProduct"))
+        {
+            signals.push(Signal::MsiImports);
+        }
+        if overlay_size != 0
+            && imports.iter().any(|import| import.imports("CreateProcess"))
+            && imports.iter().any(|import| import.imports("ShellExecute"))
+        {
+            signals.push(Signal::ExtractsAndRuns);
+        }
+
+        if product_info
+            .manifest()
+            .is_some_and(|manifest| manifest.contains("requireAdministrator"))
+        {
+            signals.push(Signal::RequiresAdministrator);
+        }
+
+        let classification = Classification::new(signals);
+        debug!(?classification);
+
+        if let Some(report) = &mut report {
+            report.classification = Some(classification.clone());
+        }
+
+        // Installers set up their own dependencies, so these are only inferred for portable apps
+        let (package_dependencies, minimum_os_version) = if classification.installer_type
+            == InstallerType::Portable
+        {
+            let clr_metadata = pe.clr_metadata(&mut reader).ok().flatten();
+            debug!(?clr_metadata);
+            let runtime_config = pe
+                .overlay_offset()
+                .and_then(|image_size| {
+                    RuntimeConfig::read_from_bundle(&mut reader, image_size).ok()
+                })
+                .flatten();
+            debug!(?runtime_config);
+            (
+                dependencies::package_dependencies(
+                    &imports,
+                    clr_metadata.as_ref(),
+                    runtime_config.as_ref(),
+                    pe.winget_architecture(),
+                    pe.optional_header.windows_fields.subsystem() == IMAGE_SUBSYSTEM_WINDOWS_GUI,
+                ),
+                dependencies::minimum_os_version(&imports),
+            )
+        } else {
+            (BTreeSet::new(), None)
+        };
+
+        Ok(Self {
+            r#type: ExeType::Generic(Box::new(Installer {
+                architecture: pe.winget_architecture(),
+                r#type: Some(classification.installer_type),
+                minimum_os_version,
+                dependencies: Dependencies {
+                    package_dependencies,
+                    ..Dependencies::default()
+                },
+                switches: if !silent.is_empty() {
+                    InstallerSwitches::builder()
+                        .silent(silent.parse().unwrap())
+                        .silent_with_progress(silent.parse().unwrap())
+                        .build()
+                } else {
+                    InstallerSwitches::default()
+                },
+                ..Installer::default()
+            })),
+            legal_copyright,
+            product_name,
+            company_name,
+            classification: Some(classification),
+            report,
+            icon,
+            product_info,
+        })
+    }
+
+    /// Fills the ARP entry of installers from frameworks that don't embed a readable setup script
+    /// with the product info of the exe, which their stubs take from the project settings.
+    fn with_product_info(&self, mut installers: Vec<Installer>) -> Vec<Installer> {
+        for installer in &mut installers {
+            installer.apps_and_features_entries = self.product_info.apps_and_features_entries();
+        }
+        installers
+    }
+}
+
+impl Detector {
+    fn detect<R: Read + Seek>(
+        self,
+        reader: &mut R,
+        pe: &PE,
+        product_info: &ProductInfo,
+    ) -> Result<Option<ExeType>> {
+        match self {
+            Self::AdvancedInstaller => match AdvancedInstaller::new(reader) {
+                Ok(advanced) => Ok(Some(ExeType::AdvancedInstaller(advanced))),
+                Err(AdvancedInstallerError::NotAdvancedInstallerFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Burn => match Burn::new(reader, pe) {
+                Ok(burn) => Ok(Some(ExeType::Burn(Box::new(burn)))),
+                Err(BurnError::NotBurnFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Inno => match Inno::new(reader) {
+                Ok(inno) => Ok(Some(ExeType::Inno(Box::new(inno)))),
+                Err(InnoError::NotInnoFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::InstallShield => match InstallShield::new(reader, pe) {
+                Ok(installshield) => Ok(Some(ExeType::InstallShield(installshield))),
+                Err(InstallShieldError::NotInstallShieldFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Nsis => match Nsis::new(reader, pe) {
+                Ok(nsis) => Ok(Some(ExeType::Nsis(nsis))),
+                Err(NsisError::NotNsisFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Qt => match Qt::new(reader, pe) {
+                Ok(qt) => Ok(Some(ExeType::Qt(qt))),
+                Err(QtError::NotQtFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::SevenZipSfx => match SevenZipSfx::new(reader, pe) {
+                Ok(sfx) => Ok(Some(ExeType::SevenZipSfx(sfx))),
+                Err(SevenZipSfxError::NotSevenZipSfx | SevenZipSfxError::NoRunProgram) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Squirrel => match Squirrel::new(reader, pe) {
+                Ok(squirrel) => Ok(Some(ExeType::Squirrel(squirrel))),
+                Err(SquirrelError::NotSquirrelFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::InstallAware => match InstallAware::new(pe, product_info) {
+                Ok(installaware) => Ok(Some(ExeType::InstallAware(installaware))),
+                Err(InstallAwareError::NotInstallAwareFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::SetupFactory => match SetupFactory::new(reader, pe) {
+                Ok(setup_factory) => Ok(Some(ExeType::SetupFactory(setup_factory))),
+                Err(SetupFactoryError::NotSetupFactoryFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Wise => match Wise::new(pe) {
+                Ok(wise) => Ok(Some(ExeType::Wise(wise))),
+                Err(WiseError::NotWiseFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::SmartInstallMaker => match SmartInstallMaker::new(reader, pe) {
+                Ok(smart_install_maker) => {
+                    Ok(Some(ExeType::SmartInstallMaker(smart_install_maker)))
+                }
+                Err(SmartInstallMakerError::NotSmartInstallMakerFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Self::Clickteam => match Clickteam::new(reader, pe) {
+                Ok(clickteam) => Ok(Some(ExeType::Clickteam(clickteam))),
+                Err(ClickteamError::NotClickteamFile) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+        }
+    }
+
+    /// Returns the evidence in the PE relevant to this detector.
+    fn evidence(self, pe: &PE) -> Option<String> {
+        match self {
+            Self::AdvancedInstaller | Self::Inno | Self::InstallAware => None,
+            Self::Burn => pe.find_section(WIXBURN).map(|section| {
+                format!("a .wixburn section at {:#x}", section.pointer_to_raw_data())
+            }),
+            Self::Wise => pe
+                .find_section(WISE)
+                .map(|section| format!("a .WISE section at {:#x}", section.pointer_to_raw_data())),
+            Self::InstallShield
+            | Self::Nsis
+            | Self::Qt
+            | Self::SevenZipSfx
+            | Self::Squirrel
+            | Self::SetupFactory
+            | Self::SmartInstallMaker
+            | Self::Clickteam => pe
+                .overlay_offset()
+                .map(|offset| format!("an overlay at {offset:#x}")),
+        }
+    }
+}
+
+impl DetectionReport {
+    /// The number of bytes at the start of the overlay to include in the report.
+    const MAGIC_LEN: usize = 16;
+
+    fn new<R: Read + Seek>(
+        pe: &PE,
+        mut reader: R,
+        string_table: Option<&IndexMap<&str, &str>>,
+    ) -> Self {
+        let overlay_offset = pe.overlay_offset();
+        let overlay_magic = overlay_offset.and_then(|offset| {
+            reader.seek(SeekFrom::Start(offset)).ok()?;
+            let mut magic = Vec::with_capacity(Self::MAGIC_LEN);
+            reader
+                .by_ref()
+                .take(Self::MAGIC_LEN as u64)
+                .read_to_end(&mut magic)
+                .ok()?;
+            (!magic.is_empty()).then(|| format_magic(&magic))
+        });
+
+        Self {
+            detected: "Generic",
+            machine: Machine {
+                value: pe.machine(),
+                architecture: pe.winget_architecture(),
+            },
+            subsystem: Subsystem::new(pe.optional_header.windows_fields.subsystem()),
+            sections: pe
+                .section_table
+                .sections()
+                .iter()
+                .map(|section| section.real_name().to_owned())
+                .collect(),
+            overlay_offset,
+            overlay_magic,
+            version_info: string_table
+                .into_iter()
+                .flatten()
+                .map(|(&key, &value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            manifest: pe.manifest(&mut reader).ok(),
+            detectors: Vec::with_capacity(Detector::ALL.len()),
+            classification: None,
+        }
+    }
+}
+
+/// Returns the size of the data appended after the PE sections, excluding the certificate table.
+fn overlay_size<R: Read + Seek>(pe: &PE, mut reader: R) -> io::Result<u64> {
+    let Some(overlay_start) = pe.overlay_offset() else {
+        return Ok(0);
+    };
+
+    let file_end = reader.seek(SeekFrom::End(0))?;
+    let overlay_end = pe
+        .certificate_table()
+        .map(|directory| u64::from(directory.virtual_address()))
+        .filter(|&offset| offset > overlay_start)
+        .unwrap_or(file_end);
+
+    Ok(overlay_end.saturating_sub(overlay_start))
+}
+
+impl Exe {
+    /// Returns the URL of the product's homepage if the installer framework records one.
+    pub fn product_url(&self) -> Option<&str> {
+        match &self.r#type {
+            ExeType::Qt(qt) => qt.product_url(),
+            _ => None,
+        }
+    }
+}
+
+impl Installers for Exe {
+    fn installers(&self) -> Vec<Installer> {
+        match &self.r#type {
+            ExeType::AdvancedInstaller(advanced) => advanced.installers(),
+            ExeType::Burn(burn) => burn.installers(),
+            ExeType::Inno(inno) => inno.installers(),
+            ExeType::InstallShield(installshield) => installshield.installers(),
+            ExeType::Nsis(nsis) => nsis.installers(),
+            ExeType::Qt(qt) => qt.installers(),
+            ExeType::SevenZipSfx(sfx) => sfx.installers(),
+            ExeType::Squirrel(squirrel) => squirrel.installers(),
+            ExeType::InstallAware(installaware) => installaware.installers(),
+            ExeType::SetupFactory(setup_factory) => {
+                self.with_product_info(setup_factory.installers())
+            }
+            ExeType::Wise(wise) => self.with_product_info(wise.installers()),
+            ExeType::SmartInstallMaker(smart_install_maker) => {
+                self.with_product_info(smart_install_maker.installers())
+            }
+            ExeType::Clickteam(clickteam) => self.with_product_info(clickteam.installers()),
+            ExeType::Generic(installer) => vec![*installer.clone()],
+        }
+    }
+}
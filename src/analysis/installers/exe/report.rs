@@ -0,0 +1,275 @@
+use std::{fmt, fmt::Write as _};
+
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use winget_types::installer::Architecture;
+
+use super::Classification;
+
+/// A trace of how the installer type of an exe was determined, used by `komac analyze --explain`.
+#[derive(Debug, Serialize)]
+pub struct DetectionReport {
+    /// The installer type that was chosen.
+    pub detected: &'static str,
+    pub machine: Machine,
+    pub subsystem: Subsystem,
+    /// The names of the sections in the PE section table.
+    pub sections: Vec<String>,
+    /// The file offset where data appended after the last PE section begins.
+    pub overlay_offset: Option<u64>,
+    /// The first bytes of the overlay, formatted as hex.
+    pub overlay_magic: Option<String>,
+    pub version_info: IndexMap<String, String>,
+    pub manifest: Option<String>,
+    pub detectors: Vec<Detection>,
+    /// How the exe was classified if it matched no known installer framework.
+    pub classification: Option<Classification>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Machine {
+    pub value: u16,
+    pub architecture: Architecture,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Subsystem {
+    pub value: u16,
+    pub name: &'static str,
+}
+
+impl Subsystem {
+    pub const fn new(value: u16) -> Self {
+        let name = match value {
+            1 => "Native",
+            2 => "Windows GUI",
+            3 => "Windows CUI",
+            5 => "OS/2 CUI",
+            7 => "POSIX CUI",
+            9 => "Windows CE GUI",
+            10 => "EFI application",
+            11 => "EFI boot service driver",
+            12 => "EFI runtime driver",
+            13 => "EFI ROM",
+            14 => "Xbox",
+            16 => "Windows boot application",
+            _ => "Unknown",
+        };
+
+        Self { value, name }
+    }
+}
+
+/// The installer types that an exe is checked for, in the order that they are checked.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum Detector {
+    AdvancedInstaller,
+    Burn,
+    Inno,
+    InstallShield,
+    Nsis,
+    Qt,
+    SevenZipSfx,
+    Squirrel,
+    InstallAware,
+    SetupFactory,
+    Wise,
+    SmartInstallMaker,
+    Clickteam,
+}
+
+impl Detector {
+    pub const ALL: [Self; 13] = [
+        Self::AdvancedInstaller,
+        Self::Burn,
+        Self::Inno,
+        Self::InstallShield,
+        Self::Nsis,
+        Self::Qt,
+        Self::SevenZipSfx,
+        Self::Squirrel,
+        Self::InstallAware,
+        Self::SetupFactory,
+        Self::Wise,
+        Self::SmartInstallMaker,
+        Self::Clickteam,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::AdvancedInstaller => "Advanced Installer",
+            Self::Burn => "Burn",
+            Self::Inno => "Inno Setup",
+            Self::InstallShield => "InstallShield",
+            Self::Nsis => "NSIS",
+            Self::Qt => "Qt Installer Framework",
+            Self::SevenZipSfx => "7-Zip SFX",
+            Self::Squirrel => "Squirrel",
+            Self::InstallAware => "InstallAware",
+            Self::SetupFactory => "Setup Factory",
+            Self::Wise => "Wise Installation System",
+            Self::SmartInstallMaker => "Smart Install Maker",
+            Self::Clickteam => "Clickteam Install Creator",
+        }
+    }
+
+    /// Describes the evidence that the detector looks for.
+    pub const fn looks_for(self) -> &'static str {
+        match self {
+            Self::AdvancedInstaller => "an Advanced Installer footer at the end of the file",
+            Self::Burn => "a .wixburn section or a Burn manifest resource",
+            Self::Inno => "an Inno Setup loader resource",
+            Self::InstallShield => "setup.ini, setup.iss or Setup.xml in the overlay",
+            Self::Nsis => "an NSIS first header at the start of the overlay",
+            Self::Qt => "a Qt resource containing Updates.xml in the overlay",
+            Self::SevenZipSfx => "a ;!@Install@!UTF-8! config at the start of the overlay",
+            Self::Squirrel => {
+                "a DATA resource containing a .nupkg, or a Velopack package in the overlay"
+            }
+            Self::InstallAware => "an InstallAware assembly identity in the manifest",
+            Self::SetupFactory => "a Setup Factory signature at the start of the overlay",
+            Self::Wise => "a .WISE section",
+            Self::SmartInstallMaker => "a Smart Install Maker header at the start of the overlay",
+            Self::Clickteam => "a Clickteam data block signature at the start of the overlay",
+        }
+    }
+}
+
+impl fmt::Display for Detector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Detection {
+    pub detector: Detector,
+    pub looks_for: &'static str,
+    /// Evidence found in the file that is relevant to this detector, such as a matched section.
+    pub evidence: Option<String>,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "verdict", content = "error", rename_all = "snake_case")]
+pub enum Verdict {
+    Detected,
+    NotDetected,
+    /// The file looked like this installer type but could not be parsed.
+    Failed(String),
+}
+
+impl DetectionReport {
+    /// Formats the report as human-readable text.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, text: &mut String) -> fmt::Result {
+        writeln!(text, "{} {}", "Detected:".bold(), self.detected.green())?;
+        writeln!(
+            text,
+            "{} {:#06x} ({})",
+            "Machine:".bold(),
+            self.machine.value,
+            self.machine.architecture
+        )?;
+        writeln!(
+            text,
+            "{} {} ({})",
+            "Subsystem:".bold(),
+            self.subsystem.value,
+            self.subsystem.name
+        )?;
+        writeln!(text, "{} {}", "Sections:".bold(), self.sections.join(", "))?;
+        match self.overlay_offset {
+            Some(offset) => writeln!(
+                text,
+                "{} {offset:#x} ({})",
+                "Overlay:".bold(),
+                self.overlay_magic.as_deref().unwrap_or("no data")
+            )?,
+            None => writeln!(text, "{} none", "Overlay:".bold())?,
+        }
+
+        writeln!(text, "\n{}", "Detectors:".bold())?;
+        for detection in &self.detectors {
+            match &detection.verdict {
+                Verdict::Detected => writeln!(text, "  {} {}", "✓".green(), detection.detector)?,
+                Verdict::NotDetected => {
+                    writeln!(text, "  {} {}", "✗".bright_black(), detection.detector)?;
+                }
+                Verdict::Failed(error) => writeln!(
+                    text,
+                    "  {} {}: {}",
+                    "!".red(),
+                    detection.detector,
+                    error.red()
+                )?,
+            }
+            writeln!(text, "      looks for {}", detection.looks_for)?;
+            if let Some(evidence) = &detection.evidence {
+                writeln!(text, "      found {evidence}")?;
+            }
+        }
+
+        if let Some(classification) = &self.classification {
+            writeln!(
+                text,
+                "\n{} {:?} ({:.0}% confidence)",
+                "Classified as:".bold(),
+                classification.installer_type,
+                classification.confidence * 100.0
+            )?;
+            for signal in &classification.signals {
+                writeln!(text, "  {signal:?} ({:+})", signal.weight())?;
+            }
+        }
+
+        if !self.version_info.is_empty() {
+            writeln!(text, "\n{}", "Version info:".bold())?;
+            for (key, value) in &self.version_info {
+                writeln!(text, "  {key}: {value}")?;
+            }
+        }
+
+        if let Some(manifest) = &self.manifest {
+            writeln!(text, "\n{}", "Manifest:".bold())?;
+            writeln!(text, "{}", manifest.trim())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats bytes as space-separated hex, followed by their printable ASCII representation.
+pub fn format_magic(bytes: &[u8]) -> String {
+    let mut magic = String::with_capacity(bytes.len() * 4);
+    for byte in bytes {
+        let _ = write!(magic, "{byte:02X} ");
+    }
+    magic.push('|');
+    magic.extend(bytes.iter().map(|&byte| {
+        if byte.is_ascii_graphic() || byte == b' ' {
+            char::from(byte)
+        } else {
+            '.'
+        }
+    }));
+    magic.push('|');
+    magic
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_magic;
+
+    #[test]
+    fn magic() {
+        assert_eq!(format_magic(b"\x1f\x8bNull"), "1F 8B 4E 75 6C 6C |..Null|");
+    }
+}
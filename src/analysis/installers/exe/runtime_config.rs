@@ -0,0 +1,220 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use serde::Deserialize;
+use zerocopy::{LittleEndian, U64};
+
+use crate::read::ReadBytesExt;
+
+/// The marker that the .NET apphost of a single-file bundle is stamped with, preceded by the
+/// offset of the bundle header. This is the SHA-256 of `.net core bundle`.
+///
+/// <https://github.com/dotnet/runtime/blob/main/src/native/corehost/apphost/bundle_marker.cpp>
+const BUNDLE_SIGNATURE: [u8; 32] = [
+    0x8B, 0x12, 0x02, 0xB9, 0x6A, 0x61, 0x20, 0x38, 0x72, 0x7B, 0x93, 0x02, 0x14, 0xD7, 0xA0, 0x32,
+    0x13, 0xF5, 0xB9, 0xE6, 0xEF, 0xAE, 0x33, 0x18, 0xEE, 0x3B, 0x2D, 0xCE, 0x24, 0xB3, 0x6A, 0xAE,
+];
+
+/// Bundles before this version don't record where their `runtimeconfig.json` is.
+const MIN_BUNDLE_MAJOR_VERSION: u32 = 2;
+
+/// Guards against reading an unreasonable amount of data from a malformed bundle or exe.
+const MAX_SIZE: u64 = 1 << 24;
+
+/// The `<name>.runtimeconfig.json` that a framework-dependent .NET app is published with, which
+/// lists the shared frameworks it needs to run.
+///
+/// .NET Core 3.0 and later apps are launched by a native apphost exe rather than being .NET
+/// assemblies themselves, so their CLR header can't be read from the exe. Instead, this file is
+/// either next to the exe or embedded in it when the app is published as a single file.
+///
+/// <https://learn.microsoft.com/dotnet/core/runtime-config/>
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    #[serde(default)]
+    runtime_options: RuntimeOptions,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeOptions {
+    framework: Option<Framework>,
+    #[serde(default)]
+    frameworks: Vec<Framework>,
+}
+
+/// A shared framework that an app depends on, such as `Microsoft.NETCore.App`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Framework {
+    pub name: String,
+    pub version: String,
+}
+
+impl RuntimeConfig {
+    pub fn from_slice(json: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(json)
+    }
+
+    /// Returns the shared frameworks that the app depends on. A self-contained app includes its
+    /// frameworks, so it doesn't depend on any.
+    pub fn frameworks(&self) -> impl Iterator<Item = &Framework> {
+        self.runtime_options
+            .framework
+            .iter()
+            .chain(&self.runtime_options.frameworks)
+    }
+
+    /// Reads the `runtimeconfig.json` embedded in a .NET single-file bundle.
+    ///
+    /// The apphost of a bundle is stamped with the offset of the bundle header, which records
+    /// where the `runtimeconfig.json` is. Returns `None` if the exe isn't a bundle.
+    ///
+    /// <https://github.com/dotnet/runtime/blob/main/docs/design/features/single-file-bundler.md>
+    pub fn read_from_bundle<R: Read + Seek>(
+        mut reader: R,
+        image_size: u64,
+    ) -> io::Result<Option<Self>> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut image = Vec::new();
+        reader
+            .by_ref()
+            .take(image_size.min(MAX_SIZE))
+            .read_to_end(&mut image)?;
+
+        let Some(signature_offset) = memchr::memmem::find(&image, &BUNDLE_SIGNATURE) else {
+            return Ok(None);
+        };
+
+        let header_offset = signature_offset
+            .checked_sub(size_of::<u64>())
+            .and_then(|start| image.get(start..signature_offset))
+            .and_then(|bytes| bytes.try_into().ok())
+            .map_or(0, u64::from_le_bytes);
+
+        // An apphost that isn't a bundle still has the marker, but with an offset of zero
+        if header_offset == 0 {
+            return Ok(None);
+        }
+
+        reader.seek(SeekFrom::Start(header_offset))?;
+        let major_version = reader.read_u32::<LittleEndian>()?;
+        if major_version < MIN_BUNDLE_MAJOR_VERSION {
+            return Ok(None);
+        }
+
+        // Skip the minor version and the number of embedded files
+        reader.seek_relative(8)?;
+
+        // Skip the bundle ID, which is a string prefixed with its 7-bit encoded length
+        let bundle_id_length = read_7bit_encoded_length(&mut reader)?;
+        reader.seek_relative(i64::from(bundle_id_length))?;
+
+        // Skip the offset and size of the deps.json
+        reader.seek_relative(16)?;
+
+        let offset = reader.read_t::<U64<LittleEndian>>()?.get();
+        let size = reader.read_t::<U64<LittleEndian>>()?.get();
+        if offset == 0 || size == 0 || size > MAX_SIZE {
+            return Ok(None);
+        }
+
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut json = Vec::new();
+        reader.take(size).read_to_end(&mut json)?;
+
+        Self::from_slice(&json)
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+/// Reads a length written by .NET's `BinaryWriter.Write7BitEncodedInt`.
+fn read_7bit_encoded_length<R: Read>(mut reader: R) -> io::Result<u32> {
+    let mut length = 0;
+    for shift in (0..32).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        length |= u32::from(byte[0] & 0x7F) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(length);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Invalid 7-bit encoded length in .NET bundle header",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{BUNDLE_SIGNATURE, RuntimeConfig};
+
+    const RUNTIME_CONFIG: &str = r#"{
+        "runtimeOptions": {
+            "tfm": "net8.0",
+            "frameworks": [
+                { "name": "Microsoft.NETCore.App", "version": "8.0.0" },
+                { "name": "Microsoft.WindowsDesktop.App", "version": "8.0.0" }
+            ]
+        }
+    }"#;
+
+    /// Builds an apphost-like exe followed by a bundle whose header points to the
+    /// `runtimeconfig.json`.
+    fn single_file_bundle(header_offset: u64) -> (Vec<u8>, u64) {
+        let mut exe = b"MZ apphost".to_vec();
+        exe.extend(header_offset.to_le_bytes());
+        exe.extend(BUNDLE_SIGNATURE);
+        exe.extend([0; 6]);
+        let image_size = exe.len() as u64;
+
+        let runtime_config_offset = exe.len() as u64;
+        exe.extend(RUNTIME_CONFIG.as_bytes());
+
+        if header_offset != 0 {
+            exe.resize(header_offset as usize, 0);
+            exe.extend(6u32.to_le_bytes());
+            exe.extend(0u32.to_le_bytes());
+            exe.extend(1i32.to_le_bytes());
+            exe.push(3);
+            exe.extend(b"abc");
+            exe.extend([0; 16]);
+            exe.extend(runtime_config_offset.to_le_bytes());
+            exe.extend((RUNTIME_CONFIG.len() as u64).to_le_bytes());
+            exe.extend(0u64.to_le_bytes());
+        }
+
+        (exe, image_size)
+    }
+
+    #[test]
+    fn reads_runtime_config_from_single_file_bundle() {
+        let (exe, image_size) = single_file_bundle(1024);
+
+        let runtime_config = RuntimeConfig::read_from_bundle(Cursor::new(exe), image_size)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            runtime_config
+                .frameworks()
+                .map(|framework| framework.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Microsoft.NETCore.App", "Microsoft.WindowsDesktop.App"]
+        );
+    }
+
+    #[test]
+    fn apphost_without_bundle() {
+        let (exe, image_size) = single_file_bundle(0);
+
+        assert!(
+            RuntimeConfig::read_from_bundle(Cursor::new(exe), image_size)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::BTreeSet, io};
+
+use thiserror::Error;
+use winget_types::installer::{
+    Architecture, ExpectedReturnCodes, InstallModes, Installer, InstallerReturnCode,
+    InstallerSwitches, InstallerType, ReturnResponse,
+};
+
+use crate::{
+    analysis::{
+        Installers,
+        installers::{pe::PE, utils::ProductInfo},
+    },
+    traits::FromMachine,
+};
+
+/// The prefix of the manifest assembly identity of the InstallAware setup engine stub.
+const ASSEMBLY_NAME_PREFIX: &str = "InstallAware.";
+
+#[derive(Error, Debug)]
+pub enum InstallAwareError {
+    #[error("File is not an InstallAware installer")]
+    NotInstallAwareFile,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub struct InstallAware {
+    architecture: Architecture,
+    product_info: ProductInfo,
+}
+
+impl InstallAware {
+    /// Detects an InstallAware native setup from the assembly identity in its manifest, which is
+    /// set by the setup engine stub rather than the project.
+    ///
+    /// InstallAware setups that are wrapped in a 7-Zip SFX are handled by
+    /// [`SevenZipSfx`](super::SevenZipSfx) instead.
+    pub fn new(pe: &PE, product_info: &ProductInfo) -> Result<Self, InstallAwareError> {
+        if !product_info
+            .assembly_name()
+            .is_some_and(Self::is_engine_assembly)
+        {
+            return Err(InstallAwareError::NotInstallAwareFile);
+        }
+
+        Ok(Self {
+            architecture: Architecture::from_machine(pe.machine()),
+            product_info: product_info.clone(),
+        })
+    }
+
+    fn is_engine_assembly(name: &str) -> bool {
+        name.get(..ASSEMBLY_NAME_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(ASSEMBLY_NAME_PREFIX))
+    }
+}
+
+impl Installers for InstallAware {
+    fn installers(&self) -> Vec<Installer> {
+        vec![Installer {
+            architecture: self.architecture,
+            r#type: Some(InstallerType::Exe),
+            install_modes: InstallModes::all(),
+            // https://www.installaware.com/mhtml/en/installaware/command_line_parameters.htm
+            switches: InstallerSwitches::builder()
+                .silent("/s".parse().unwrap())
+                .silent_with_progress("/s".parse().unwrap())
+                .install_location("TARGETDIR=\"<INSTALLPATH>\"".parse().unwrap())
+                .log("/l=\"<LOGPATH>\"".parse().unwrap())
+                .build(),
+            expected_return_codes: expected_return_codes(),
+            apps_and_features_entries: self.product_info.apps_and_features_entries(),
+            ..Installer::default()
+        }]
+    }
+}
+
+/// InstallAware drives Windows Installer, so it exits with the Windows Installer error codes.
+fn expected_return_codes() -> BTreeSet<ExpectedReturnCodes> {
+    [
+        (1602, ReturnResponse::CancelledByUser),
+        (1618, ReturnResponse::InstallInProgress),
+        (1641, ReturnResponse::RebootInitiated),
+        (3010, ReturnResponse::RebootRequiredToFinish),
+    ]
+    .into_iter()
+    .map(|(code, response)| ExpectedReturnCodes {
+        installer_return_code: InstallerReturnCode::new(code),
+        return_response: response,
+        return_response_url: None,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::InstallAware;
+
+    #[rstest]
+    #[case("InstallAware.Setup", true)]
+    #[case("installaware.setup", true)]
+    #[case("InstallAware", false)]
+    #[case("Contoso.InstallAware.Setup", false)]
+    #[case("Nullsoft.NSIS.exehead", false)]
+    fn engine_assembly(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(InstallAware::is_engine_assembly(name), expected);
+    }
+}
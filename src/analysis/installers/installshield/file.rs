@@ -19,6 +19,13 @@ pub struct File {
 }
 
 impl File {
+    /// Returns `true` if the file name ends with the given extension, ignoring ASCII case.
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.name
+            .rsplit_once('.')
+            .is_some_and(|(_, file_extension)| file_extension.eq_ignore_ascii_case(extension))
+    }
+
     pub fn read_text<R: Read + Seek>(&self, reader: &mut R) -> Result<Option<String>, FileError> {
         Ok(self.read(reader)?.map(|data| {
             if let Some((encoding, bom_len)) = Encoding::for_bom(&data) {
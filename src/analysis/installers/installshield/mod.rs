@@ -1,4 +1,5 @@
 mod file;
+mod project_type;
 mod return_codes;
 mod setup_ini;
 mod setup_iss;
@@ -6,7 +7,7 @@
 
 use std::{
     ffi::CStr,
-    io::{self, Read, Seek, SeekFrom},
+    io::{self, Cursor, Read, Seek, SeekFrom},
 };
 
 use camino::Utf8PathBuf;
@@ -18,7 +19,7 @@
     LanguageTag,
     installer::{
         AppsAndFeaturesEntry, Architecture, InstallModes, InstallationMetadata, Installer,
-        InstallerSwitches, InstallerType, Scope,
+        InstallerType, Scope,
     },
 };
 use zerocopy::LittleEndian;
@@ -27,6 +28,7 @@
     analysis::{
         Installers,
         installers::{
+            Msi,
             installshield::{
                 file::File, setup_ini::SetupIni, setup_iss::SetupIss, setup_xml::SetupXml,
             },
@@ -39,6 +41,7 @@
 };
 
 pub use file::FileError;
+pub use project_type::ProjectType;
 
 #[derive(Error, Debug)]
 pub enum InstallShieldError {
@@ -55,9 +58,12 @@ pub enum InstallShieldError {
 #[derive(Debug)]
 pub struct InstallShield {
     pub architecture: Architecture,
+    pub project_type: ProjectType,
     pub setup_ini: Option<SetupIni>,
     pub setup_iss: Option<SetupIss>,
     pub setup_xml: Option<SetupXml>,
+    /// The Windows Installer package embedded in a Basic MSI or InstallScript MSI project.
+    pub msi: Option<Msi>,
 }
 
 impl InstallShield {
@@ -152,11 +158,45 @@ enum InstallScriptEncoding {
             return Err(InstallShieldError::NotInstallShieldFile);
         }
 
+        let project_type = if setup_xml.is_some() {
+            ProjectType::Suite
+        } else {
+            setup_ini
+                .as_ref()
+                .and_then(|ini| ini.startup.script_driven.as_deref())
+                .and_then(ProjectType::from_script_driven)
+                .unwrap_or_else(|| ProjectType::from_files(&files))
+        };
+        debug!(?project_type);
+
+        // Only the package that Setup.ini launches is read, falling back to the first embedded MSI
+        let package_name = setup_ini
+            .as_ref()
+            .and_then(|ini| ini.startup.package_name.as_deref());
+        let msi = project_type
+            .is_msi_based()
+            .then(|| {
+                files.iter().find(|file| {
+                    package_name.map_or_else(
+                        || file.has_extension("msi"),
+                        |package_name| file.name.eq_ignore_ascii_case(package_name),
+                    )
+                })
+            })
+            .flatten()
+            .and_then(|file| file.read(&mut reader).ok().flatten())
+            .and_then(|msi_data| Msi::new(Cursor::new(msi_data)).ok());
+
         Ok(Self {
-            architecture: Architecture::from_machine(pe.machine()),
+            architecture: msi.as_ref().map_or_else(
+                || Architecture::from_machine(pe.machine()),
+                |msi| msi.architecture,
+            ),
+            project_type,
             setup_ini,
             setup_iss,
             setup_xml,
+            msi,
         })
     }
 }
@@ -193,13 +233,12 @@ fn installers(&self) -> Vec<Installer> {
         let startup = self.setup_ini.as_ref().map(|ini| &ini.startup);
         let xml = self.setup_xml.as_ref();
         let iss = self.setup_iss.as_ref();
-        let script_driven = startup.and_then(|s| s.script_driven.as_deref());
-        let msi_based = matches!(script_driven, Some("0" | "2"));
+        let msi_based = self.project_type.is_msi_based();
 
-        if script_driven == Some("4") && iss.is_none() {
+        if self.project_type.is_installscript() && iss.is_none() {
             warn!(
-                "InstallScriptUnicode installer without embedded setup.iss - \
-                 a separate response file may be required for silent installation: \
+                "InstallScript installer without embedded setup.iss - \
+                 a response file recorded with /r may need to be passed with /f1 for silent installation: \
                  https://github.com/microsoft/winget-pkgs/issues/246"
             );
         }
@@ -222,16 +261,25 @@ fn installers(&self) -> Vec<Installer> {
                 })
             });
 
-        let product_code = startup
-            .and_then(|s| s.product_code.as_ref())
-            .map(|code| {
-                if msi_based {
-                    code.clone()
-                } else if script_driven == Some("4") {
-                    format!("{{{code}}}")
-                } else {
-                    format!("InstallShield_{code}")
-                }
+        // The product code of an MSI based project is that of its embedded MSI
+        let msi_installer = self
+            .msi
+            .as_ref()
+            .and_then(|msi| msi.installers().into_iter().next());
+
+        let product_code = msi_installer
+            .as_ref()
+            .and_then(|installer| installer.product_code.clone())
+            .or_else(|| {
+                startup
+                    .and_then(|s| s.product_code.as_ref())
+                    .map(|code| match self.project_type {
+                        ProjectType::BasicMsi | ProjectType::InstallScriptMsi => code.clone(),
+                        ProjectType::InstallScriptUnicode => format!("{{{code}}}"),
+                        ProjectType::InstallScript | ProjectType::Suite => {
+                            format!("InstallShield_{code}")
+                        }
+                    })
             })
             .or_else(|| {
                 xml.and_then(|xml| {
@@ -262,16 +310,36 @@ fn installers(&self) -> Vec<Installer> {
 
         let upgrade_code = startup
             .and_then(|s| s.upgrade_code.clone())
+            .or_else(|| {
+                self.msi
+                    .as_ref()
+                    .and_then(|msi| msi.property_table.get("UpgradeCode"))
+                    .map(str::to_owned)
+            })
             .or_else(|| xml.and_then(|xml| xml.get_property("UpgradeCode")));
 
         // TODO are these MSI vars? could reuse logic from burn/manifest/variable.rs
         let install_dir = xml
             .and_then(|xml| xml.get_property("INSTALLDIR"))
-            .map(|value| value.replace("[ProgramFiles64Folder]", RELATIVE_PROGRAM_FILES_64));
+            .map(|value| value.replace("[ProgramFiles64Folder]", RELATIVE_PROGRAM_FILES_64))
+            .map(Utf8PathBuf::from)
+            .or_else(|| {
+                msi_installer.as_ref().and_then(|installer| {
+                    installer
+                        .installation_metadata
+                        .default_install_location
+                        .clone()
+                })
+            });
 
-        let scope = install_dir
-            .as_deref()
-            .and_then(Scope::from_install_directory)
+        let scope = msi_installer
+            .as_ref()
+            .and_then(|installer| installer.scope)
+            .or_else(|| {
+                install_dir
+                    .as_deref()
+                    .and_then(|install_dir| Scope::from_install_directory(install_dir.as_str()))
+            })
             .or(Some(Scope::Machine));
 
         vec![Installer {
@@ -289,30 +357,11 @@ fn installers(&self) -> Vec<Installer> {
                 .build()
                 .into(),
             installation_metadata: InstallationMetadata {
-                default_install_location: install_dir.map(Utf8PathBuf::from),
+                default_install_location: install_dir,
                 ..InstallationMetadata::default()
             },
             install_modes: InstallModes::all(),
-            switches: if xml.is_some() {
-                InstallerSwitches::builder()
-                    .silent("/silent".parse().unwrap())
-                    .silent_with_progress("/passive".parse().unwrap())
-                    .log("/log \"<LOGPATH>\"".parse().unwrap())
-                    .repair("/repair".parse().unwrap())
-                    .build()
-            } else if msi_based {
-                InstallerSwitches::builder()
-                    .silent("/s /v\"/qn /norestart\"".parse().unwrap())
-                    .silent_with_progress("/s /v\"/qb /norestart\"".parse().unwrap())
-                    .install_location("/v\"INSTALLDIR=\"\"<INSTALLPATH>\"\"\"".parse().unwrap())
-                    .log("/v\"/log \"\"<LOGPATH>\"\"\"".parse().unwrap())
-                    .build()
-            } else {
-                InstallerSwitches::builder()
-                    .silent("/s".parse().unwrap())
-                    .silent_with_progress("/s".parse().unwrap())
-                    .build()
-            },
+            switches: self.project_type.switches(),
             expected_return_codes: return_codes::expected_return_codes(msi_based),
             ..Installer::default()
         }]
@@ -0,0 +1,128 @@
+use winget_types::installer::InstallerSwitches;
+
+use super::file::File;
+
+/// The kind of InstallShield project that produced the installer, which determines how the
+/// bootstrapper is driven from the command line.
+///
+/// See <https://docs.revenera.com/installshield28helplib/helplibrary/ProjectTypes.htm>.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProjectType {
+    /// A Windows Installer package wrapped in a bootstrapper, with the UI provided by the MSI.
+    BasicMsi,
+    /// A Windows Installer package whose UI and logic is driven by InstallScript.
+    InstallScriptMsi,
+    /// A pure InstallScript installer that does not use Windows Installer.
+    InstallScript,
+    /// A pure InstallScript installer built with Unicode support, which registers itself under its
+    /// product code rather than an `InstallShield_` prefixed key.
+    InstallScriptUnicode,
+    /// An Advanced UI or Suite/Advanced UI project, configured by `Setup.xml`.
+    Suite,
+}
+
+impl ProjectType {
+    const SETUP_SCRIPT: &'static str = "setup.inx";
+
+    /// Maps the `ScriptDriven` value from the `[Startup]` section of `Setup.ini`.
+    pub fn from_script_driven(script_driven: &str) -> Option<Self> {
+        match script_driven {
+            "0" => Some(Self::BasicMsi),
+            "1" => Some(Self::InstallScript),
+            "2" => Some(Self::InstallScriptMsi),
+            "4" => Some(Self::InstallScriptUnicode),
+            _ => None,
+        }
+    }
+
+    /// Infers the project type from the files embedded in the bootstrapper when `Setup.ini`
+    /// doesn't specify it.
+    pub fn from_files(files: &[File]) -> Self {
+        let has_msi = files.iter().any(|file| file.has_extension("msi"));
+        let has_script = files
+            .iter()
+            .any(|file| file.name.eq_ignore_ascii_case(Self::SETUP_SCRIPT));
+
+        match (has_msi, has_script) {
+            (true, true) => Self::InstallScriptMsi,
+            (true, false) => Self::BasicMsi,
+            (false, _) => Self::InstallScript,
+        }
+    }
+
+    /// Returns `true` if the project installs through Windows Installer.
+    #[inline]
+    pub const fn is_msi_based(self) -> bool {
+        matches!(self, Self::BasicMsi | Self::InstallScriptMsi)
+    }
+
+    /// Returns `true` if the project is driven by InstallScript without Windows Installer.
+    #[inline]
+    pub const fn is_installscript(self) -> bool {
+        matches!(self, Self::InstallScript | Self::InstallScriptUnicode)
+    }
+
+    /// Returns the switches for the project type.
+    ///
+    /// MSI based projects pass their arguments through to msiexec with `/v`. InstallScript projects
+    /// run silently with `/s`, reading their responses from a `setup.iss` file that can be given
+    /// with `/f1` and logging to the file given with `/f2`.
+    pub fn switches(self) -> InstallerSwitches {
+        match self {
+            Self::BasicMsi | Self::InstallScriptMsi => InstallerSwitches::builder()
+                .silent("/s /v\"/qn /norestart\"".parse().unwrap())
+                .silent_with_progress("/s /v\"/qb /norestart\"".parse().unwrap())
+                .install_location("/v\"INSTALLDIR=\"\"<INSTALLPATH>\"\"\"".parse().unwrap())
+                .log("/v\"/log \"\"<LOGPATH>\"\"\"".parse().unwrap())
+                .build(),
+            Self::InstallScript | Self::InstallScriptUnicode => InstallerSwitches::builder()
+                .silent("/s".parse().unwrap())
+                .silent_with_progress("/s".parse().unwrap())
+                .log("/f2\"<LOGPATH>\"".parse().unwrap())
+                .build(),
+            Self::Suite => InstallerSwitches::builder()
+                .silent("/silent".parse().unwrap())
+                .silent_with_progress("/passive".parse().unwrap())
+                .log("/log \"<LOGPATH>\"".parse().unwrap())
+                .repair("/repair".parse().unwrap())
+                .build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::ProjectType;
+    use crate::analysis::installers::installshield::file::File;
+
+    fn file(name: &str) -> File {
+        File {
+            name: name.to_owned(),
+            encoded_flags: 0,
+            size: 0,
+            offset: 0,
+        }
+    }
+
+    #[rstest]
+    #[case("0", Some(ProjectType::BasicMsi))]
+    #[case("1", Some(ProjectType::InstallScript))]
+    #[case("2", Some(ProjectType::InstallScriptMsi))]
+    #[case("3", None)]
+    #[case("4", Some(ProjectType::InstallScriptUnicode))]
+    fn script_driven(#[case] script_driven: &str, #[case] expected: Option<ProjectType>) {
+        assert_eq!(ProjectType::from_script_driven(script_driven), expected);
+    }
+
+    #[rstest]
+    #[case(&["Setup.ini", "Product.msi"], ProjectType::BasicMsi)]
+    #[case(&["setup.inx", "Product.MSI", "ISSetup.dll"], ProjectType::InstallScriptMsi)]
+    #[case(&["setup.inx", "data1.cab"], ProjectType::InstallScript)]
+    fn from_files(#[case] names: &[&str], #[case] expected: ProjectType) {
+        let files = names.iter().map(|name| file(name)).collect::<Vec<_>>();
+
+        assert_eq!(ProjectType::from_files(&files), expected);
+    }
+}
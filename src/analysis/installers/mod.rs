@@ -1,25 +1,35 @@
 mod advanced;
 pub mod burn;
+mod clickteam;
 mod exe;
 pub mod inno;
+mod installaware;
 mod installshield;
 mod msi;
 pub mod msix_family;
 pub mod nsis;
 pub mod pe;
 mod qt;
+mod setup_factory;
 mod sevenzip_sfx;
+mod smart_install_maker;
 pub mod squirrel;
 pub mod utils;
+mod wise;
 mod zip;
 
 pub use advanced::AdvancedInstaller;
 pub use burn::Burn;
-pub use exe::Exe;
+pub use clickteam::Clickteam;
+pub use exe::{Classification, DetectionReport, Exe};
+pub use installaware::InstallAware;
 pub use installshield::InstallShield;
 pub use msi::Msi;
 pub use nsis::Nsis;
 pub use qt::Qt;
+pub use setup_factory::SetupFactory;
 pub use sevenzip_sfx::SevenZipSfx;
+pub use smart_install_maker::SmartInstallMaker;
 pub use squirrel::Squirrel;
+pub use wise::Wise;
 pub use zip::Zip;
@@ -0,0 +1,170 @@
+use color_eyre::eyre::{Result, bail};
+use quick_xml::{Reader, events::Event};
+use winget_types::{PackageVersion, url::DecodedUrl};
+
+/// An App Installer file, which points to the current version of an MSIX package or bundle.
+///
+/// See <https://learn.microsoft.com/uwp/schemas/appinstallerschema/element-appinstaller>.
+pub struct AppInstaller {
+    /// The version of the App Installer file itself.
+    pub version: Option<String>,
+    pub main_package: MainPackage,
+}
+
+/// The `MainBundle` or `MainPackage` element of an App Installer file.
+///
+/// See <https://learn.microsoft.com/uwp/schemas/appinstallerschema/element-mainbundle> and
+/// <https://learn.microsoft.com/uwp/schemas/appinstallerschema/element-mainpackage>.
+pub struct MainPackage {
+    pub name: String,
+    pub version: String,
+    pub uri: DecodedUrl,
+    pub is_bundle: bool,
+}
+
+impl AppInstaller {
+    pub fn new(xml: &str) -> Result<Self> {
+        let mut version = None;
+        let mut main_package = None;
+
+        let mut reader = Reader::from_str(xml);
+        let config = reader.config_mut();
+        config.expand_empty_elements = true;
+        config.trim_text(true);
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(event) => match event.local_name().as_ref() {
+                    b"AppInstaller" => {
+                        version = event
+                            .attributes()
+                            .flatten()
+                            .find(|attribute| attribute.key.as_ref() == b"Version")
+                            .map(|attribute| {
+                                String::from_utf8_lossy(&attribute.value).into_owned()
+                            });
+                    }
+                    name @ (b"MainBundle" | b"MainPackage") => {
+                        let is_bundle = name == b"MainBundle";
+                        let mut package_name = String::new();
+                        let mut package_version = String::new();
+                        let mut uri = None;
+                        for attribute in event.attributes().flatten() {
+                            match attribute.key.as_ref() {
+                                b"Name" => {
+                                    package_name =
+                                        String::from_utf8_lossy(&attribute.value).into_owned();
+                                }
+                                b"Version" => {
+                                    package_version =
+                                        String::from_utf8_lossy(&attribute.value).into_owned();
+                                }
+                                b"Uri" => {
+                                    uri = Some(
+                                        String::from_utf8_lossy(&attribute.value)
+                                            .parse::<DecodedUrl>()?,
+                                    );
+                                }
+                                _ => {}
+                            }
+                        }
+                        if let Some(uri) = uri {
+                            main_package = Some(MainPackage {
+                                name: package_name,
+                                version: package_version,
+                                uri,
+                                is_bundle,
+                            });
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let Some(main_package) = main_package else {
+            bail!("No MainBundle or MainPackage with a Uri was found in the App Installer file");
+        };
+
+        Ok(Self {
+            version,
+            main_package,
+        })
+    }
+
+    /// Returns the version of the main package, falling back to the version of the App Installer
+    /// file.
+    pub fn package_version(&self) -> Option<PackageVersion> {
+        self.main_package
+            .version
+            .parse()
+            .ok()
+            .or_else(|| self.version.as_deref()?.parse().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::AppInstaller;
+
+    #[test]
+    fn main_bundle() {
+        const APP_INSTALLER: &str = indoc! {r#"
+            <?xml version="1.0" encoding="utf-8"?>
+            <AppInstaller
+                xmlns="http://schemas.microsoft.com/appx/appinstaller/2018"
+                Version="1.2.3.0"
+                Uri="https://example.com/Contoso.appinstaller">
+                <MainBundle
+                    Name="Contoso.App"
+                    Publisher="CN=Contoso"
+                    Version="1.2.3.0"
+                    Uri="https://example.com/Contoso.App_1.2.3.0.msixbundle" />
+                <Dependencies>
+                    <Package
+                        Name="Microsoft.VCLibs.140.00.UWPDesktop"
+                        Publisher="CN=Microsoft Corporation"
+                        Version="14.0.30704.0"
+                        Uri="https://example.com/Microsoft.VCLibs.x64.14.00.Desktop.appx" />
+                </Dependencies>
+            </AppInstaller>
+        "#};
+
+        let app_installer = AppInstaller::new(APP_INSTALLER).unwrap();
+
+        assert_eq!(app_installer.version.as_deref(), Some("1.2.3.0"));
+        assert_eq!(app_installer.main_package.name, "Contoso.App");
+        assert!(app_installer.main_package.is_bundle);
+        assert_eq!(
+            app_installer.main_package.uri.as_str(),
+            "https://example.com/Contoso.App_1.2.3.0.msixbundle"
+        );
+        assert_eq!(app_installer.package_version().unwrap().as_str(), "1.2.3.0");
+    }
+
+    #[test]
+    fn main_package() {
+        const APP_INSTALLER: &str = indoc! {r#"
+            <AppInstaller xmlns="http://schemas.microsoft.com/appx/appinstaller/2021" Version="2.0.0.0">
+                <MainPackage Name="Contoso.App" Version="2.0.0.0" Uri="https://example.com/Contoso.msix" />
+            </AppInstaller>
+        "#};
+
+        let app_installer = AppInstaller::new(APP_INSTALLER).unwrap();
+
+        assert!(!app_installer.main_package.is_bundle);
+        assert_eq!(
+            app_installer.main_package.uri.as_str(),
+            "https://example.com/Contoso.msix"
+        );
+    }
+
+    #[test]
+    fn no_main_package() {
+        assert!(AppInstaller::new("<AppInstaller Version=\"1.0.0.0\" />").is_err());
+    }
+}
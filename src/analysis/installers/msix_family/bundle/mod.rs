@@ -85,7 +85,7 @@ pub fn new<R: Read + Seek>(reader: R) -> Result<Self> {
                     let mut embedded_msix = zip.by_name(&file_name)?;
                     let mut temp_file = tempfile::tempfile()?;
                     io::copy(&mut embedded_msix, &mut temp_file)?;
-                    Msix::new(BufReader::new(temp_file))
+                    Msix::new(BufReader::new(temp_file), false)
                 })
                 .collect::<Result<Vec<_>>>()?,
             signature_sha_256,
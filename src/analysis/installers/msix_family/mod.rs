@@ -1,3 +1,4 @@
+pub mod app_installer;
 pub mod bundle;
 mod utils;
 
@@ -6,21 +7,24 @@
     io::{Read, Seek},
 };
 
+use camino::Utf8Path;
 use color_eyre::eyre::Result;
 use quick_xml::{Reader, events::Event};
 use winget_types::{
     Sha256String,
     installer::{
-        AppsAndFeaturesEntry, Architecture, Capability, FileExtension, InstallationMetadata,
-        Installer, InstallerType, MinimumOSVersion, PackageFamilyName, Platform,
-        RestrictedCapability, UpgradeBehavior,
+        AppsAndFeaturesEntry, Architecture, Capability, Command, Dependencies, FileExtension,
+        InstallationMetadata, Installer, InstallerType, MinimumOSVersion, PackageFamilyName,
+        Platform, Protocol, RestrictedCapability, UpgradeBehavior,
     },
 };
 use zip::ZipArchive;
 
-use super::msix_family::utils::{get_install_location, hash_signature, read_manifest};
+use super::msix_family::utils::{
+    get_install_location, hash_signature, package_dependency, read_logo, read_manifest,
+};
 use crate::{
-    analysis::{Installers, extensions::MSIX},
+    analysis::{Installers, extensions::MSIX, icon::Icon},
     traits::AsciiExt,
 };
 
@@ -28,6 +32,8 @@ pub struct Msix {
     appx_manifest: String,
     pub signature_sha_256: Sha256String,
     pub manifest: Package,
+    /// The largest variant of the `Square44x44Logo` declared by the package's visual elements.
+    pub icon: Option<Icon>,
 }
 
 const APPX_MANIFEST_XML: &str = "AppxManifest.xml";
@@ -35,16 +41,131 @@ pub struct Msix {
 const MSIX_MIN_VERSION: MinimumOSVersion = MinimumOSVersion::new(10, 0, 17763, 0);
 
 impl Msix {
-    pub fn new<R: Read + Seek>(reader: R) -> Result<Self> {
+    /// Reads the MSIX, only reading its logo if `extract_icon` is true.
+    pub fn new<R: Read + Seek>(reader: R, extract_icon: bool) -> Result<Self> {
         let mut zip = ZipArchive::new(reader)?;
 
         let appx_manifest = read_manifest(&mut zip, APPX_MANIFEST_XML)?;
 
         let signature_sha_256 = hash_signature(&mut zip)?;
 
-        let mut manifest = Package::default();
+        let manifest = Package::from_manifest(&appx_manifest)?;
+
+        let icon = extract_icon
+            .then(|| read_logo(&mut zip, &manifest.visual_elements.square_44x44_logo))
+            .flatten();
+
+        Ok(Self {
+            appx_manifest,
+            signature_sha_256,
+            manifest,
+            icon,
+        })
+    }
+}
+
+impl Installers for Msix {
+    fn installers(&self) -> Vec<Installer> {
+        let is_appx = self
+            .manifest
+            .dependencies
+            .target_device_family
+            .iter()
+            .all(|target_device_family| target_device_family.min_version < MSIX_MIN_VERSION)
+            && !self.appx_manifest.contains_ignore_ascii_case(MSIX);
+
+        vec![Installer {
+            platform: self
+                .manifest
+                .dependencies
+                .target_device_family
+                .iter()
+                .map(|target_device_family| target_device_family.name)
+                .collect(),
+            minimum_os_version: self
+                .manifest
+                .dependencies
+                .target_device_family
+                .iter()
+                .map(|target_device_family| target_device_family.min_version)
+                .min(),
+            architecture: self
+                .manifest
+                .identity
+                .processor_architecture
+                .parse()
+                .unwrap_or(Architecture::X86),
+            r#type: if is_appx {
+                Some(InstallerType::Appx)
+            } else {
+                Some(InstallerType::Msix)
+            },
+            signature_sha_256: Some(self.signature_sha_256.clone()),
+            upgrade_behavior: Some(UpgradeBehavior::Install),
+            file_extensions: self
+                .manifest
+                .file_type_association
+                .supported_file_types
+                .clone(),
+            package_family_name: Some(PackageFamilyName::new(
+                self.manifest.identity.name.clone(),
+                &self.manifest.identity.publisher,
+            )),
+            protocols: self.manifest.extensions.protocols.clone(),
+            commands: self.manifest.extensions.execution_aliases.clone(),
+            dependencies: Dependencies {
+                package_dependencies: self
+                    .manifest
+                    .dependencies
+                    .package_dependencies
+                    .iter()
+                    .filter_map(|dependency| {
+                        package_dependency(&dependency.name, dependency.min_version.as_deref())
+                    })
+                    .collect(),
+                ..Dependencies::default()
+            },
+            capabilities: self.manifest.capabilities.unrestricted.clone(),
+            restricted_capabilities: self.manifest.capabilities.restricted.clone(),
+            apps_and_features_entries: AppsAndFeaturesEntry::builder()
+                .display_name(&self.manifest.properties.display_name)
+                .publisher(&self.manifest.properties.publisher_display_name)
+                .display_version(&self.manifest.identity.version)
+                .build()
+                .into(),
+            installation_metadata: InstallationMetadata {
+                default_install_location: Some(get_install_location(
+                    &self.manifest.identity.name,
+                    &self.manifest.identity.publisher,
+                    &self.manifest.identity.version,
+                    &self.manifest.identity.processor_architecture,
+                    &self.manifest.identity.resource_id,
+                )),
+                ..InstallationMetadata::default()
+            },
+            ..Installer::default()
+        }]
+    }
+}
+
+/// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-package>
+#[derive(Clone, Default)]
+pub struct Package {
+    identity: Identity,
+    properties: Properties,
+    dependencies: Dependencies,
+    capabilities: Capabilities,
+    file_type_association: FileTypeAssociation,
+    extensions: Extensions,
+    visual_elements: VisualElements,
+}
+
+impl Package {
+    /// Parses the parts of an `AppxManifest.xml` that are used to build an installer.
+    pub fn from_manifest(appx_manifest: &str) -> Result<Self> {
+        let mut manifest = Self::default();
 
-        let mut reader = Reader::from_str(&appx_manifest);
+        let mut reader = Reader::from_str(appx_manifest);
         let config = reader.config_mut();
         config.expand_empty_elements = true;
         config.trim_text(true);
@@ -85,6 +206,16 @@ pub fn new<R: Read + Seek>(reader: R) -> Result<Self> {
                         manifest.properties.display_name =
                             reader.read_text(event.to_end().name())?.into_owned();
                     }
+                    b"VisualElements" => {
+                        if let Some(attribute) = event
+                            .attributes()
+                            .flatten()
+                            .find(|attribute| attribute.key.as_ref() == b"Square44x44Logo")
+                        {
+                            manifest.visual_elements.square_44x44_logo =
+                                String::from_utf8_lossy(&attribute.value).into_owned();
+                        }
+                    }
                     b"PublisherDisplayName" => {
                         manifest.properties.publisher_display_name =
                             reader.read_text(event.to_end().name())?.into_owned();
@@ -149,6 +280,57 @@ pub fn new<R: Read + Seek>(reader: R) -> Result<Self> {
                             }
                         }
                     }
+                    b"PackageDependency" => {
+                        let mut name = None;
+                        let mut min_version = None;
+                        for attribute in event.attributes().flatten() {
+                            match attribute.key.as_ref() {
+                                b"Name" => {
+                                    name = Some(
+                                        String::from_utf8_lossy(&attribute.value).into_owned(),
+                                    );
+                                }
+                                b"MinVersion" => {
+                                    min_version = Some(
+                                        String::from_utf8_lossy(&attribute.value).into_owned(),
+                                    );
+                                }
+                                _ => {}
+                            }
+                        }
+                        if let Some(name) = name {
+                            manifest
+                                .dependencies
+                                .package_dependencies
+                                .insert(PackageDependency { name, min_version });
+                        }
+                    }
+                    b"Protocol" => {
+                        if let Some(attribute) = event
+                            .attributes()
+                            .flatten()
+                            .find(|attribute| attribute.key.as_ref() == b"Name")
+                            && let Ok(protocol) = std::str::from_utf8(&attribute.value)
+                            && let Ok(protocol) = protocol.parse::<Protocol>()
+                        {
+                            manifest.extensions.protocols.insert(protocol);
+                        }
+                    }
+                    b"ExecutionAlias" => {
+                        if let Some(attribute) = event
+                            .attributes()
+                            .flatten()
+                            .find(|attribute| attribute.key.as_ref() == b"Alias")
+                            && let Ok(alias) = std::str::from_utf8(&attribute.value)
+                        {
+                            // Aliases are declared as file names, but are run without the extension
+                            let alias = Utf8Path::new(alias).file_stem().unwrap_or(alias);
+
+                            if let Ok(command) = alias.parse::<Command>() {
+                                manifest.extensions.execution_aliases.insert(command);
+                            }
+                        }
+                    }
                     _ => {}
                 },
                 Event::Eof => break,
@@ -156,94 +338,10 @@ pub fn new<R: Read + Seek>(reader: R) -> Result<Self> {
             }
         }
 
-        Ok(Self {
-            appx_manifest,
-            signature_sha_256,
-            manifest,
-        })
+        Ok(manifest)
     }
 }
 
-impl Installers for Msix {
-    fn installers(&self) -> Vec<Installer> {
-        let is_appx = self
-            .manifest
-            .dependencies
-            .target_device_family
-            .iter()
-            .all(|target_device_family| target_device_family.min_version < MSIX_MIN_VERSION)
-            && !self.appx_manifest.contains_ignore_ascii_case(MSIX);
-
-        vec![Installer {
-            platform: self
-                .manifest
-                .dependencies
-                .target_device_family
-                .iter()
-                .map(|target_device_family| target_device_family.name)
-                .collect(),
-            minimum_os_version: self
-                .manifest
-                .dependencies
-                .target_device_family
-                .iter()
-                .map(|target_device_family| target_device_family.min_version)
-                .min(),
-            architecture: self
-                .manifest
-                .identity
-                .processor_architecture
-                .parse()
-                .unwrap_or(Architecture::X86),
-            r#type: if is_appx {
-                Some(InstallerType::Appx)
-            } else {
-                Some(InstallerType::Msix)
-            },
-            signature_sha_256: Some(self.signature_sha_256.clone()),
-            upgrade_behavior: Some(UpgradeBehavior::Install),
-            file_extensions: self
-                .manifest
-                .file_type_association
-                .supported_file_types
-                .clone(),
-            package_family_name: Some(PackageFamilyName::new(
-                self.manifest.identity.name.clone(),
-                &self.manifest.identity.publisher,
-            )),
-            capabilities: self.manifest.capabilities.unrestricted.clone(),
-            restricted_capabilities: self.manifest.capabilities.restricted.clone(),
-            apps_and_features_entries: AppsAndFeaturesEntry::builder()
-                .display_name(&self.manifest.properties.display_name)
-                .publisher(&self.manifest.properties.publisher_display_name)
-                .display_version(&self.manifest.identity.version)
-                .build()
-                .into(),
-            installation_metadata: InstallationMetadata {
-                default_install_location: Some(get_install_location(
-                    &self.manifest.identity.name,
-                    &self.manifest.identity.publisher,
-                    &self.manifest.identity.version,
-                    &self.manifest.identity.processor_architecture,
-                    &self.manifest.identity.resource_id,
-                )),
-                ..InstallationMetadata::default()
-            },
-            ..Installer::default()
-        }]
-    }
-}
-
-/// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-package>
-#[derive(Clone, Default)]
-pub struct Package {
-    identity: Identity,
-    properties: Properties,
-    dependencies: Dependencies,
-    capabilities: Capabilities,
-    file_type_association: FileTypeAssociation,
-}
-
 /// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-identity>
 #[derive(Clone, Default)]
 pub struct Identity {
@@ -261,10 +359,17 @@ pub struct Properties {
     publisher_display_name: String,
 }
 
+/// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-uap-visualelements>
+#[derive(Clone, Default)]
+pub struct VisualElements {
+    square_44x44_logo: String,
+}
+
 /// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-dependencies>
 #[derive(Clone, Default)]
 pub struct Dependencies {
     pub target_device_family: BTreeSet<TargetDeviceFamily>,
+    pub package_dependencies: BTreeSet<PackageDependency>,
 }
 
 /// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-targetdevicefamily>
@@ -274,6 +379,13 @@ pub struct TargetDeviceFamily {
     pub min_version: MinimumOSVersion,
 }
 
+/// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-packagedependency>
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PackageDependency {
+    pub name: String,
+    pub min_version: Option<String>,
+}
+
 /// <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-capabilities>
 #[derive(Clone, Default)]
 pub struct Capabilities {
@@ -286,3 +398,69 @@ pub struct Capabilities {
 pub struct FileTypeAssociation {
     supported_file_types: BTreeSet<FileExtension>,
 }
+
+/// The protocols and execution aliases declared by the package's application extensions.
+///
+/// See <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-uap-protocol>
+/// and <https://learn.microsoft.com/uwp/schemas/appxpackage/uapmanifestschema/element-uap5-appexecutionalias>.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    protocols: BTreeSet<Protocol>,
+    execution_aliases: BTreeSet<Command>,
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use winget_types::installer::{Command, Protocol};
+
+    use super::{Package, PackageDependency};
+
+    #[test]
+    fn parses_extensions_and_package_dependencies() {
+        const APPX_MANIFEST: &str = indoc! {r#"
+            <?xml version="1.0" encoding="utf-8"?>
+            <Package xmlns="http://schemas.microsoft.com/appx/manifest/foundation/windows10" xmlns:uap="http://schemas.microsoft.com/appx/manifest/uap/windows10" xmlns:uap3="http://schemas.microsoft.com/appx/manifest/uap/windows10/3" xmlns:uap5="http://schemas.microsoft.com/appx/manifest/uap/windows10/5">
+              <Identity Name="Example.App" Version="1.2.3.0" Publisher="CN=Example" ProcessorArchitecture="x64" />
+              <Dependencies>
+                <TargetDeviceFamily Name="Windows.Desktop" MinVersion="10.0.17763.0" MaxVersionTested="10.0.22621.0" />
+                <PackageDependency Name="Microsoft.VCLibs.140.00.UWPDesktop" MinVersion="14.0.30704.0" Publisher="CN=Microsoft Corporation, O=Microsoft Corporation, L=Redmond, S=Washington, C=US" />
+              </Dependencies>
+              <Applications>
+                <Application Id="App" Executable="Example.exe" EntryPoint="Windows.FullTrustApplication">
+                  <Extensions>
+                    <uap:Extension Category="windows.protocol">
+                      <uap:Protocol Name="example" />
+                    </uap:Extension>
+                    <uap3:Extension Category="windows.appExecutionAlias">
+                      <uap3:AppExecutionAlias>
+                        <uap5:ExecutionAlias Alias="example.exe" />
+                      </uap3:AppExecutionAlias>
+                    </uap3:Extension>
+                  </Extensions>
+                </Application>
+              </Applications>
+            </Package>
+        "#};
+
+        let package = Package::from_manifest(APPX_MANIFEST).unwrap();
+
+        assert_eq!(
+            package.extensions.protocols,
+            ["example".parse::<Protocol>().unwrap()].into()
+        );
+        assert_eq!(
+            package.extensions.execution_aliases,
+            ["example".parse::<Command>().unwrap()].into()
+        );
+        assert!(
+            package
+                .dependencies
+                .package_dependencies
+                .contains(&PackageDependency {
+                    name: "Microsoft.VCLibs.140.00.UWPDesktop".to_owned(),
+                    min_version: Some("14.0.30704.0".to_owned()),
+                })
+        );
+    }
+}
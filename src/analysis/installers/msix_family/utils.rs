@@ -3,12 +3,15 @@
     io::{Read, Seek},
 };
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use color_eyre::eyre::Result;
-use winget_types::{Sha256String, package_family_name::PublisherId};
+use winget_types::{
+    PackageIdentifier, PackageVersion, Sha256String, installer::PackageDependencies,
+    package_family_name::PublisherId,
+};
 use zip::ZipArchive;
 
-use crate::analysis::installers::utils::RELATIVE_PROGRAM_FILES_64;
+use crate::analysis::{icon::Icon, installers::utils::RELATIVE_PROGRAM_FILES_64};
 
 pub fn read_manifest<R: Read + Seek>(zip: &mut ZipArchive<R>, path: &str) -> Result<String> {
     let mut appx_manifest_file = zip.by_name(path)?;
@@ -24,6 +27,43 @@ pub fn hash_signature<R: Read + Seek>(zip: &mut ZipArchive<R>) -> io::Result<Sha
     Sha256String::hash_from_reader(signature_file)
 }
 
+/// Reads the largest scaled variant of a logo that the package manifest refers to.
+///
+/// A logo such as `Assets\Square44x44Logo.png` is usually only present as qualified variants like
+/// `Assets/Square44x44Logo.scale-200.png` or `Assets/Square44x44Logo.targetsize-256.png`, so the
+/// largest file that shares the logo's directory, stem and extension is chosen.
+pub fn read_logo<R: Read + Seek>(zip: &mut ZipArchive<R>, logo: &str) -> Option<Icon> {
+    let logo = logo.replace('\\', "/");
+    let logo = Utf8Path::new(&logo);
+    let (stem, extension) = (logo.file_stem()?, logo.extension()?);
+
+    let is_variant = |path: &Utf8Path| {
+        path.parent() == logo.parent()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+            && path.file_stem().is_some_and(|file_stem| {
+                file_stem
+                    .get(..stem.len())
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(stem))
+                    && matches!(file_stem.as_bytes().get(stem.len()), None | Some(b'.'))
+            })
+    };
+
+    let name = zip
+        .file_names()
+        .filter(|name| is_variant(Utf8Path::new(name)))
+        .map(str::to_owned)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .max_by_key(|name| zip.by_name(name).map_or(0, |file| file.size()))?;
+
+    let mut file = zip.by_name(&name).ok()?;
+    let mut data = Vec::with_capacity(usize::try_from(file.size()).ok()?);
+    file.read_to_end(&mut data).ok()?;
+    Icon::from_png(data)
+}
+
 pub fn get_install_location(
     name: &str,
     publisher: &str,
@@ -41,3 +81,93 @@ pub fn get_install_location(
     ));
     path
 }
+
+/// Maps an MSIX framework package dependency to the package that provides it in winget-pkgs.
+///
+/// Only frameworks that are known to be in winget-pkgs are mapped, as an unknown dependency would
+/// fail validation.
+pub fn package_dependency(name: &str, min_version: Option<&str>) -> Option<PackageDependencies> {
+    const VCLIBS_DESKTOP: &str = "Microsoft.VCLibs.140.00.UWPDesktop";
+    const UI_XAML: &str = "Microsoft.UI.Xaml.";
+    const WINDOWS_APP_RUNTIME: &str = "Microsoft.WindowsAppRuntime.";
+    const NET_NATIVE_FRAMEWORK: &str = "Microsoft.NET.Native.Framework.";
+    const NET_NATIVE_RUNTIME: &str = "Microsoft.NET.Native.Runtime.";
+
+    let (identifier, keep_version) = if name.eq_ignore_ascii_case(VCLIBS_DESKTOP) {
+        ("Microsoft.VCLibs.Desktop.14", true)
+    } else if starts_with_ignore_ascii_case(name, UI_XAML)
+        || starts_with_ignore_ascii_case(name, NET_NATIVE_FRAMEWORK)
+        || starts_with_ignore_ascii_case(name, NET_NATIVE_RUNTIME)
+    {
+        (name, true)
+    } else if starts_with_ignore_ascii_case(name, WINDOWS_APP_RUNTIME) {
+        // The framework package's version (such as 5001.95.533.0) does not correspond to the
+        // versions of the Windows App Runtime in winget-pkgs (such as 1.5.240311000)
+        (name, false)
+    } else {
+        return None;
+    };
+
+    Some(PackageDependencies {
+        package_identifier: identifier.parse::<PackageIdentifier>().ok()?,
+        minimum_version: min_version
+            .filter(|_| keep_version)
+            .and_then(|version| version.parse::<PackageVersion>().ok()),
+    })
+}
+
+/// Package family names are case-insensitive, so framework prefixes are matched regardless of case.
+fn starts_with_ignore_ascii_case(name: &str, prefix: &str) -> bool {
+    name.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winget_types::PackageVersion;
+
+    use super::package_dependency;
+
+    #[rstest]
+    #[case(
+        "Microsoft.VCLibs.140.00.UWPDesktop",
+        Some("14.0.30704.0"),
+        Some(("Microsoft.VCLibs.Desktop.14", Some("14.0.30704.0")))
+    )]
+    #[case(
+        "Microsoft.UI.Xaml.2.8",
+        Some("8.2310.30001.0"),
+        Some(("Microsoft.UI.Xaml.2.8", Some("8.2310.30001.0")))
+    )]
+    #[case(
+        "Microsoft.WindowsAppRuntime.1.5",
+        Some("5001.95.533.0"),
+        Some(("Microsoft.WindowsAppRuntime.1.5", None))
+    )]
+    #[case(
+        "microsoft.ui.xaml.2.8",
+        Some("8.2310.30001.0"),
+        Some(("microsoft.ui.xaml.2.8", Some("8.2310.30001.0")))
+    )]
+    #[case("Microsoft.VCLibs.140.00", Some("14.0.30704.0"), None)]
+    #[case("Contoso.Framework", None, None)]
+    fn msix_package_dependency(
+        #[case] name: &str,
+        #[case] min_version: Option<&str>,
+        #[case] expected: Option<(&str, Option<&str>)>,
+    ) {
+        let dependency = package_dependency(name, min_version);
+
+        assert_eq!(
+            dependency.as_ref().map(|dependency| (
+                dependency.package_identifier.as_str(),
+                dependency
+                    .minimum_version
+                    .as_ref()
+                    .map(PackageVersion::as_str)
+            )),
+            expected
+        );
+    }
+}
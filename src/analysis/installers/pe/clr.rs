@@ -0,0 +1,264 @@
+use std::{
+    fmt, io,
+    io::{Read, Seek, SeekFrom},
+    str::FromStr,
+};
+
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U16, U32};
+
+use super::{PE, optional_header::DataDirectory};
+use crate::read::ReadBytesExt;
+
+/// The CLR runtime header of a .NET assembly.
+///
+/// See <https://learn.microsoft.com/windows/win32/api/corhdr/ns-corhdr-image_cor20_header>.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+pub struct Cor20Header {
+    cb: U32<LittleEndian>,
+    major_runtime_version: U16<LittleEndian>,
+    minor_runtime_version: U16<LittleEndian>,
+    metadata: DataDirectory,
+    flags: U32<LittleEndian>,
+    entry_point: U32<LittleEndian>,
+    resources: DataDirectory,
+    strong_name_signature: DataDirectory,
+    code_manager_table: DataDirectory,
+    vtable_fixups: DataDirectory,
+    export_address_table_jumps: DataDirectory,
+    managed_native_header: DataDirectory,
+}
+
+impl Cor20Header {
+    #[inline]
+    pub const fn metadata(&self) -> DataDirectory {
+        self.metadata
+    }
+}
+
+/// Information read from the metadata of a .NET assembly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClrMetadata {
+    /// The version of the runtime that the assembly was built against, such as `v4.0.30319`.
+    pub runtime_version: String,
+    /// The framework from the assembly's `TargetFrameworkAttribute`, if it has one.
+    pub target_framework: Option<TargetFramework>,
+}
+
+impl ClrMetadata {
+    /// The signature at the start of the metadata root.
+    const SIGNATURE: u32 = u32::from_le_bytes(*b"BSJB");
+
+    /// Guards against reading an unreasonable amount of metadata from a malformed header.
+    const MAX_SIZE: u32 = 1 << 24;
+
+    fn read_from(metadata: &[u8]) -> io::Result<Self> {
+        let mut reader = metadata;
+
+        if reader.read_u32::<LittleEndian>()? != Self::SIGNATURE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid CLR metadata signature",
+            ));
+        }
+
+        // Skip the major version, minor version and reserved fields
+        let mut skipped = [0; size_of::<u16>() * 2 + size_of::<u32>()];
+        reader.read_exact(&mut skipped)?;
+
+        let version_length = reader.read_u32::<LittleEndian>()? as usize;
+        let version = reader.get(..version_length).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "CLR metadata version too long",
+            )
+        })?;
+        let runtime_version = String::from_utf8_lossy(version)
+            .trim_end_matches('\0')
+            .to_owned();
+
+        Ok(Self {
+            runtime_version,
+            target_framework: TargetFramework::find(metadata),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FrameworkIdentifier {
+    NetFramework,
+    NetCoreApp,
+    NetStandard,
+}
+
+impl FrameworkIdentifier {
+    const ALL: [Self; 3] = [Self::NetFramework, Self::NetCoreApp, Self::NetStandard];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NetFramework => ".NETFramework",
+            Self::NetCoreApp => ".NETCoreApp",
+            Self::NetStandard => ".NETStandard",
+        }
+    }
+}
+
+/// A target framework moniker, such as `.NETCoreApp,Version=v8.0`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TargetFramework {
+    pub identifier: FrameworkIdentifier,
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl TargetFramework {
+    const VERSION_SEPARATOR: &'static str = ",Version=v";
+
+    /// Finds the first target framework moniker in the metadata.
+    ///
+    /// The moniker is stored as the serialized argument of the `TargetFrameworkAttribute` in the
+    /// blob heap, so searching for it avoids having to decode the metadata tables.
+    fn find(metadata: &[u8]) -> Option<Self> {
+        FrameworkIdentifier::ALL.into_iter().find_map(|identifier| {
+            let prefix = format!("{}{}", identifier.as_str(), Self::VERSION_SEPARATOR);
+            let start = metadata
+                .windows(prefix.len())
+                .position(|window| window == prefix.as_bytes())?;
+            let moniker = &metadata[start..];
+            let end = moniker
+                .iter()
+                .skip(prefix.len())
+                .position(|&byte| !(byte.is_ascii_digit() || byte == b'.'))
+                .map_or(moniker.len(), |end| prefix.len() + end);
+            std::str::from_utf8(&moniker[..end]).ok()?.parse().ok()
+        })
+    }
+}
+
+impl FromStr for TargetFramework {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid framework: {s}"),
+            )
+        };
+
+        let (identifier, version) = s.split_once(Self::VERSION_SEPARATOR).ok_or_else(invalid)?;
+        let identifier = FrameworkIdentifier::ALL
+            .into_iter()
+            .find(|framework| framework.as_str() == identifier)
+            .ok_or_else(invalid)?;
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        let minor = minor.split('.').next().unwrap_or_default();
+
+        Ok(Self {
+            identifier,
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for TargetFramework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}.{}",
+            self.identifier.as_str(),
+            Self::VERSION_SEPARATOR,
+            self.major,
+            self.minor
+        )
+    }
+}
+
+impl PE {
+    /// Reads the CLR runtime header and metadata if the PE is a .NET assembly.
+    ///
+    /// Returns `None` if the PE has no CLR runtime header.
+    pub fn clr_metadata<R>(&self, mut reader: R) -> io::Result<Option<ClrMetadata>>
+    where
+        R: Read + Seek,
+    {
+        let Some(clr_runtime_header) = self.optional_header.data_directories.clr_runtime_header()
+        else {
+            return Ok(None);
+        };
+
+        reader.seek(SeekFrom::Start(
+            clr_runtime_header.file_offset(&self.section_table)?.into(),
+        ))?;
+        let header = reader.read_t::<Cor20Header>()?;
+
+        let metadata_directory = header.metadata();
+        reader.seek(SeekFrom::Start(
+            metadata_directory.file_offset(&self.section_table)?.into(),
+        ))?;
+
+        let mut metadata = Vec::new();
+        reader
+            .take(metadata_directory.size().min(ClrMetadata::MAX_SIZE).into())
+            .read_to_end(&mut metadata)?;
+
+        ClrMetadata::read_from(&metadata).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{ClrMetadata, Cor20Header, FrameworkIdentifier, TargetFramework};
+
+    #[test]
+    fn size() {
+        assert_eq!(size_of::<Cor20Header>(), 72);
+    }
+
+    #[rstest]
+    #[case(".NETCoreApp,Version=v8.0", FrameworkIdentifier::NetCoreApp, 8, 0)]
+    #[case(
+        ".NETFramework,Version=v4.7.2",
+        FrameworkIdentifier::NetFramework,
+        4,
+        7
+    )]
+    #[case(".NETStandard,Version=v2.1", FrameworkIdentifier::NetStandard, 2, 1)]
+    fn target_framework(
+        #[case] moniker: &str,
+        #[case] identifier: FrameworkIdentifier,
+        #[case] major: u16,
+        #[case] minor: u16,
+    ) {
+        assert_eq!(
+            moniker.parse::<TargetFramework>().unwrap(),
+            TargetFramework {
+                identifier,
+                major,
+                minor
+            }
+        );
+    }
+
+    #[test]
+    fn metadata() {
+        let mut metadata =
+            b"BSJB\x01\x00\x01\x00\x00\x00\x00\x00\x0c\x00\x00\x00v4.0.30319\0\0".to_vec();
+        metadata.extend_from_slice(b"\x00\x01\x00\x1a.NETFramework,Version=v4.8\x01\x00T");
+
+        let metadata = ClrMetadata::read_from(&metadata).unwrap();
+
+        assert_eq!(metadata.runtime_version, "v4.0.30319");
+        assert_eq!(
+            metadata.target_framework,
+            Some(TargetFramework {
+                identifier: FrameworkIdentifier::NetFramework,
+                major: 4,
+                minor: 8
+            })
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U16, U32};
+
+use super::{
+    PE,
+    resource::{IdOrName, Resource, ResourceType},
+};
+use crate::{
+    analysis::icon::{Icon, IconFileType, is_png},
+    read::ReadBytesExt,
+};
+
+/// The header of an icon directory, shared by `.ico` files and `RT_GROUP_ICON` resources.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+pub struct IconDirectory {
+    reserved: U16<LittleEndian>,
+    r#type: U16<LittleEndian>,
+    count: U16<LittleEndian>,
+}
+
+impl IconDirectory {
+    const ICON_TYPE: u16 = 1;
+
+    const fn single() -> Self {
+        Self {
+            reserved: U16::ZERO,
+            r#type: U16::new(Self::ICON_TYPE),
+            count: U16::new(1),
+        }
+    }
+
+    #[inline]
+    pub const fn count(&self) -> u16 {
+        self.count.get()
+    }
+}
+
+/// An entry in an `RT_GROUP_ICON` resource, referring to an `RT_ICON` resource by its ID.
+///
+/// See <https://devblogs.microsoft.com/oldnewthing/20120720-00/?p=7083>.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+pub struct GroupIconDirectoryEntry {
+    width: u8,
+    height: u8,
+    color_count: u8,
+    reserved: u8,
+    planes: U16<LittleEndian>,
+    bit_count: U16<LittleEndian>,
+    bytes_in_res: U32<LittleEndian>,
+    id: U16<LittleEndian>,
+}
+
+impl GroupIconDirectoryEntry {
+    /// Returns the width of the image, where a stored width of 0 means 256 pixels.
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        match self.width {
+            0 => 256,
+            width => width as u32,
+        }
+    }
+
+    /// Returns the height of the image, where a stored height of 0 means 256 pixels.
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        match self.height {
+            0 => 256,
+            height => height as u32,
+        }
+    }
+
+    #[inline]
+    pub const fn id(&self) -> u16 {
+        self.id.get()
+    }
+
+    /// Orders entries by their size and then by their colour depth.
+    fn sort_key(&self) -> (u32, u16) {
+        (self.width() * self.height(), self.bit_count.get())
+    }
+}
+
+/// An entry in the directory of an `.ico` file, referring to image data by its file offset.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+struct IconDirectoryEntry {
+    width: u8,
+    height: u8,
+    color_count: u8,
+    reserved: u8,
+    planes: U16<LittleEndian>,
+    bit_count: U16<LittleEndian>,
+    bytes_in_res: U32<LittleEndian>,
+    image_offset: U32<LittleEndian>,
+}
+
+/// Assembles a single image `.ico` file from a group icon entry and its `RT_ICON` data.
+fn assemble_ico(entry: &GroupIconDirectoryEntry, image: &[u8]) -> Vec<u8> {
+    let header = IconDirectory::single();
+    let directory_entry = IconDirectoryEntry {
+        width: entry.width,
+        height: entry.height,
+        color_count: entry.color_count,
+        reserved: 0,
+        planes: entry.planes,
+        bit_count: entry.bit_count,
+        bytes_in_res: U32::new(image.len() as u32),
+        image_offset: U32::new(
+            (size_of::<IconDirectory>() + size_of::<IconDirectoryEntry>()) as u32,
+        ),
+    };
+
+    let mut ico = Vec::with_capacity(
+        size_of::<IconDirectory>() + size_of::<IconDirectoryEntry>() + image.len(),
+    );
+    ico.extend_from_slice(header.as_bytes());
+    ico.extend_from_slice(directory_entry.as_bytes());
+    ico.extend_from_slice(image);
+    ico
+}
+
+impl PE {
+    /// Guards against reading an unreasonable amount of data from a malformed icon resource.
+    const MAX_ICON_SIZE: u32 = 1 << 24;
+
+    /// Extracts the largest image from the first icon group in the PE's resources.
+    ///
+    /// Images stored as PNG are returned as they are. Bitmap images are reassembled into an
+    /// `.ico` file by prepending an icon directory to the `RT_ICON` data.
+    ///
+    /// Returns `None` if the PE has no icon group.
+    pub fn largest_icon<R>(&self, mut reader: R) -> io::Result<Option<Icon>>
+    where
+        R: Read + Seek,
+    {
+        let resources = self.resources(&mut reader)?.collect::<Vec<_>>();
+
+        let is_type = |resource: &&Resource, resource_type: ResourceType| {
+            *resource.id_or_name() == IdOrName::Id(resource_type.id())
+        };
+
+        let Some(group_icon) = resources
+            .iter()
+            .find(|resource| is_type(resource, ResourceType::GroupIcon))
+        else {
+            return Ok(None);
+        };
+
+        let group = self.read_resource(&mut reader, group_icon)?;
+        let mut group = group.as_slice();
+        let directory = group.read_t::<IconDirectory>()?;
+
+        let mut entries = Vec::with_capacity(directory.count().into());
+        for _ in 0..directory.count() {
+            entries.push(group.read_t::<GroupIconDirectoryEntry>()?);
+        }
+
+        let Some(entry) = entries.iter().max_by_key(|entry| entry.sort_key()) else {
+            return Ok(None);
+        };
+
+        let Some(icon) = resources.iter().find(|resource| {
+            is_type(resource, ResourceType::Icon) && resource.name_id() == u32::from(entry.id())
+        }) else {
+            return Ok(None);
+        };
+
+        let image = self.read_resource(&mut reader, icon)?;
+
+        Ok(Some(if is_png(&image) {
+            Icon::from_png(image).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Invalid PNG icon header")
+            })?
+        } else {
+            Icon::new(
+                IconFileType::Ico,
+                entry.width(),
+                entry.height(),
+                assemble_ico(entry, &image),
+            )
+        }))
+    }
+
+    fn read_resource<R>(&self, mut reader: R, resource: &Resource) -> io::Result<Vec<u8>>
+    where
+        R: Read + Seek,
+    {
+        reader.seek(SeekFrom::Start(
+            self.section_table
+                .to_file_offset(resource.offset_to_data())?
+                .into(),
+        ))?;
+
+        let mut data = Vec::new();
+        reader
+            .take(resource.size().min(Self::MAX_ICON_SIZE).into())
+            .read_to_end(&mut data)?;
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zerocopy::FromBytes;
+
+    use super::{GroupIconDirectoryEntry, IconDirectory, IconDirectoryEntry, assemble_ico};
+
+    #[test]
+    fn sizes() {
+        assert_eq!(size_of::<IconDirectory>(), 6);
+        assert_eq!(size_of::<GroupIconDirectoryEntry>(), 14);
+        assert_eq!(size_of::<IconDirectoryEntry>(), 16);
+    }
+
+    #[test]
+    fn ico() {
+        let entry = GroupIconDirectoryEntry::read_from_bytes(
+            b"\x00\x00\x00\x00\x01\x00\x20\x00\x04\x00\x00\x00\x01\x00",
+        )
+        .unwrap();
+        let ico = assemble_ico(&entry, b"data");
+
+        assert_eq!(entry.width(), 256);
+        assert_eq!(
+            ico,
+            b"\x00\x00\x01\x00\x01\x00\
+              \x00\x00\x00\x00\x01\x00\x20\x00\x04\x00\x00\x00\x16\x00\x00\x00\
+              data"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+
+use zerocopy::{FromBytes, FromZeros, Immutable, IntoBytes, KnownLayout, LittleEndian, U32};
+
+use super::{PE, SectionTable, optional_header::StandardFields};
+use crate::read::ReadBytesExt;
+
+/// An entry in the import directory table, describing the imports from a single DLL.
+///
+/// See <https://learn.microsoft.com/windows/win32/debug/pe-format#import-directory-table>.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+pub struct ImportDescriptor {
+    import_lookup_table: U32<LittleEndian>,
+    time_date_stamp: U32<LittleEndian>,
+    forwarder_chain: U32<LittleEndian>,
+    name: U32<LittleEndian>,
+    import_address_table: U32<LittleEndian>,
+}
+
+impl ImportDescriptor {
+    /// Returns the RVA of the import lookup table, falling back to the import address table for
+    /// binaries produced by linkers that leave the lookup table empty.
+    #[inline]
+    pub const fn lookup_table(&self) -> u32 {
+        match self.import_lookup_table.get() {
+            0 => self.import_address_table.get(),
+            lookup_table => lookup_table,
+        }
+    }
+
+    /// Returns the RVA of the ASCII name of the DLL.
+    #[inline]
+    pub const fn name(&self) -> u32 {
+        self.name.get()
+    }
+
+    /// Returns `true` if this is the null descriptor that terminates the import directory table.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        *self == Self::new_zeroed()
+    }
+}
+
+/// The functions imported from a single DLL. Functions imported by ordinal are not included.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Import {
+    pub dll: String,
+    pub functions: Vec<String>,
+}
+
+impl Import {
+    /// Returns `true` if this import is from the given DLL, ignoring ASCII case.
+    pub fn is_dll(&self, dll: &str) -> bool {
+        self.dll.eq_ignore_ascii_case(dll)
+    }
+
+    /// Returns `true` if any imported function starts with the given prefix. This allows matching
+    /// both the ANSI and wide variants of a function, such as `ShellExecuteA` and `ShellExecuteW`.
+    pub fn imports(&self, function_prefix: &str) -> bool {
+        self.functions
+            .iter()
+            .any(|function| function.starts_with(function_prefix))
+    }
+}
+
+impl PE {
+    /// Guards against malformed import tables that are never terminated.
+    const MAX_IMPORT_DESCRIPTORS: usize = 1 << 10;
+    const MAX_IMPORTED_FUNCTIONS: usize = 1 << 14;
+
+    /// Reads the DLLs and functions that the PE imports.
+    ///
+    /// Returns an empty list if the PE has no import table.
+    pub fn imports<R>(&self, mut reader: R) -> io::Result<Vec<Import>>
+    where
+        R: Read + Seek,
+    {
+        let Some(import_table) = self.optional_header.data_directories.import_table() else {
+            return Ok(Vec::new());
+        };
+
+        reader.seek(SeekFrom::Start(
+            import_table.file_offset(&self.section_table)?.into(),
+        ))?;
+
+        let mut descriptors = Vec::new();
+        while descriptors.len() < Self::MAX_IMPORT_DESCRIPTORS {
+            let descriptor = reader.read_t::<ImportDescriptor>()?;
+            if descriptor.is_null() {
+                break;
+            }
+            descriptors.push(descriptor);
+        }
+
+        let is_64_bit = matches!(
+            self.optional_header.standard_fields,
+            StandardFields::PE64(_)
+        );
+
+        descriptors
+            .into_iter()
+            .map(|descriptor| {
+                Ok(Import {
+                    dll: read_c_string(&mut reader, &self.section_table, descriptor.name())?,
+                    functions: self.imported_functions(
+                        &mut reader,
+                        descriptor.lookup_table(),
+                        is_64_bit,
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    fn imported_functions<R>(
+        &self,
+        mut reader: R,
+        lookup_table: u32,
+        is_64_bit: bool,
+    ) -> io::Result<Vec<String>>
+    where
+        R: Read + Seek,
+    {
+        const ORDINAL_FLAG_32: u32 = 1 << 31;
+        const ORDINAL_FLAG_64: u64 = 1 << 63;
+
+        let mut offset = u64::from(self.section_table.to_file_offset(lookup_table)?);
+
+        let mut hint_name_rvas = Vec::new();
+        while hint_name_rvas.len() < Self::MAX_IMPORTED_FUNCTIONS {
+            reader.seek(SeekFrom::Start(offset))?;
+
+            let entry = if is_64_bit {
+                let mut entry = [0; size_of::<u64>()];
+                reader.read_exact(&mut entry)?;
+                offset += size_of::<u64>() as u64;
+                u64::from_le_bytes(entry)
+            } else {
+                offset += size_of::<u32>() as u64;
+                u64::from(reader.read_u32::<LittleEndian>()?)
+            };
+
+            if entry == 0 {
+                break;
+            }
+
+            let by_ordinal = if is_64_bit {
+                entry & ORDINAL_FLAG_64 != 0
+            } else {
+                entry & u64::from(ORDINAL_FLAG_32) != 0
+            };
+
+            if !by_ordinal {
+                // The lower 31 bits are the RVA of a hint/name table entry
+                hint_name_rvas.push((entry & u64::from(!ORDINAL_FLAG_32)) as u32);
+            }
+        }
+
+        hint_name_rvas
+            .into_iter()
+            // Skip the 2-byte hint that precedes the name
+            .map(|rva| read_c_string(&mut reader, &self.section_table, rva + 2))
+            .collect()
+    }
+}
+
+/// Reads a null-terminated ASCII string at the given RVA.
+fn read_c_string<R>(mut reader: R, section_table: &SectionTable, rva: u32) -> io::Result<String>
+where
+    R: Read + Seek,
+{
+    const MAX_LEN: u64 = 1 << 9;
+
+    reader.seek(SeekFrom::Start(section_table.to_file_offset(rva)?.into()))?;
+
+    let mut bytes = Vec::new();
+    BufReader::new(reader.take(MAX_LEN)).read_until(b'\0', &mut bytes)?;
+    if bytes.last() == Some(&b'\0') {
+        bytes.pop();
+    }
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImportDescriptor;
+
+    #[test]
+    fn size() {
+        assert_eq!(size_of::<ImportDescriptor>(), 20);
+    }
+}
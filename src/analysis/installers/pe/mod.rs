@@ -1,7 +1,10 @@
 #![expect(unused)]
 
+pub mod clr;
 mod coff;
 pub mod dos;
+pub mod icon;
+mod imports;
 pub mod optional_header;
 pub mod resource;
 mod section_table;
@@ -16,6 +19,7 @@
 
 pub use coff::CoffHeader;
 pub use dos::DosHeader;
+pub use imports::{Import, ImportDescriptor};
 pub use optional_header::OptionalHeader;
 pub use section_table::{SectionHeader, SectionTable};
 pub use signature::Signature;
@@ -111,6 +115,22 @@ pub fn overlay_offset(&self) -> Option<u64> {
             .max()
     }
 
+    /// Reads up to `len` bytes from the start of the data appended after the PE sections. The
+    /// returned bytes are empty if there is no overlay.
+    pub fn overlay_prefix<R>(&self, mut reader: R, len: usize) -> io::Result<Vec<u8>>
+    where
+        R: Read + Seek,
+    {
+        let Some(overlay_offset) = self.overlay_offset() else {
+            return Ok(Vec::new());
+        };
+
+        reader.seek(SeekFrom::Start(overlay_offset))?;
+        let mut prefix = Vec::with_capacity(len);
+        reader.take(len as u64).read_to_end(&mut prefix)?;
+        Ok(prefix)
+    }
+
     #[inline]
     pub fn resource_table(&self) -> Option<&DataDirectory> {
         self.optional_header.data_directories.resource_table()
@@ -43,6 +43,11 @@ pub const fn name(&self) -> Option<&str> {
         }
     }
 
+    #[inline]
+    pub const fn name_id(&self) -> u32 {
+        self.name_id
+    }
+
     #[inline]
     pub const fn offset_to_data(&self) -> u32 {
         self.entry.offset_to_data()
@@ -0,0 +1,167 @@
+use const_format::formatcp;
+use serde::Deserialize;
+use winget_types::installer::{Architecture, Scope};
+
+use crate::analysis::installers::utils::{
+    RELATIVE_LOCAL_APP_DATA, RELATIVE_PROGRAM_FILES_32, RELATIVE_PROGRAM_FILES_64,
+    RELATIVE_SYSTEM_DRIVE, RELATIVE_USER_PROFILE,
+};
+
+/// The installer configuration file that is passed to `binarycreator`.
+///
+/// See <https://doc.qt.io/qtinstallerframework/ifw-globalconfig.html>.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename = "Installer", rename_all = "PascalCase")]
+pub struct Config {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub title: Option<String>,
+    pub publisher: Option<String>,
+    pub product_url: Option<String>,
+    pub target_dir: Option<String>,
+}
+
+impl Config {
+    pub const FILE_NAME: &'static str = "config.xml";
+
+    const HOME_DIR: &'static str = "@HomeDir@";
+    const ROOT_DIR: &'static str = "@RootDir@";
+    const APPLICATIONS_DIR: &'static str = "@ApplicationsDir@";
+    const APPLICATIONS_DIR_USER: &'static str = "@ApplicationsDirUser@";
+    const APPLICATIONS_DIR_X86: &'static str = "@ApplicationsDirX86@";
+    const APPLICATIONS_DIR_X64: &'static str = "@ApplicationsDirX64@";
+
+    /// The per-user applications directory that `@ApplicationsDirUser@` expands to on Windows.
+    const RELATIVE_USER_APPLICATIONS_DIR: &'static str =
+        formatcp!(r"{RELATIVE_LOCAL_APP_DATA}\Programs");
+
+    /// Resolves the `TargetDir` into a relative install location, replacing the predefined
+    /// variables that IFW expands at install time.
+    ///
+    /// See <https://doc.qt.io/qtinstallerframework/scripting.html#predefined-variables>.
+    pub fn install_location(&self, architecture: Architecture) -> Option<String> {
+        let applications_dir = if architecture == Architecture::X86 {
+            RELATIVE_PROGRAM_FILES_32
+        } else {
+            RELATIVE_PROGRAM_FILES_64
+        };
+
+        let mut target_dir = self.target_dir.as_deref()?.trim().to_owned();
+
+        for (variable, value) in [
+            (
+                Self::APPLICATIONS_DIR_USER,
+                Self::RELATIVE_USER_APPLICATIONS_DIR,
+            ),
+            (Self::APPLICATIONS_DIR_X86, RELATIVE_PROGRAM_FILES_32),
+            (Self::APPLICATIONS_DIR_X64, RELATIVE_PROGRAM_FILES_64),
+            (Self::APPLICATIONS_DIR, applications_dir),
+            (Self::HOME_DIR, RELATIVE_USER_PROFILE),
+            (Self::ROOT_DIR, RELATIVE_SYSTEM_DRIVE),
+            ("@ProductName@", self.name.as_deref().unwrap_or_default()),
+            ("@Name@", self.name.as_deref().unwrap_or_default()),
+            ("@Version@", self.version.as_deref().unwrap_or_default()),
+            (
+                "@ProductVersion@",
+                self.version.as_deref().unwrap_or_default(),
+            ),
+            ("@Title@", self.title.as_deref().unwrap_or_default()),
+            ("@Publisher@", self.publisher.as_deref().unwrap_or_default()),
+        ] {
+            target_dir = target_dir.replace(variable, value);
+        }
+
+        // Any variable that remains can't be resolved without running the installer
+        (!target_dir.is_empty() && !target_dir.contains('@')).then(|| target_dir.replace('/', r"\"))
+    }
+
+    /// Returns the scope implied by the `TargetDir`. Installing into the user's home directory or
+    /// the per-user applications directory doesn't require elevation, whereas the other
+    /// applications directories do.
+    pub fn scope(&self) -> Option<Scope> {
+        let target_dir = self.target_dir.as_deref()?.trim();
+
+        if [Self::HOME_DIR, Self::APPLICATIONS_DIR_USER]
+            .iter()
+            .any(|variable| target_dir.starts_with(variable))
+        {
+            Some(Scope::User)
+        } else if [
+            Self::APPLICATIONS_DIR,
+            Self::APPLICATIONS_DIR_X86,
+            Self::APPLICATIONS_DIR_X64,
+            Self::ROOT_DIR,
+        ]
+        .iter()
+        .any(|variable| target_dir.starts_with(variable))
+        {
+            Some(Scope::Machine)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use winget_types::installer::{Architecture, Scope};
+
+    use super::Config;
+
+    #[test]
+    fn deserialize() {
+        let config = quick_xml::de::from_str::<Config>(indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <Installer>
+                <Name>Example App</Name>
+                <Version>1.2.3</Version>
+                <Title>Example App Installer</Title>
+                <Publisher>Example Corp</Publisher>
+                <ProductUrl>https://example.com</ProductUrl>
+                <StartMenuDir>Example</StartMenuDir>
+                <TargetDir>@ApplicationsDir@/@Publisher@/@Name@</TargetDir>
+            </Installer>
+        "#})
+        .unwrap();
+
+        assert_eq!(config.publisher.as_deref(), Some("Example Corp"));
+        assert_eq!(config.product_url.as_deref(), Some("https://example.com"));
+        assert_eq!(
+            config.install_location(Architecture::X64).as_deref(),
+            Some(r"%ProgramFiles%\Example Corp\Example App")
+        );
+        assert_eq!(config.scope(), Some(Scope::Machine));
+    }
+
+    #[rstest]
+    #[case("@HomeDir@/Example", Some(r"%UserProfile%\Example"), Some(Scope::User))]
+    #[case(
+        "@ApplicationsDirX86@/Example",
+        Some(r"%ProgramFiles(x86)%\Example"),
+        Some(Scope::Machine)
+    )]
+    #[case(
+        "@ApplicationsDirUser@/Example",
+        Some(r"%LocalAppData%\Programs\Example"),
+        Some(Scope::User)
+    )]
+    #[case("@DesktopDir@/Example", None, None)]
+    fn target_dir(
+        #[case] target_dir: &str,
+        #[case] install_location: Option<&str>,
+        #[case] scope: Option<Scope>,
+    ) {
+        let config = Config {
+            target_dir: Some(target_dir.to_owned()),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.install_location(Architecture::X64).as_deref(),
+            install_location
+        );
+        assert_eq!(config.scope(), scope);
+    }
+}
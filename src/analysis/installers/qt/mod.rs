@@ -1,23 +1,28 @@
+mod config;
+mod resource;
+
 use std::{
     collections::BTreeSet,
-    io::{self, Read, Seek, SeekFrom},
+    io::{self, Read, Seek},
 };
 
+use camino::Utf8PathBuf;
+use config::Config;
+use resource::QtResource;
 use serde::Deserialize;
 use thiserror::Error;
+use tracing::debug;
 use winget_types::{
     Version,
     installer::{
         AppsAndFeaturesEntries, AppsAndFeaturesEntry, Architecture, ExpectedReturnCodes,
-        InstallModes, Installer, InstallerReturnCode, InstallerSwitches, InstallerType,
-        ReturnResponse,
+        InstallModes, InstallationMetadata, Installer, InstallerReturnCode, InstallerSwitches,
+        InstallerType, ReturnResponse,
     },
 };
-use zerocopy::byteorder::BigEndian;
 
 use crate::{
     analysis::{Installers, installers::pe::PE},
-    read::ReadBytesExt,
     traits::FromMachine,
 };
 
@@ -51,46 +56,72 @@ struct PackageUpdate {
 pub struct Qt {
     architecture: Architecture,
     updates: Updates,
+    config: Config,
 }
 
 impl Qt {
-    // Detects Qt Installer Framework (IFW) by parsing Updates.xml from the PE overlay's QT resource (qres)
-    // Installer config.xml has Publisher and DefaultInstallDirectory, but we'd need to traverse the file tree
+    const UPDATES_XML: &str = "Updates.xml";
+
+    /// Detects Qt Installer Framework (IFW) by parsing `Updates.xml` from the Qt resource
+    /// collection (qres) in the PE overlay, along with the installer's `config.xml` if it can be
+    /// found in the collection's file tree.
     pub fn new<R: Read + Seek>(mut reader: R, pe: &PE) -> Result<Self, QtError> {
         let overlay_offset = pe.overlay_offset().ok_or(QtError::NotQtFile)?;
 
-        reader
-            .seek(SeekFrom::Start(overlay_offset))
-            .map_err(|_| QtError::NotQtFile)?;
-
-        let mut magic = [0u8; 4];
-        reader
-            .read_exact(&mut magic)
-            .map_err(|_| QtError::NotQtFile)?;
-        if &magic != b"qres" {
-            return Err(QtError::NotQtFile);
-        }
-
-        reader.seek(SeekFrom::Current(8))?; // Skip version and tree_offset
-        let data_offset = reader.read_u32::<BigEndian>()?;
-
-        reader.seek(SeekFrom::Start(overlay_offset + u64::from(data_offset)))?;
-
-        let size = reader.read_u32::<BigEndian>()? as usize;
-        let mut data = vec![0u8; size];
-        reader.read_exact(&mut data)?;
-
-        let updates: Updates =
-            quick_xml::de::from_str(std::str::from_utf8(&data).map_err(|_| QtError::NotQtFile)?)
-                .map_err(|_| QtError::NotQtFile)?;
+        let mut resource = QtResource::new(&mut reader, overlay_offset)
+            .map_err(|_| QtError::NotQtFile)?
+            .ok_or(QtError::NotQtFile)?;
+
+        let files = resource.files().unwrap_or_else(|error| {
+            debug!(%error, "Failed to traverse Qt resource tree");
+            Vec::new()
+        });
+        debug!(files = ?files.iter().map(|(path, _)| path).collect::<Vec<_>>());
+
+        let find_file = |name: &str| {
+            files
+                .iter()
+                .find(|(path, _)| {
+                    path.rsplit('/')
+                        .next()
+                        .is_some_and(|file_name| file_name.eq_ignore_ascii_case(name))
+                })
+                .map(|&(_, file)| file)
+        };
+
+        // Older collections may not have a tree, in which case Updates.xml is the first data
+        let updates_data = match find_file(Self::UPDATES_XML) {
+            Some(file) => resource.read(file)?,
+            None => resource.read_first()?,
+        };
+
+        let updates: Updates = quick_xml::de::from_str(
+            std::str::from_utf8(&updates_data).map_err(|_| QtError::NotQtFile)?,
+        )
+        .map_err(|_| QtError::NotQtFile)?;
+
+        let config = find_file(Config::FILE_NAME)
+            .and_then(|file| resource.read(file).ok())
+            .and_then(|data| String::from_utf8(data).ok())
+            .and_then(|config| quick_xml::de::from_str::<Config>(&config).ok())
+            .unwrap_or_default();
 
         Ok(Self {
             architecture: Architecture::from_machine(pe.coff_header.machine()),
             updates,
+            config,
         })
     }
 }
 
+impl Qt {
+    /// Returns the `ProductUrl` from the installer's `config.xml`.
+    #[inline]
+    pub fn product_url(&self) -> Option<&str> {
+        self.config.product_url.as_deref()
+    }
+}
+
 impl Installers for Qt {
     fn installers(&self) -> Vec<Installer> {
         let package = self
@@ -100,20 +131,23 @@ fn installers(&self) -> Vec<Installer> {
             .and_then(|p| p.first());
 
         let display_name = self
-            .updates
-            .application_name
+            .config
+            .name
             .as_deref()
+            .or(self.updates.application_name.as_deref())
             .or_else(|| package.and_then(|p| p.display_name.as_deref()));
 
         let version = self
-            .updates
-            .application_version
+            .config
+            .version
             .as_deref()
+            .or(self.updates.application_version.as_deref())
             .or_else(|| package.and_then(|p| p.version.as_deref()));
 
         vec![Installer {
             architecture: self.architecture,
             r#type: Some(InstallerType::Exe),
+            scope: self.config.scope(),
             install_modes: InstallModes::all(),
             switches: InstallerSwitches::builder()
                 .silent(
@@ -132,11 +166,19 @@ fn installers(&self) -> Vec<Installer> {
             apps_and_features_entries: AppsAndFeaturesEntries::from(
                 AppsAndFeaturesEntry::builder()
                     .maybe_display_name(display_name)
+                    .maybe_publisher(self.config.publisher.as_deref())
                     .maybe_display_version(
                         version.and_then(|v| v.parse::<Version>().ok()),
                     )
                     .build(),
             ),
+            installation_metadata: InstallationMetadata {
+                default_install_location: self
+                    .config
+                    .install_location(self.architecture)
+                    .map(Utf8PathBuf::from),
+                ..InstallationMetadata::default()
+            },
             ..Installer::default()
         }]
     }
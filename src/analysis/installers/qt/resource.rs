@@ -0,0 +1,292 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use flate2::read::ZlibDecoder;
+use zerocopy::{BigEndian, FromBytes, Immutable, IntoBytes, KnownLayout, U32};
+
+use crate::read::ReadBytesExt;
+
+/// The header of a compiled Qt resource collection, as produced by `rcc`.
+///
+/// See <https://github.com/qt/qtbase/blob/dev/src/tools/rcc/rcc.cpp>.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+#[repr(C)]
+pub struct ResourceHeader {
+    magic: [u8; 4],
+    version: U32<BigEndian>,
+    tree_offset: U32<BigEndian>,
+    data_offset: U32<BigEndian>,
+    names_offset: U32<BigEndian>,
+}
+
+impl ResourceHeader {
+    pub const MAGIC: [u8; 4] = *b"qres";
+
+    /// Returns the size of a node in the tree. Trees from format version 2 onwards include the
+    /// last modified time of each node.
+    #[inline]
+    const fn node_size(&self) -> u64 {
+        if self.version.get() >= 2 { 22 } else { 14 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Node {
+    Directory { child_count: u32, first_child: u32 },
+    File(ResourceFile),
+}
+
+/// A file in a Qt resource collection.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ResourceFile {
+    flags: u16,
+    data_offset: u32,
+}
+
+impl ResourceFile {
+    const COMPRESSED: u16 = 1 << 0;
+    const COMPRESSED_ZSTD: u16 = 1 << 2;
+}
+
+/// A reader over the tree of a Qt resource collection.
+pub struct QtResource<R> {
+    reader: R,
+    offset: u64,
+    header: ResourceHeader,
+}
+
+impl<R: Read + Seek> QtResource<R> {
+    const DIRECTORY: u16 = 1 << 1;
+
+    /// Guards against malformed trees that refer back to their own ancestors.
+    const MAX_NODES: usize = 1 << 14;
+
+    /// Reads the header of a resource collection at the given offset.
+    ///
+    /// Returns `None` if there is no resource collection at the offset.
+    pub fn new(mut reader: R, offset: u64) -> io::Result<Option<Self>> {
+        reader.seek(SeekFrom::Start(offset))?;
+        let header = reader.read_t::<ResourceHeader>()?;
+
+        Ok((header.magic == ResourceHeader::MAGIC).then_some(Self {
+            reader,
+            offset,
+            header,
+        }))
+    }
+
+    /// Returns the slash-separated paths of every file in the collection, in tree order.
+    pub fn files(&mut self) -> io::Result<Vec<(String, ResourceFile)>> {
+        let mut files = Vec::new();
+        let mut pending = vec![(String::new(), 0)];
+        let mut visited = 0;
+
+        while let Some((path, index)) = pending.pop() {
+            visited += 1;
+            if visited > Self::MAX_NODES {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Qt resource tree has too many nodes",
+                ));
+            }
+
+            match self.node(index)? {
+                Node::Directory {
+                    child_count,
+                    first_child,
+                } => {
+                    // Push in reverse so that children are visited in order
+                    for child in (first_child..first_child.saturating_add(child_count)).rev() {
+                        let name = self.name(child)?;
+                        let child_path = if path.is_empty() {
+                            name
+                        } else {
+                            format!("{path}/{name}")
+                        };
+                        pending.push((child_path, child));
+                    }
+                }
+                Node::File(file) => files.push((path, file)),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Reads a file's data, decompressing it if it was compressed with zlib.
+    pub fn read(&mut self, file: ResourceFile) -> io::Result<Vec<u8>> {
+        self.seek_to(self.header.data_offset.get(), u64::from(file.data_offset))?;
+
+        let size = self.reader.read_u32::<BigEndian>()?;
+        let mut data = Vec::new();
+        self.reader
+            .by_ref()
+            .take(size.into())
+            .read_to_end(&mut data)?;
+
+        if file.flags & ResourceFile::COMPRESSED_ZSTD != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Zstandard compressed Qt resources are not supported",
+            ));
+        }
+
+        if file.flags & ResourceFile::COMPRESSED != 0 {
+            // qCompress prefixes the zlib stream with the big-endian uncompressed size
+            let compressed = data.get(size_of::<u32>()..).unwrap_or_default();
+            let mut decompressed = Vec::new();
+            ZlibDecoder::new(compressed).read_to_end(&mut decompressed)?;
+            return Ok(decompressed);
+        }
+
+        Ok(data)
+    }
+
+    /// Reads the data that is stored first in the collection, regardless of where it is in the
+    /// tree.
+    pub fn read_first(&mut self) -> io::Result<Vec<u8>> {
+        self.read(ResourceFile {
+            flags: 0,
+            data_offset: 0,
+        })
+    }
+
+    fn node(&mut self, index: u32) -> io::Result<Node> {
+        self.seek_to(
+            self.header.tree_offset.get(),
+            u64::from(index) * self.header.node_size(),
+        )?;
+
+        let _name_offset = self.reader.read_u32::<BigEndian>()?;
+        let flags = self.reader.read_u16::<BigEndian>()?;
+
+        if flags & Self::DIRECTORY != 0 {
+            Ok(Node::Directory {
+                child_count: self.reader.read_u32::<BigEndian>()?,
+                first_child: self.reader.read_u32::<BigEndian>()?,
+            })
+        } else {
+            let _country_and_language = self.reader.read_u32::<BigEndian>()?;
+            Ok(Node::File(ResourceFile {
+                flags,
+                data_offset: self.reader.read_u32::<BigEndian>()?,
+            }))
+        }
+    }
+
+    fn name(&mut self, index: u32) -> io::Result<String> {
+        self.seek_to(
+            self.header.tree_offset.get(),
+            u64::from(index) * self.header.node_size(),
+        )?;
+        let name_offset = self.reader.read_u32::<BigEndian>()?;
+
+        self.seek_to(self.header.names_offset.get(), u64::from(name_offset))?;
+        let length = self.reader.read_u16::<BigEndian>()?;
+        let _hash = self.reader.read_u32::<BigEndian>()?;
+
+        let mut name = Vec::with_capacity(length.into());
+        for _ in 0..length {
+            name.push(self.reader.read_u16::<BigEndian>()?);
+        }
+
+        Ok(String::from_utf16_lossy(&name))
+    }
+
+    fn seek_to(&mut self, section_offset: u32, offset: u64) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(
+            self.offset + u64::from(section_offset) + offset,
+        ))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{QtResource, ResourceHeader};
+
+    /// Builds a version 1 collection containing `config/config.xml` and `Updates.xml`.
+    fn collection() -> Vec<u8> {
+        fn name(name: &str) -> Vec<u8> {
+            let mut bytes = (name.len() as u16).to_be_bytes().to_vec();
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend(name.encode_utf16().flat_map(u16::to_be_bytes));
+            bytes
+        }
+
+        fn directory(name_offset: u32, child_count: u32, first_child: u32) -> Vec<u8> {
+            [
+                &name_offset.to_be_bytes()[..],
+                &2u16.to_be_bytes(),
+                &child_count.to_be_bytes(),
+                &first_child.to_be_bytes(),
+            ]
+            .concat()
+        }
+
+        fn file(name_offset: u32, data_offset: u32) -> Vec<u8> {
+            [
+                &name_offset.to_be_bytes()[..],
+                &0u16.to_be_bytes(),
+                &[0; 4],
+                &data_offset.to_be_bytes(),
+            ]
+            .concat()
+        }
+
+        let config = b"<Installer/>";
+        let updates = b"<Updates/>";
+
+        let names = [name("config"), name("config.xml"), name("Updates.xml")];
+        let names_offsets = [0, names[0].len(), names[0].len() + names[1].len()].map(|n| n as u32);
+
+        let tree = [
+            directory(0, 2, 1),
+            directory(names_offsets[0], 1, 3),
+            file(names_offsets[2], 0),
+            file(names_offsets[1], (4 + updates.len()) as u32),
+        ]
+        .concat();
+
+        let mut data = (updates.len() as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(updates);
+        data.extend_from_slice(&(config.len() as u32).to_be_bytes());
+        data.extend_from_slice(config);
+
+        let tree_offset = size_of::<ResourceHeader>() as u32;
+        let data_offset = tree_offset + tree.len() as u32;
+        let names_offset = data_offset + data.len() as u32;
+
+        let mut collection = ResourceHeader::MAGIC.to_vec();
+        for value in [1, tree_offset, data_offset, names_offset] {
+            collection.extend_from_slice(&value.to_be_bytes());
+        }
+        collection.extend(tree);
+        collection.extend(data);
+        collection.extend(names.concat());
+        collection
+    }
+
+    #[test]
+    fn traverse() {
+        let mut resource = QtResource::new(Cursor::new(collection()), 0)
+            .unwrap()
+            .unwrap();
+
+        let files = resource.files().unwrap();
+        let paths = files
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["config/config.xml", "Updates.xml"]);
+
+        assert_eq!(resource.read(files[0].1).unwrap(), b"<Installer/>");
+        assert_eq!(resource.read_first().unwrap(), b"<Updates/>");
+    }
+
+    #[test]
+    fn not_a_collection() {
+        assert!(QtResource::new(Cursor::new([0; 20]), 0).unwrap().is_none());
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{self, Read, Seek};
+
+use thiserror::Error;
+use winget_types::installer::{
+    Architecture, InstallModes, Installer, InstallerSwitches, InstallerType,
+};
+
+use crate::{
+    analysis::{Installers, installers::pe::PE},
+    traits::FromMachine,
+};
+
+/// The signature at the start of the overlay of Setup Factory 7 and later.
+const SIGNATURE: [u8; 8] = [0xE0, 0xE1, 0xE2, 0xE3, 0xE4, 0xE5, 0xE6, 0xE7];
+
+/// The signature at the start of the overlay of Setup Factory 5 and 6.
+const LEGACY_SIGNATURE: [u8; 16] = [
+    0xE0, 0xE0, 0xE1, 0xE1, 0xE2, 0xE2, 0xE3, 0xE3, 0xE4, 0xE4, 0xE5, 0xE5, 0xE6, 0xE6, 0xE7, 0xE7,
+];
+
+#[derive(Error, Debug)]
+pub enum SetupFactoryError {
+    #[error("File is not a Setup Factory installer")]
+    NotSetupFactoryFile,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub struct SetupFactory {
+    architecture: Architecture,
+}
+
+impl SetupFactory {
+    pub fn new<R: Read + Seek>(reader: R, pe: &PE) -> Result<Self, SetupFactoryError> {
+        if !Self::has_signature(&pe.overlay_prefix(reader, LEGACY_SIGNATURE.len())?) {
+            return Err(SetupFactoryError::NotSetupFactoryFile);
+        }
+
+        Ok(Self {
+            architecture: Architecture::from_machine(pe.machine()),
+        })
+    }
+
+    fn has_signature(overlay: &[u8]) -> bool {
+        overlay.starts_with(&SIGNATURE) || overlay.starts_with(&LEGACY_SIGNATURE)
+    }
+}
+
+impl Installers for SetupFactory {
+    fn installers(&self) -> Vec<Installer> {
+        vec![Installer {
+            architecture: self.architecture,
+            r#type: Some(InstallerType::Exe),
+            install_modes: InstallModes::all(),
+            switches: InstallerSwitches::builder()
+                .silent("/S".parse().unwrap())
+                .silent_with_progress("/S".parse().unwrap())
+                .build(),
+            ..Installer::default()
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{LEGACY_SIGNATURE, SIGNATURE, SetupFactory};
+
+    #[rstest]
+    #[case(&SIGNATURE, true)]
+    #[case(&LEGACY_SIGNATURE, true)]
+    #[case(&SIGNATURE[..4], false)]
+    #[case(b"wwgT)H", false)]
+    fn signature(#[case] overlay: &[u8], #[case] expected: bool) {
+        assert_eq!(SetupFactory::has_signature(overlay), expected);
+    }
+}
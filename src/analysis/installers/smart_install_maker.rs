@@ -0,0 +1,75 @@
+use std::io::{self, Read, Seek};
+
+use thiserror::Error;
+use winget_types::installer::{
+    Architecture, InstallModes, Installer, InstallerSwitches, InstallerType,
+};
+
+use crate::{
+    analysis::{Installers, installers::pe::PE},
+    traits::FromMachine,
+};
+
+/// The header at the start of the overlay of a Smart Install Maker setup, which is followed by the
+/// version of Smart Install Maker that built it.
+const SIGNATURE: &[u8] = b"Smart Install Maker";
+
+#[derive(Error, Debug)]
+pub enum SmartInstallMakerError {
+    #[error("File is not a Smart Install Maker installer")]
+    NotSmartInstallMakerFile,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub struct SmartInstallMaker {
+    architecture: Architecture,
+}
+
+impl SmartInstallMaker {
+    pub fn new<R: Read + Seek>(reader: R, pe: &PE) -> Result<Self, SmartInstallMakerError> {
+        if !Self::has_signature(&pe.overlay_prefix(reader, SIGNATURE.len())?) {
+            return Err(SmartInstallMakerError::NotSmartInstallMakerFile);
+        }
+
+        Ok(Self {
+            architecture: Architecture::from_machine(pe.machine()),
+        })
+    }
+
+    fn has_signature(overlay: &[u8]) -> bool {
+        overlay.starts_with(SIGNATURE)
+    }
+}
+
+impl Installers for SmartInstallMaker {
+    fn installers(&self) -> Vec<Installer> {
+        vec![Installer {
+            architecture: self.architecture,
+            r#type: Some(InstallerType::Exe),
+            install_modes: InstallModes::all(),
+            switches: InstallerSwitches::builder()
+                .silent("/s".parse().unwrap())
+                .silent_with_progress("/s".parse().unwrap())
+                .build(),
+            ..Installer::default()
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::SmartInstallMaker;
+
+    #[rstest]
+    #[case(b"Smart Install Maker v. 5.04", true)]
+    #[case(b"Smart Install Maker", true)]
+    #[case(b"Smart Install", false)]
+    #[case(b"MZ\x90\0Smart Install Maker", false)]
+    #[case(b"", false)]
+    fn signature(#[case] overlay: &[u8], #[case] expected: bool) {
+        assert_eq!(SmartInstallMaker::has_signature(overlay), expected);
+    }
+}
@@ -1,5 +1,8 @@
 mod lzma_stream_header;
+mod product_info;
+
 pub use lzma_stream_header::LzmaStreamHeader;
+pub use product_info::ProductInfo;
 
 pub const RELATIVE_PROGRAM_FILES_64: &str = "%ProgramFiles%";
 pub const RELATIVE_PROGRAM_FILES_32: &str = "%ProgramFiles(x86)%";
@@ -12,3 +15,4 @@
 pub const RELATIVE_SYSTEM_ROOT: &str = "%SystemRoot%";
 pub const RELATIVE_SYSTEM_DRIVE: &str = "%SystemDrive%";
 pub const RELATIVE_TEMP_FOLDER: &str = "%Temp%";
+pub const RELATIVE_USER_PROFILE: &str = "%UserProfile%";
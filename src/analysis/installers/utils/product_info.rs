@@ -0,0 +1,122 @@
+use indexmap::IndexMap;
+use quick_xml::de::from_str;
+use serde::Deserialize;
+use winget_types::installer::{AppsAndFeaturesEntries, AppsAndFeaturesEntry};
+
+/// The product described by an installer's version info and manifest.
+///
+/// Installer frameworks that don't embed a readable setup script fill the version info of their
+/// stub in from the project settings, so it is the best source of ARP metadata for them.
+#[derive(Clone, Debug, Default)]
+pub struct ProductInfo {
+    string_table: IndexMap<String, String>,
+    manifest: Option<String>,
+}
+
+impl ProductInfo {
+    const PRODUCT_NAME: &str = "ProductName";
+    const COMPANY_NAME: &str = "CompanyName";
+    const PRODUCT_VERSION: &str = "ProductVersion";
+
+    /// Creates the product info from the string table of the exe's already parsed version info
+    /// and its manifest.
+    pub fn new(string_table: Option<&IndexMap<&str, &str>>, manifest: Option<String>) -> Self {
+        Self {
+            string_table: string_table
+                .into_iter()
+                .flatten()
+                .map(|(&key, &value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            manifest,
+        }
+    }
+
+    #[inline]
+    pub fn manifest(&self) -> Option<&str> {
+        self.manifest.as_deref()
+    }
+
+    /// Returns the name of the manifest's assembly identity.
+    ///
+    /// Unlike the version info, the manifest belongs to the setup stub rather than being filled in
+    /// from the project settings, so its identity names the installer framework itself.
+    pub fn assembly_name(&self) -> Option<&str> {
+        #[derive(Deserialize)]
+        struct Assembly<'data> {
+            #[serde(rename = "assemblyIdentity", borrow)]
+            assembly_identity: AssemblyIdentity<'data>,
+        }
+
+        #[derive(Deserialize)]
+        struct AssemblyIdentity<'data> {
+            #[serde(rename = "@name")]
+            name: &'data str,
+        }
+
+        from_str::<Assembly>(self.manifest()?)
+            .ok()
+            .map(|assembly| assembly.assembly_identity.name)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.string_table
+            .get(key)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Returns an ARP entry built from the product name, company name and product version, unless
+    /// none of them are present.
+    pub fn apps_and_features_entries(&self) -> AppsAndFeaturesEntries {
+        let display_name = self.get(Self::PRODUCT_NAME);
+        let publisher = self.get(Self::COMPANY_NAME);
+        let display_version = self.get(Self::PRODUCT_VERSION);
+
+        if display_name.is_none() && publisher.is_none() && display_version.is_none() {
+            return AppsAndFeaturesEntries::new();
+        }
+
+        AppsAndFeaturesEntry::builder()
+            .maybe_display_name(display_name)
+            .maybe_publisher(publisher)
+            .maybe_display_version(display_version)
+            .build()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use indoc::indoc;
+
+    use super::ProductInfo;
+
+    #[test]
+    fn assembly_name() {
+        const MANIFEST: &str = indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+            <assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+                <assemblyIdentity version="1.0.0.0" processorArchitecture="X86" name="InstallAware.Setup" type="win32"/>
+                <description>InstallAware Setup</description>
+            </assembly>
+        "#};
+
+        let product_info = ProductInfo::new(None, Some(MANIFEST.to_owned()));
+
+        assert_eq!(product_info.assembly_name(), Some("InstallAware.Setup"));
+    }
+
+    #[test]
+    fn version_info_does_not_name_assembly() {
+        let string_table = IndexMap::from([("CompanyName", "InstallAware Software Corporation")]);
+
+        let product_info = ProductInfo::new(Some(&string_table), None);
+
+        assert_eq!(product_info.assembly_name(), None);
+        assert_eq!(
+            product_info.get("CompanyName"),
+            Some("InstallAware Software Corporation")
+        );
+    }
+}
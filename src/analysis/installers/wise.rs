@@ -0,0 +1,86 @@
+use std::io;
+
+use thiserror::Error;
+use winget_types::installer::{
+    Architecture, InstallModes, Installer, InstallerSwitches, InstallerType,
+};
+
+use crate::{
+    analysis::{
+        Installers,
+        installers::pe::{PE, SectionHeader},
+    },
+    traits::FromMachine,
+};
+
+/// The section that the Wise Installation System stub stores its script and payload offsets in.
+pub const WISE: [u8; 8] = *b".WISE\0\0\0";
+
+#[derive(Error, Debug)]
+pub enum WiseError {
+    #[error("File is not a Wise installer")]
+    NotWiseFile,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub struct Wise {
+    architecture: Architecture,
+}
+
+impl Wise {
+    pub fn new(pe: &PE) -> Result<Self, WiseError> {
+        if !Self::has_wise_section(pe.section_table.sections()) {
+            return Err(WiseError::NotWiseFile);
+        }
+
+        Ok(Self {
+            architecture: Architecture::from_machine(pe.machine()),
+        })
+    }
+
+    fn has_wise_section(sections: &[SectionHeader]) -> bool {
+        sections.iter().any(|section| section.raw_name() == WISE)
+    }
+}
+
+impl Installers for Wise {
+    fn installers(&self) -> Vec<Installer> {
+        vec![Installer {
+            architecture: self.architecture,
+            r#type: Some(InstallerType::Exe),
+            install_modes: InstallModes::all(),
+            switches: InstallerSwitches::builder()
+                .silent("/s".parse().unwrap())
+                .silent_with_progress("/s".parse().unwrap())
+                .build(),
+            ..Installer::default()
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use zerocopy::FromBytes;
+
+    use super::{WISE, Wise};
+    use crate::analysis::installers::pe::SectionHeader;
+
+    fn section(name: [u8; 8]) -> SectionHeader {
+        let mut header = [0; size_of::<SectionHeader>()];
+        header[..name.len()].copy_from_slice(&name);
+        SectionHeader::read_from_bytes(&header).unwrap()
+    }
+
+    #[rstest]
+    #[case(&[*b".text\0\0\0", WISE], true)]
+    #[case(&[*b".text\0\0\0", *b".rsrc\0\0\0"], false)]
+    #[case(&[*b".wise\0\0\0"], false)]
+    #[case(&[], false)]
+    fn wise_section(#[case] names: &[[u8; 8]], #[case] expected: bool) {
+        let sections = names.iter().copied().map(section).collect::<Vec<_>>();
+
+        assert_eq!(Wise::has_wise_section(&sections), expected);
+    }
+}
@@ -10,11 +10,14 @@
 use inquire::{MultiSelect, min_length};
 use tracing::debug;
 use winget_types::installer::{
-    Installer, InstallerType, NestedInstallerFiles, PortableCommandAlias,
+    Installer, InstallerType, NestedInstallerFiles, PackageDependencies, PortableCommandAlias,
 };
 use zip::ZipArchive;
 
-use super::super::Analyzer;
+use super::{
+    super::Analyzer,
+    exe::{RuntimeConfig, dotnet_runtime_dependencies},
+};
 use crate::prompts::{handle_inquire_error, text::optional_prompt};
 
 const VALID_NESTED_FILE_EXTENSIONS: [&str; 6] =
@@ -88,6 +91,7 @@ pub fn new(reader: R) -> Result<Self> {
                 relative_file_path: (*chosen_file_name).clone(),
                 portable_command_alias: None,
             }]);
+            let runtime_dependencies = runtime_dependencies(&mut zip, chosen_file_name);
             if let Ok(mut chosen_file) = zip.by_name(chosen_file_name.as_str()) {
                 let mut temp_file = tempfile::tempfile()?;
                 io::copy(&mut chosen_file, &mut temp_file)?;
@@ -97,6 +101,9 @@ pub fn new(reader: R) -> Result<Self> {
                     file_analyzer
                         .installers
                         .into_iter()
+                        .map(|installer| {
+                            with_runtime_dependencies(installer, &runtime_dependencies)
+                        })
                         .map(|installer| Installer {
                             r#type: Some(InstallerType::Zip),
                             nested_installer_type: installer
@@ -140,6 +147,7 @@ pub fn prompt(&mut self) -> Result<()> {
             )?;
             temp_file.seek(SeekFrom::Start(0))?;
             let file_analyzer = Analyzer::new(&mut temp_file, first_choice.file_name().unwrap())?;
+            let runtime_dependencies = runtime_dependencies(&mut self.archive, first_choice);
             let nested_installer_files = chosen
                 .into_iter()
                 .map(|path| {
@@ -158,6 +166,7 @@ pub fn prompt(&mut self) -> Result<()> {
             self.installers = file_analyzer
                 .installers
                 .into_iter()
+                .map(|installer| with_runtime_dependencies(installer, &runtime_dependencies))
                 .map(|installer| Installer {
                     nested_installer_type: installer
                         .r#type
@@ -170,3 +179,38 @@ pub fn prompt(&mut self) -> Result<()> {
         Ok(())
     }
 }
+
+/// Returns the .NET runtimes that an exe in the archive depends on, from the
+/// `<name>.runtimeconfig.json` that a .NET apphost is published next to.
+fn runtime_dependencies<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    exe: &Utf8Path,
+) -> BTreeSet<PackageDependencies> {
+    let Ok(mut runtime_config) = archive.by_name(exe.with_extension("runtimeconfig.json").as_str())
+    else {
+        return BTreeSet::new();
+    };
+
+    let mut json = Vec::new();
+    if runtime_config.read_to_end(&mut json).is_err() {
+        return BTreeSet::new();
+    }
+
+    RuntimeConfig::from_slice(&json)
+        .map(|runtime_config| dotnet_runtime_dependencies(&runtime_config))
+        .unwrap_or_default()
+}
+
+/// Adds the .NET runtimes from a `runtimeconfig.json` to a portable exe's dependencies.
+fn with_runtime_dependencies(
+    mut installer: Installer,
+    runtime_dependencies: &BTreeSet<PackageDependencies>,
+) -> Installer {
+    if installer.r#type == Some(InstallerType::Portable) {
+        installer
+            .dependencies
+            .package_dependencies
+            .extend(runtime_dependencies.iter().cloned());
+    }
+    installer
+}
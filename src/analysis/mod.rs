@@ -1,7 +1,8 @@
 mod analyzer;
-mod extensions;
+pub mod extensions;
+pub mod icon;
 pub mod installers;
 mod r#trait;
 
-pub use analyzer::Analyzer;
+pub use analyzer::{AnalyzeOptions, Analyzer};
 pub use r#trait::Installers;
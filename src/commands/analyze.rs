@@ -1,24 +1,52 @@
 use std::{
     fs::File,
     io,
-    io::{Read, Seek, SeekFrom},
+    io::{Read, Seek, SeekFrom, Write},
+    panic::resume_unwind,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
 };
 
 use anstream::stdout;
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::Parser;
-use color_eyre::{Result, eyre::ensure};
+use clap::{Args, Parser};
+use color_eyre::{Report, Result, eyre::ensure};
+use serde::Serialize;
 use sha2::{Digest, Sha256, digest::Output};
-use winget_types::Sha256String;
+use walkdir::WalkDir;
+use winget_types::{
+    PackageVersion, Sha256String,
+    installer::Installer,
+    locale::{Copyright, PackageName, Publisher},
+};
 
-use crate::{analysis::Analyzer, manifests::print_manifest};
+use crate::{
+    analysis::{
+        AnalyzeOptions, Analyzer, extensions::ANALYZABLE, icon::Icon, installers::DetectionReport,
+    },
+    manifests::print_manifest,
+};
 
-/// Analyzes a file and outputs information about it
+/// Analyzes files and outputs information about them
 #[derive(Parser)]
 #[clap(visible_alias = "analyse")]
 pub struct Analyze {
-    #[arg(value_parser = is_valid_file, value_hint = clap::ValueHint::FilePath)]
-    file_path: Utf8PathBuf,
+    /// Files or directories to analyze. Directories are searched recursively for installers
+    #[arg(required = true, num_args = 1.., value_parser = is_valid_path, value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<Utf8PathBuf>,
+
+    /// Explain how the installer type was detected, including each detector's verdict and the
+    /// evidence it found
+    #[arg(long)]
+    explain: bool,
+
+    #[command(flatten)]
+    output_type: OutputType,
+
+    /// Extract the largest icon from each exe or MSIX into this directory, named by its SHA-256, so
+    /// that it can be hosted for the `Icons` field of the default locale manifest
+    #[arg(long, value_name = "DIRECTORY", value_hint = clap::ValueHint::DirPath)]
+    icons: Option<Utf8PathBuf>,
 
     #[cfg(not(debug_assertions))]
     /// Hash the file and include it in the `InstallerSha256` field
@@ -41,29 +69,251 @@ pub struct Analyze {
     _no_hash: bool,
 }
 
+#[derive(Args)]
+#[group(multiple = false)]
+struct OutputType {
+    /// Output an array of analyzed files as JSON, including the detection trace with `--explain`
+    #[arg(long)]
+    json: bool,
+
+    /// Output an array of analyzed files as YAML
+    #[arg(long)]
+    yaml: bool,
+}
+
 impl Analyze {
     pub fn run(self) -> Result<()> {
-        let mut file = File::open(&self.file_path)?;
-        let file_name = self
-            .file_path
-            .file_name()
-            .unwrap_or_else(|| self.file_path.as_str());
-        let mut installers = Analyzer::new(&mut file, file_name)?.installers;
-        if self.hash {
+        let file_paths = self.file_paths()?;
+
+        let mut lock = stdout().lock();
+
+        // A single file without an output type keeps the highlighted installer output
+        if let (
+            [file_path],
+            OutputType {
+                json: false,
+                yaml: false,
+            },
+        ) = (file_paths.as_slice(), &self.output_type)
+        {
+            let analyzed =
+                AnalyzedFile::new(file_path, self.explain, self.hash, self.icons.as_deref())?;
+
+            if self.explain {
+                match &analyzed.detection {
+                    Some(report) => writeln!(lock, "{}", report.to_text())?,
+                    None => writeln!(
+                        lock,
+                        "Detection is only traced for exe files. {} was identified by its extension\n",
+                        analyzed.file_name
+                    )?,
+                }
+            }
+
+            if let Some(icon) = &analyzed.icon {
+                writeln!(
+                    lock,
+                    "Wrote {} ({}, {}, {})\n",
+                    icon.path,
+                    icon.icon.file_type.extension(),
+                    icon.icon.resolution,
+                    icon.icon.sha_256
+                )?;
+            }
+
+            let yaml = match analyzed.installers.as_slice() {
+                [installer] => serde_yaml::to_string(installer)?,
+                installers => serde_yaml::to_string(installers)?,
+            };
+            print_manifest(&mut lock, &yaml);
+            return Ok(());
+        }
+
+        let analyzed_files =
+            analyze_concurrently(&file_paths, self.explain, self.hash, self.icons.as_deref());
+
+        if self.output_type.json {
+            serde_json::to_writer_pretty(&mut lock, &analyzed_files)?;
+            writeln!(lock)?;
+        } else {
+            print_manifest(&mut lock, &serde_yaml::to_string(&analyzed_files)?);
+        }
+
+        Ok(())
+    }
+
+    /// Expands directories into the analyzable files they contain.
+    fn file_paths(&self) -> Result<Vec<Utf8PathBuf>> {
+        let mut file_paths = Vec::new();
+
+        for path in &self.paths {
+            if path.is_file() {
+                file_paths.push(path.clone());
+                continue;
+            }
+
+            for entry in WalkDir::new(path).sort_by_file_name() {
+                let entry = entry?;
+                if entry.file_type().is_file()
+                    && entry.path().extension().is_some_and(|extension| {
+                        ANALYZABLE
+                            .iter()
+                            .any(|analyzable| extension.eq_ignore_ascii_case(analyzable))
+                    })
+                    && let Ok(file_path) = Utf8PathBuf::from_path_buf(entry.into_path())
+                {
+                    file_paths.push(file_path);
+                }
+            }
+        }
+
+        ensure!(
+            !file_paths.is_empty(),
+            "No analyzable files were found. Supported extensions are: {}",
+            ANALYZABLE.join(", ")
+        );
+
+        Ok(file_paths)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct AnalyzedFile {
+    file_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher: Option<Publisher>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_name: Option<PackageName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    copyright: Option<Copyright>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_version: Option<PackageVersion>,
+    installers: Vec<Installer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<ExtractedIcon>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detection: Option<DetectionReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// An icon that was written to the icons directory, with the values for the `Icons` manifest field.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ExtractedIcon {
+    path: Utf8PathBuf,
+    #[serde(flatten)]
+    icon: Icon,
+}
+
+impl AnalyzedFile {
+    fn new(path: &Utf8Path, explain: bool, hash: bool, icons: Option<&Utf8Path>) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let file_name = path.file_name().unwrap_or_else(|| path.as_str());
+
+        let Analyzer {
+            publisher,
+            package_name,
+            copyright,
+            package_version,
+            mut installers,
+            report,
+            icon,
+            ..
+        } = Analyzer::with_options(
+            &mut file,
+            file_name,
+            AnalyzeOptions {
+                explain,
+                extract_icon: icons.is_some(),
+            },
+        )?;
+
+        if hash {
             file.seek(SeekFrom::Start(0))?;
             let sha_256 = Sha256String::from_digest(&sha256_digest(file)?);
             for installer in &mut installers {
                 installer.sha_256 = sha_256.clone();
             }
         }
-        let yaml = match installers.as_slice() {
-            [installer] => serde_yaml::to_string(installer)?,
-            installers => serde_yaml::to_string(installers)?,
+
+        let icon = match (icons, icon) {
+            (Some(directory), Some(icon)) => Some(ExtractedIcon {
+                path: icon.write_to(directory)?,
+                icon,
+            }),
+            _ => None,
         };
-        let mut lock = stdout().lock();
-        print_manifest(&mut lock, &yaml);
-        Ok(())
+
+        Ok(Self {
+            file_name: path.to_string(),
+            publisher,
+            package_name,
+            copyright,
+            package_version,
+            installers,
+            icon,
+            detection: report,
+            error: None,
+        })
     }
+
+    /// Records a file that could not be analyzed so that the other files are still output.
+    fn error(path: &Utf8Path, error: &Report) -> Self {
+        Self {
+            file_name: path.to_string(),
+            publisher: None,
+            package_name: None,
+            copyright: None,
+            package_version: None,
+            installers: Vec::new(),
+            icon: None,
+            detection: None,
+            error: Some(format!("{error:#}")),
+        }
+    }
+}
+
+/// Analyzes files across as many threads as there are logical cores, preserving the given order.
+fn analyze_concurrently(
+    file_paths: &[Utf8PathBuf],
+    explain: bool,
+    hash: bool,
+    icons: Option<&Utf8Path>,
+) -> Vec<AnalyzedFile> {
+    let next_file = AtomicUsize::new(0);
+
+    let mut analyzed_files = thread::scope(|scope| {
+        let workers = (0..num_cpus::get().min(file_paths.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut analyzed_files = Vec::new();
+                    loop {
+                        let index = next_file.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = file_paths.get(index) else {
+                            break analyzed_files;
+                        };
+                        let analyzed_file = AnalyzedFile::new(path, explain, hash, icons)
+                            .unwrap_or_else(|error| AnalyzedFile::error(path, &error));
+                        analyzed_files.push((index, analyzed_file));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|panic| resume_unwind(panic)))
+            .collect::<Vec<_>>()
+    });
+
+    analyzed_files.sort_unstable_by_key(|&(index, _)| index);
+
+    analyzed_files
+        .into_iter()
+        .map(|(_, analyzed_file)| analyzed_file)
+        .collect()
 }
 
 fn sha256_digest<R: Read>(mut reader: R) -> io::Result<Output<Sha256>> {
@@ -81,6 +331,12 @@ fn sha256_digest<R: Read>(mut reader: R) -> io::Result<Output<Sha256>> {
     Ok(digest.finalize())
 }
 
+fn is_valid_path(path: &str) -> Result<Utf8PathBuf> {
+    let path = Utf8Path::new(path);
+    ensure!(path.exists(), "{path} does not exist");
+    Ok(path.to_path_buf())
+}
+
 pub(super) fn is_valid_file(path: &str) -> Result<Utf8PathBuf> {
     let path = Utf8Path::new(path);
     ensure!(path.exists(), "{path} does not exist");
@@ -0,0 +1,489 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem,
+    num::NonZeroUsize,
+};
+
+use anstream::println;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use color_eyre::eyre::{Result, bail};
+use indicatif::ProgressBar;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use secrecy::SecretString;
+use winget_types::{
+    PackageIdentifier, PackageVersion, Sha256String, installer::Installer, url::DecodedUrl,
+};
+
+use crate::{
+    commands::utils::{
+        SPINNER_TICK_RATE, SubmitOption, prompt_existing_pull_request, write_changes_to_dir,
+    },
+    download::Downloader,
+    download_file::process_files,
+    github::{
+        client::GitHub,
+        utils::{PackagePath, pull_request::pr_changes},
+    },
+    manifests::Manifests,
+    match_installers::match_installers,
+    token::TokenManager,
+};
+
+/// Download the installers of existing versions and check them against their manifest hashes
+#[expect(clippy::struct_excessive_bools)]
+#[derive(Parser)]
+#[clap(visible_alias = "audit")]
+pub struct AuditHashes {
+    /// Exact package identifier to audit
+    #[arg()]
+    package_identifier: PackageIdentifier,
+
+    /// Audit all versions instead of only the latest
+    #[arg(long)]
+    all_versions: bool,
+
+    /// Minimum version to audit (inclusive)
+    #[arg(long)]
+    min_version: Option<PackageVersion>,
+
+    /// Maximum version to audit (inclusive)
+    #[arg(long)]
+    max_version: Option<PackageVersion>,
+
+    /// Also compare the product codes and Apps and Features entries of the downloaded installers
+    #[arg(long)]
+    metadata: bool,
+
+    /// Prepare a pull request that updates the hashes of each version with drifted installers
+    #[arg(long)]
+    update: bool,
+
+    /// Automatically submit the pull requests
+    #[arg(short, long, requires = "update")]
+    submit: bool,
+
+    /// Directory to output the updated manifests to
+    #[arg(short, long, env = "OUTPUT_DIRECTORY", requires = "update", value_hint = clap::ValueHint::DirPath)]
+    output: Option<Utf8PathBuf>,
+
+    /// Open pull request links automatically
+    #[arg(long, env = "OPEN_PR")]
+    open_pr: bool,
+
+    /// Run without submitting
+    #[arg(long, env = "DRY_RUN")]
+    dry_run: bool,
+
+    /// Number of installers to download at the same time
+    #[arg(long, default_value_t = NonZeroUsize::new(2).unwrap())]
+    concurrent_downloads: NonZeroUsize,
+
+    /// GitHub personal access token with the `public_repo` scope
+    #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    token: Option<SecretString>,
+}
+
+impl AuditHashes {
+    pub async fn run(mut self) -> Result<()> {
+        let token_manager = TokenManager::handle(self.token.take()).await?;
+        let github = GitHub::new(&token_manager)?;
+
+        let progress = ProgressBar::new_spinner().with_message("Fetching package versions...");
+        progress.enable_steady_tick(SPINNER_TICK_RATE);
+
+        let versions = github.get_versions(&self.package_identifier).await;
+
+        progress.finish_and_clear();
+
+        let versions = versions?;
+
+        let versions_to_audit: Vec<&PackageVersion> =
+            match (self.all_versions, &self.min_version, &self.max_version) {
+                (true, _, _) => versions.iter().collect(),
+                (false, Some(min), Some(max)) => versions.range(min..=max).collect(),
+                (false, Some(min), None) => versions.range(min..).collect(),
+                (false, None, Some(max)) => versions.range(..=max).collect(),
+                (false, None, None) => versions.iter().next_back().into_iter().collect(),
+            };
+
+        if versions_to_audit.is_empty() {
+            println!(
+                "{} No versions matched the selected range for {}",
+                "⚠".yellow(),
+                self.package_identifier
+            );
+            return Ok(());
+        }
+
+        let downloader = Downloader::new_with_concurrent(self.concurrent_downloads)?;
+
+        let total_versions = versions_to_audit.len();
+        let mut total_matches = 0u32;
+        let mut total_drifted = 0u32;
+        let mut total_errors = 0u32;
+        let mut failed_updates = 0u32;
+
+        println!("\n{} ({total_versions})", self.package_identifier.bold());
+
+        for version in versions_to_audit {
+            let audit = match self.audit_version(&github, &downloader, version).await {
+                Ok(audit) => audit,
+                Err(err) => {
+                    total_errors += 1;
+                    println!("  {} {version}: {err}", "⚠".yellow());
+                    continue;
+                }
+            };
+
+            if audit.is_clean() {
+                total_matches += 1;
+                println!("  {} {version}", "✓".green());
+                continue;
+            }
+
+            total_drifted += 1;
+            println!("  {} {version}", "✗".red());
+            audit.print();
+
+            if self.update
+                && !audit.hash_drift.is_empty()
+                && let Err(err) = self.update_hashes(&github, &versions, version, audit).await
+            {
+                // Carry on with the remaining versions so that one failed pull request doesn't
+                // hide the drift of the others
+                failed_updates += 1;
+                println!(
+                    "  {} {version}: failed to update hashes: {err}",
+                    "⚠".yellow()
+                );
+            }
+        }
+
+        println!("\n{}", "═".repeat(60));
+        println!(
+            "Versions: {total_versions}  Matches: {}  Drifted: {}  Errors: {}  Failed updates: {}",
+            total_matches.green(),
+            total_drifted.red(),
+            total_errors.yellow(),
+            failed_updates.yellow()
+        );
+
+        if total_errors > 0 || failed_updates > 0 {
+            bail!(
+                "{} of {total_versions} versions of {} could not be audited or updated",
+                total_errors + failed_updates,
+                self.package_identifier
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn audit_version(
+        &self,
+        github: &GitHub,
+        downloader: &Downloader,
+        version: &PackageVersion,
+    ) -> Result<VersionAudit> {
+        let manifests = github
+            .get_manifests(&self.package_identifier, version)
+            .await?;
+
+        let urls = manifests
+            .installer
+            .installers
+            .iter()
+            .map(|installer| installer.url.clone())
+            .collect::<BTreeSet<_>>();
+
+        let mut files = downloader.download(urls).await?;
+
+        let hashes = files
+            .iter()
+            .map(|file| (file.url.inner().clone(), file.sha_256.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let hash_drift = hash_drift(&manifests.installer.installers, &hashes);
+
+        let metadata_drift = if self.metadata {
+            let mut download_results = process_files(&mut files).await?;
+            let installer_results = download_results
+                .values_mut()
+                .flat_map(|analyzer| mem::take(&mut analyzer.installers))
+                .collect::<Vec<_>>();
+            metadata_drift(&manifests, &installer_results)
+        } else {
+            Vec::new()
+        };
+
+        Ok(VersionAudit {
+            manifests,
+            hash_drift,
+            metadata_drift,
+        })
+    }
+
+    async fn update_hashes(
+        &self,
+        github: &GitHub,
+        versions: &BTreeSet<PackageVersion>,
+        version: &PackageVersion,
+        audit: VersionAudit,
+    ) -> Result<()> {
+        let VersionAudit {
+            mut manifests,
+            hash_drift,
+            ..
+        } = audit;
+
+        if let Some(ref pull_request) = github
+            .get_existing_pull_request(&self.package_identifier, version)
+            .await?
+            && !self.dry_run
+            && !prompt_existing_pull_request(&self.package_identifier, version, pull_request)?
+        {
+            return Ok(());
+        }
+
+        for installer in &mut manifests.installer.installers {
+            if let Some(drift) = hash_drift.iter().find(|drift| drift.url == installer.url) {
+                installer.sha_256.clone_from(&drift.actual);
+            }
+        }
+
+        let package_path = PackagePath::new(&self.package_identifier, Some(version), None);
+        let changes = pr_changes()
+            .package_identifier(&self.package_identifier)
+            .manifests(&manifests)
+            .package_path(&package_path)
+            .create()?;
+
+        let (changes, submit_option) = SubmitOption::prompt_async(
+            changes,
+            self.package_identifier.clone(),
+            version.clone(),
+            self.submit,
+            self.dry_run,
+        )
+        .await?;
+
+        if let Some(output) = self
+            .output
+            .as_ref()
+            .map(|out| out.join(package_path.as_str()))
+        {
+            write_changes_to_dir(&changes, output.as_path()).await?;
+            println!(
+                "{} written all manifest files to {output}",
+                "Successfully".green()
+            );
+        }
+
+        if submit_option.is_exit() {
+            return Ok(());
+        }
+
+        let pr_progress = ProgressBar::new_spinner().with_message(format!(
+            "Creating a pull request for {} {version}",
+            self.package_identifier
+        ));
+        pr_progress.enable_steady_tick(SPINNER_TICK_RATE);
+
+        let pull_request = github
+            .add_version()
+            .identifier(&self.package_identifier)
+            .version(version)
+            .versions(versions)
+            .changes(changes)
+            .issue_resolves(&[])
+            .send()
+            .await?;
+
+        pr_progress.finish_and_clear();
+
+        pull_request.print_success();
+
+        if self.open_pr {
+            open::that(pull_request.url().as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+struct VersionAudit {
+    manifests: Manifests,
+    hash_drift: Vec<HashDrift>,
+    metadata_drift: Vec<MetadataDrift>,
+}
+
+impl VersionAudit {
+    fn is_clean(&self) -> bool {
+        self.hash_drift.is_empty() && self.metadata_drift.is_empty()
+    }
+
+    fn print(&self) {
+        for drift in &self.hash_drift {
+            println!("    {}", drift.url);
+            println!("      {} {}", "-".red(), drift.expected.red());
+            println!("      {} {}", "+".green(), drift.actual.green());
+        }
+
+        for drift in &self.metadata_drift {
+            println!("    {} ({})", drift.field, drift.url);
+            println!("      {} {}", "-".red(), drift.expected.red());
+            println!("      {} {}", "+".green(), drift.actual.green());
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct HashDrift {
+    url: DecodedUrl,
+    expected: Sha256String,
+    actual: Sha256String,
+}
+
+struct MetadataDrift {
+    url: DecodedUrl,
+    field: &'static str,
+    expected: String,
+    actual: String,
+}
+
+/// Returns the installers whose downloaded hash differs from the hash in the manifest, once per
+/// URL.
+fn hash_drift(
+    installers: &[Installer],
+    hashes: &HashMap<DecodedUrl, Sha256String>,
+) -> Vec<HashDrift> {
+    installers
+        .iter()
+        .unique_by(|installer| &installer.url)
+        .filter_map(|installer| {
+            let actual = hashes.get(&installer.url)?;
+            (*actual != installer.sha_256).then(|| HashDrift {
+                url: installer.url.clone(),
+                expected: installer.sha_256.clone(),
+                actual: actual.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Compares the product code and Apps and Features entries of each manifest installer with those
+/// of the installer analysed from its download. Values that the manifest doesn't set are skipped
+/// as there is nothing to drift from.
+fn metadata_drift(manifests: &Manifests, installer_results: &[Installer]) -> Vec<MetadataDrift> {
+    let previous_installers = manifests
+        .installer
+        .installers
+        .iter()
+        .cloned()
+        .map(|mut installer| {
+            if manifests.installer.product_code.is_some() {
+                installer
+                    .product_code
+                    .clone_from(&manifests.installer.product_code);
+            }
+            if !manifests.installer.apps_and_features_entries.is_empty() {
+                installer
+                    .apps_and_features_entries
+                    .clone_from(&manifests.installer.apps_and_features_entries);
+            }
+            installer
+        })
+        .collect::<Vec<_>>();
+
+    match_installers(previous_installers, installer_results, &HashMap::new())
+        .into_iter()
+        .sorted_by(|(previous, _), (other, _)| previous.url.cmp(&other.url))
+        .flat_map(|(previous, mut analysed)| {
+            let mut drift = Vec::new();
+
+            if let Some(expected) = previous.product_code.as_deref()
+                && analysed.product_code.as_deref() != Some(expected)
+            {
+                drift.push(MetadataDrift {
+                    url: previous.url.clone(),
+                    field: "ProductCode",
+                    expected: expected.to_owned(),
+                    actual: analysed.product_code.unwrap_or_default(),
+                });
+            }
+
+            for entry in &mut analysed.apps_and_features_entries {
+                entry.deduplicate(&manifests.default_locale);
+            }
+
+            if !previous.apps_and_features_entries.is_empty()
+                && previous.apps_and_features_entries != analysed.apps_and_features_entries
+            {
+                drift.push(MetadataDrift {
+                    url: previous.url.clone(),
+                    field: "AppsAndFeaturesEntries",
+                    expected: serde_json::to_string(&previous.apps_and_features_entries)
+                        .unwrap_or_default(),
+                    actual: serde_json::to_string(&analysed.apps_and_features_entries)
+                        .unwrap_or_default(),
+                });
+            }
+
+            drift
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use sha2::{Digest, Sha256};
+    use winget_types::{Sha256String, installer::Installer};
+
+    use super::{HashDrift, hash_drift};
+
+    fn sha_256(data: &[u8]) -> Sha256String {
+        Sha256String::from_digest(&Sha256::digest(data))
+    }
+
+    #[test]
+    fn reports_each_drifted_url_once() {
+        let drifted_url = "https://example.com/setup-x64.exe".parse().unwrap();
+        let unchanged_url = "https://example.com/setup-x86.exe".parse().unwrap();
+
+        let installers = [
+            Installer {
+                url: drifted_url.clone(),
+                sha_256: sha_256(b"x64"),
+                ..Installer::default()
+            },
+            Installer {
+                url: drifted_url.clone(),
+                sha_256: sha_256(b"x64"),
+                ..Installer::default()
+            },
+            Installer {
+                url: unchanged_url.clone(),
+                sha_256: sha_256(b"x86"),
+                ..Installer::default()
+            },
+        ];
+
+        let hashes = HashMap::from([
+            (drifted_url.clone(), sha_256(b"rebuilt x64")),
+            (unchanged_url, sha_256(b"x86")),
+        ]);
+
+        assert_eq!(
+            hash_drift(&installers, &hashes),
+            [HashDrift {
+                url: drifted_url,
+                expected: sha_256(b"x64"),
+                actual: sha_256(b"rebuilt x64"),
+            }]
+        );
+    }
+}
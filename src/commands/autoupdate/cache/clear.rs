@@ -0,0 +1,46 @@
+use anstream::println;
+use clap::Parser;
+use color_eyre::eyre::Result;
+use owo_colors::OwoColorize;
+
+use crate::{commands::autoupdate::cache::NoStrategyCache, prompts::text::confirm_prompt};
+
+/// Remove every package from the no-strategy cache
+#[derive(Parser)]
+pub struct ClearCache {
+    /// Skip the confirmation prompt to clear the cache
+    #[arg(short = 'y', long = "yes")]
+    skip_prompt: bool,
+}
+
+impl ClearCache {
+    pub async fn run(self) -> Result<()> {
+        let mut cache = NoStrategyCache::load().await?;
+
+        if cache.is_empty() {
+            println!("The no-strategy cache is already empty");
+            return Ok(());
+        }
+
+        let confirm = self.skip_prompt
+            || confirm_prompt(&format!(
+                "Would you like to remove all {} packages from the no-strategy cache?",
+                cache.len()
+            ))?;
+
+        if !confirm {
+            println!("{}", "No packages were removed".cyan());
+            return Ok(());
+        }
+
+        let count = cache.clear();
+        cache.persist_if_dirty().await?;
+
+        println!(
+            "{} removed {count} packages from the no-strategy cache",
+            "Successfully".green()
+        );
+
+        Ok(())
+    }
+}
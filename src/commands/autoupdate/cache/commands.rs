@@ -0,0 +1,29 @@
+use clap::{Args, Subcommand};
+use color_eyre::eyre::Result;
+
+use crate::commands::autoupdate::cache::{
+    clear::ClearCache, list::ListCache, remove::RemoveFromCache,
+};
+
+/// Manage the packages that autoupdate skips because no strategy matched them
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    List(ListCache),
+    Clear(ClearCache),
+    Remove(RemoveFromCache),
+}
+
+impl CacheCommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Self::List(list_cache) => list_cache.run().await,
+            Self::Clear(clear_cache) => clear_cache.run().await,
+            Self::Remove(remove_from_cache) => remove_from_cache.run().await,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::num::NonZeroU32;
+
+use anstream::println;
+use chrono::{TimeDelta, Utc};
+use clap::Parser;
+use color_eyre::eyre::Result;
+use owo_colors::OwoColorize;
+
+use crate::commands::autoupdate::cache::{
+    DEFAULT_NO_STRATEGY_TTL_DAYS, NO_STRATEGY_CACHE_FILE_PATH, NoStrategyCache,
+};
+
+/// List the packages in the no-strategy cache
+#[derive(Parser)]
+#[clap(visible_alias = "ls")]
+pub struct ListCache {
+    /// Number of days after which an entry is shown as expired
+    #[arg(long, value_name = "DAYS", default_value_t = NonZeroU32::new(DEFAULT_NO_STRATEGY_TTL_DAYS).unwrap())]
+    ttl: NonZeroU32,
+}
+
+impl ListCache {
+    pub async fn run(self) -> Result<()> {
+        let cache = NoStrategyCache::load().await?;
+
+        if cache.is_empty() {
+            println!("The no-strategy cache at {NO_STRATEGY_CACHE_FILE_PATH} is empty");
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let ttl = TimeDelta::days(i64::from(self.ttl.get()));
+
+        for entry in cache.entries() {
+            let cached = entry.cached_at.map_or_else(
+                || String::from("at an unknown time"),
+                |cached_at| format!("{} days ago", (now - cached_at).num_days()),
+            );
+
+            if entry.is_expired(now, ttl) {
+                println!(
+                    "{} {} ({}, cached {cached})",
+                    entry.package_identifier.bold(),
+                    "expired".yellow(),
+                    entry.reason.as_str()
+                );
+            } else {
+                println!(
+                    "{} ({}, cached {cached})",
+                    entry.package_identifier.bold(),
+                    entry.reason.as_str()
+                );
+            }
+
+            if let Some(url) = &entry.url {
+                println!("  {}", url.blue());
+            }
+        }
+
+        println!("\n{} cached packages", cache.len());
+
+        Ok(())
+    }
+}
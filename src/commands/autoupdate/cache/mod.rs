@@ -0,0 +1,257 @@
+pub mod clear;
+pub mod commands;
+pub mod list;
+pub mod remove;
+
+use std::collections::BTreeMap;
+
+use camino::Utf8PathBuf;
+use chrono::{DateTime, TimeDelta, Utc};
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::warn;
+use winget_types::{PackageIdentifier, url::DecodedUrl};
+
+pub const NO_STRATEGY_CACHE_FILE_PATH: &str = ".komac/autoupdate/no_strategy_cache.json";
+
+/// The number of days after which a package in the no-strategy cache is tried again, so that it
+/// can pick up strategies that were added since it was cached.
+pub const DEFAULT_NO_STRATEGY_TTL_DAYS: u32 = 30;
+
+/// Why autoupdate couldn't find a strategy for a package.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoStrategyReason {
+    /// None of the automatic strategies recognised the source URL.
+    #[default]
+    NoStrategyMatched,
+}
+
+impl NoStrategyReason {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NoStrategyMatched => "no strategy matched",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NoStrategyCacheEntry {
+    pub package_identifier: String,
+
+    /// The source URL that no strategy could resolve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<DecodedUrl>,
+
+    #[serde(default)]
+    pub reason: NoStrategyReason,
+
+    /// When the package was cached. Entries written before this was recorded don't have one, so
+    /// they are stamped with the time they are first loaded rather than being tried again at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<DateTime<Utc>>,
+}
+
+impl NoStrategyCacheEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>, ttl: TimeDelta) -> bool {
+        self.cached_at
+            .is_some_and(|cached_at| now - cached_at > ttl)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct NoStrategyCacheFile {
+    entries: Vec<NoStrategyCacheEntry>,
+}
+
+#[derive(Default)]
+pub struct NoStrategyCache {
+    entries: BTreeMap<String, NoStrategyCacheEntry>,
+    is_dirty: bool,
+}
+
+impl NoStrategyCache {
+    /// Creates the cache from its file, stamping entries that predate `cached_at` with `now`.
+    fn from_file(file: NoStrategyCacheFile, now: DateTime<Utc>) -> Self {
+        let mut is_dirty = false;
+        let entries = file
+            .entries
+            .into_iter()
+            .map(|mut entry| {
+                if entry.cached_at.is_none() {
+                    entry.cached_at = Some(now);
+                    is_dirty = true;
+                }
+                (entry.package_identifier.clone(), entry)
+            })
+            .collect();
+
+        Self { entries, is_dirty }
+    }
+
+    pub async fn load() -> Result<Self> {
+        let path = Utf8PathBuf::from(NO_STRATEGY_CACHE_FILE_PATH);
+        let content = match fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(error) => {
+                return Err(error)
+                    .wrap_err_with(|| format!("Failed to read no-strategy cache at {path}"));
+            }
+        };
+
+        let parsed = match serde_json::from_str::<NoStrategyCacheFile>(&content) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                warn!(
+                    path = %path,
+                    error = %error,
+                    "Failed to parse no-strategy cache JSON; ignoring cache file"
+                );
+                return Ok(Self::default());
+            }
+        };
+
+        Ok(Self::from_file(parsed, Utc::now()))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cached packages, sorted by package identifier.
+    pub fn entries(&self) -> impl Iterator<Item = &NoStrategyCacheEntry> {
+        self.entries.values()
+    }
+
+    pub fn contains(&self, package_identifier: &PackageIdentifier) -> bool {
+        self.entries.contains_key(package_identifier.as_str())
+    }
+
+    pub fn insert(
+        &mut self,
+        package_identifier: &PackageIdentifier,
+        url: Option<DecodedUrl>,
+        reason: NoStrategyReason,
+        now: DateTime<Utc>,
+    ) {
+        self.entries.insert(
+            package_identifier.to_string(),
+            NoStrategyCacheEntry {
+                package_identifier: package_identifier.to_string(),
+                url,
+                reason,
+                cached_at: Some(now),
+            },
+        );
+        self.is_dirty = true;
+    }
+
+    /// Removes a package from the cache and returns whether it was cached.
+    pub fn remove(&mut self, package_identifier: &PackageIdentifier) -> bool {
+        let removed = self.entries.remove(package_identifier.as_str()).is_some();
+        self.is_dirty |= removed;
+        removed
+    }
+
+    /// Removes every package from the cache and returns how many there were.
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        self.is_dirty |= count > 0;
+        count
+    }
+
+    /// Removes the packages that were cached longer ago than the TTL and returns how many there
+    /// were.
+    pub fn prune_expired(&mut self, now: DateTime<Utc>, ttl: TimeDelta) -> usize {
+        let count = self.entries.len();
+        self.entries.retain(|_, entry| !entry.is_expired(now, ttl));
+        let pruned = count - self.entries.len();
+        self.is_dirty |= pruned > 0;
+        pruned
+    }
+
+    pub async fn persist_if_dirty(&mut self) -> Result<()> {
+        if !self.is_dirty {
+            return Ok(());
+        }
+
+        let path = Utf8PathBuf::from(NO_STRATEGY_CACHE_FILE_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .wrap_err_with(|| format!("Failed to create cache directory {parent}"))?;
+        }
+
+        let serialized = serde_json::to_string_pretty(&NoStrategyCacheFile {
+            entries: self.entries.values().cloned().collect(),
+        })
+        .wrap_err("Failed to serialize no-strategy cache")?;
+
+        fs::write(&path, format!("{serialized}\n"))
+            .await
+            .wrap_err_with(|| format!("Failed to write no-strategy cache at {path}"))?;
+
+        self.is_dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, Utc};
+    use indoc::indoc;
+
+    use super::{NoStrategyCache, NoStrategyCacheFile, NoStrategyReason};
+
+    #[test]
+    fn stamps_entries_without_a_timestamp_when_loaded() {
+        let content = indoc! {r#"
+            {
+              "entries": [
+                { "package_identifier": "Example.Legacy" },
+                {
+                  "package_identifier": "Example.Package",
+                  "url": "https://example.com/download",
+                  "reason": "no-strategy-matched",
+                  "cached_at": "2026-01-01T00:00:00Z"
+                }
+              ]
+            }
+        "#};
+
+        let now = "2026-01-15T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut cache = NoStrategyCache::from_file(
+            serde_json::from_str::<NoStrategyCacheFile>(content).unwrap(),
+            now,
+        );
+        assert!(cache.is_dirty);
+
+        assert_eq!(cache.prune_expired(now, TimeDelta::days(30)), 0);
+        assert!(cache.contains(&"Example.Legacy".parse().unwrap()));
+        assert!(
+            cache
+                .entries()
+                .all(|entry| entry.reason == NoStrategyReason::NoStrategyMatched)
+        );
+
+        // The stamped entry expires a full TTL after it was loaded
+        let later = "2026-02-05T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(cache.prune_expired(later, TimeDelta::days(30)), 1);
+        assert!(cache.contains(&"Example.Legacy".parse().unwrap()));
+
+        let much_later = now + TimeDelta::days(31);
+        assert_eq!(cache.prune_expired(much_later, TimeDelta::days(30)), 1);
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+use anstream::println;
+use clap::Parser;
+use color_eyre::eyre::Result;
+use owo_colors::OwoColorize;
+use winget_types::PackageIdentifier;
+
+use crate::commands::autoupdate::cache::NoStrategyCache;
+
+/// Remove packages from the no-strategy cache so that autoupdate tries them again
+#[derive(Parser)]
+#[clap(visible_alias = "rm")]
+pub struct RemoveFromCache {
+    /// The identifiers of the packages to remove
+    #[arg(required = true)]
+    package_identifiers: Vec<PackageIdentifier>,
+}
+
+impl RemoveFromCache {
+    pub async fn run(self) -> Result<()> {
+        let mut cache = NoStrategyCache::load().await?;
+
+        for package_identifier in &self.package_identifiers {
+            if cache.remove(package_identifier) {
+                println!("{} {package_identifier}", "Removed".green());
+            } else {
+                println!("{package_identifier} is not in the no-strategy cache");
+            }
+        }
+
+        cache.persist_if_dirty().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,772 @@
+use std::fmt;
+
+use chrono::SecondsFormat;
+use color_eyre::eyre::{Result, bail, ensure, eyre};
+use winget_types::PackageIdentifier;
+
+use super::recipe::{
+    LATEST_SCHEMA, RecipeState, RecipeStateUpdate, SCHEMA_KEY, SOURCES_KEY, STATE_KEY,
+};
+
+/// The number of spaces that the document indents the blocks it adds by.
+const INDENT: usize = 2;
+
+/// A recipes file that autoupdate writes observed state back into.
+///
+/// Edits only replace, insert or remove the lines of the values that change, so comments, blank
+/// lines, key order, the quoting of existing values and the line endings are kept as they were.
+/// Only block-style entries can be edited.
+pub struct RecipesDocument {
+    lines: Vec<String>,
+    line_ending: &'static str,
+    has_final_line_ending: bool,
+    is_dirty: bool,
+}
+
+impl RecipesDocument {
+    pub fn new(file_content: &str) -> Self {
+        Self {
+            lines: file_content.lines().map(str::to_owned).collect(),
+            line_ending: if file_content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            },
+            has_final_line_ending: file_content.is_empty() || file_content.ends_with('\n'),
+            is_dirty: false,
+        }
+    }
+
+    #[inline]
+    pub const fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    /// Marks the document as written, so it is only written again once it changes.
+    #[inline]
+    pub const fn mark_saved(&mut self) {
+        self.is_dirty = false;
+    }
+
+    /// Sets the `value` of every source of a recipe whose URL has an update, inserting it after
+    /// the source's `header` if it doesn't have one yet.
+    ///
+    /// Returns the number of values that changed.
+    pub fn set_source_values(
+        &mut self,
+        package_identifier: &PackageIdentifier,
+        state_updates: &[RecipeStateUpdate],
+    ) -> usize {
+        let Some((start, end)) = self.entry_range(package_identifier) else {
+            return 0;
+        };
+
+        let mut changed = 0;
+
+        // Visit the sources from the bottom up so that inserted lines don't move the ones left
+        for index in (start + 1..end).rev() {
+            let Some(field) = Field::parse(&self.lines[index]) else {
+                continue;
+            };
+
+            if field.key != "url" {
+                continue;
+            }
+
+            let url = Scalar::parse(field.rest).value;
+            let Some(update) = state_updates
+                .iter()
+                .find(|update| update.url.as_str() == url)
+            else {
+                continue;
+            };
+
+            let indent = field.indent;
+            let source_end = self.block_end(index + 1, end, indent);
+            let insert_at = self
+                .find_field(index + 1, source_end, indent, "header")
+                .map_or(index + 1, |header_index| header_index + 1);
+
+            if self.set_field(
+                index + 1,
+                source_end,
+                indent,
+                "value",
+                &update.value,
+                insert_at,
+            ) {
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Writes the state of a recipe to its `state` key, removing the fields that are unset.
+    ///
+    /// An entry that only lists sources is turned into a recipe object first, which also
+    /// upgrades the file to the latest schema. An empty state leaves the entry as it is.
+    pub fn set_state(
+        &mut self,
+        package_identifier: &PackageIdentifier,
+        state: &RecipeState,
+    ) -> Result<()> {
+        let fields = [
+            (
+                "last-version",
+                state
+                    .last_version
+                    .as_ref()
+                    .map(|version| version.as_str().to_owned()),
+            ),
+            (
+                "last-success",
+                state
+                    .last_success
+                    .map(|last_success| last_success.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            ),
+            ("last-error", state.last_error.clone()),
+        ];
+
+        if fields.iter().all(|(_, value)| value.is_none()) {
+            return Ok(());
+        }
+
+        let (start, end) = self
+            .entry_range(package_identifier)
+            .ok_or_else(|| eyre!("{package_identifier} is not in the recipes file"))?;
+
+        let (key_indent, end) = self.make_recipe_object(package_identifier, start, end)?;
+
+        let (state_index, mut state_end) =
+            match self.find_field(start + 1, end, key_indent, STATE_KEY) {
+                Some(state_index) => {
+                    let field =
+                        Field::parse(&self.lines[state_index]).unwrap_or_else(|| unreachable!());
+                    ensure!(
+                        Scalar::parse(field.rest).value.is_empty(),
+                        "The state of {package_identifier} must be a block mapping"
+                    );
+                    (
+                        state_index,
+                        self.block_end(state_index + 1, end, key_indent + 1),
+                    )
+                }
+                None => {
+                    self.lines
+                        .insert(end, format!("{}{STATE_KEY}:", " ".repeat(key_indent)));
+                    self.is_dirty = true;
+                    (end, end + 1)
+                }
+            };
+
+        let field_indent = (state_index + 1..state_end)
+            .find_map(|index| Field::parse(&self.lines[index]))
+            .map_or(key_indent + INDENT, |field| field.indent);
+
+        for (key, value) in fields {
+            let lines = self.lines.len();
+            match value {
+                Some(value) => {
+                    self.set_field(
+                        state_index + 1,
+                        state_end,
+                        field_indent,
+                        key,
+                        &value,
+                        state_end,
+                    );
+                }
+                None => {
+                    if let Some(index) =
+                        self.find_field(state_index + 1, state_end, field_indent, key)
+                    {
+                        self.lines.remove(index);
+                        self.is_dirty = true;
+                    }
+                }
+            }
+            state_end = state_end + self.lines.len() - lines;
+        }
+
+        self.ensure_latest_schema();
+
+        Ok(())
+    }
+
+    /// Turns an entry that only lists sources into a recipe object by moving the sources under
+    /// `sources`.
+    ///
+    /// Returns the indent of the recipe object's keys and the new end of the entry.
+    fn make_recipe_object(
+        &mut self,
+        package_identifier: &PackageIdentifier,
+        start: usize,
+        end: usize,
+    ) -> Result<(usize, usize)> {
+        let header = Field::parse(&self.lines[start]).unwrap_or_else(|| unreachable!());
+        let value = header.rest.trim_start();
+
+        if !value.is_empty() && !value.starts_with('#') {
+            if value.starts_with(['{', '[', '|', '>', '&', '*', '!']) {
+                bail!("Can't edit the flow-style recipe entry for {package_identifier}");
+            }
+
+            // `ID: URL` becomes `ID:` with the URL moved to `sources: URL`
+            let header =
+                self.lines[start][..self.lines[start].len() - header.rest.len()].to_owned();
+            let sources = format!("{}{SOURCES_KEY}: {value}", " ".repeat(INDENT));
+            self.lines[start] = header;
+            self.lines.insert(start + 1, sources);
+            self.is_dirty = true;
+            return Ok((INDENT, end + 1));
+        }
+
+        let first = (start + 1..end)
+            .find(|index| !is_ignorable(&self.lines[*index]))
+            .ok_or_else(|| eyre!("The recipe entry for {package_identifier} has no sources"))?;
+        let indent = leading_spaces(&self.lines[first]);
+
+        if !self.lines[first].trim_start().starts_with('-')
+            && self.find_field(first, end, indent, SOURCES_KEY).is_some()
+        {
+            return Ok((indent, end));
+        }
+
+        // A list of sources or a single source object is moved under `sources`
+        let key_indent = indent.max(INDENT);
+        let shift = " ".repeat(key_indent - indent + INDENT);
+        for line in &mut self.lines[first..end] {
+            if !line.is_empty() {
+                line.insert_str(0, &shift);
+            }
+        }
+        self.lines
+            .insert(first, format!("{}{SOURCES_KEY}:", " ".repeat(key_indent)));
+        self.is_dirty = true;
+
+        Ok((key_indent, end + 1))
+    }
+
+    /// Sets the root `schema` key to the latest schema, inserting it before the first entry if
+    /// the file doesn't declare one.
+    fn ensure_latest_schema(&mut self) {
+        let schema = LATEST_SCHEMA.to_string();
+
+        if let Some(index) = self.find_field(0, self.lines.len(), 0, SCHEMA_KEY) {
+            self.set_field(index, index + 1, 0, SCHEMA_KEY, &schema, index);
+            return;
+        }
+
+        let index = self
+            .lines
+            .iter()
+            .position(|line| !is_ignorable(line) && !line.starts_with("---"))
+            .unwrap_or(self.lines.len());
+        self.lines.insert(index, format!("{SCHEMA_KEY}: {schema}"));
+        self.is_dirty = true;
+    }
+
+    /// Returns the line of the recipe's key and the line after the last line of its value.
+    fn entry_range(&self, package_identifier: &PackageIdentifier) -> Option<(usize, usize)> {
+        let start = self.find_field(0, self.lines.len(), 0, package_identifier.as_str())?;
+        Some((start, self.block_end(start + 1, self.lines.len(), 1)))
+    }
+
+    /// Returns the line after the last line from `start` that is indented by at least `indent`,
+    /// leaving out the comments and blank lines that follow it.
+    fn block_end(&self, start: usize, end: usize, indent: usize) -> usize {
+        let mut block_end = start;
+        for index in start..end {
+            let line = &self.lines[index];
+            if is_ignorable(line) {
+                continue;
+            }
+            if leading_spaces(line) < indent {
+                break;
+            }
+            block_end = index + 1;
+        }
+        block_end
+    }
+
+    fn find_field(&self, start: usize, end: usize, indent: usize, key: &str) -> Option<usize> {
+        (start..end).find(|index| {
+            Field::parse(&self.lines[*index])
+                .is_some_and(|field| field.indent == indent && field.key == key)
+        })
+    }
+
+    /// Replaces the value of a key between `start` and `end`, keeping its quoting and comment, or
+    /// inserts the key as a double-quoted string at `insert_at` if it isn't there.
+    ///
+    /// Returns whether the document changed.
+    fn set_field(
+        &mut self,
+        start: usize,
+        end: usize,
+        indent: usize,
+        key: &str,
+        value: &str,
+        insert_at: usize,
+    ) -> bool {
+        let Some(index) = self.find_field(start, end, indent, key) else {
+            self.lines.insert(
+                insert_at,
+                format!(
+                    "{}{key}: {}",
+                    " ".repeat(indent),
+                    render_scalar(value, Some('"'))
+                ),
+            );
+            self.is_dirty = true;
+            return true;
+        };
+
+        let line = &self.lines[index];
+        let field = Field::parse(line).unwrap_or_else(|| unreachable!());
+        let scalar = Scalar::parse(field.rest);
+        if scalar.value == value {
+            return false;
+        }
+
+        let key_end = line.len() - field.rest.len();
+        let updated = format!(
+            "{} {}{}",
+            &line[..key_end],
+            render_scalar(value, scalar.quote),
+            scalar.comment
+        );
+        self.lines[index] = updated;
+        self.is_dirty = true;
+        true
+    }
+}
+
+impl fmt::Display for RecipesDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, line) in self.lines.iter().enumerate() {
+            f.write_str(line)?;
+            if index + 1 < self.lines.len() || self.has_final_line_ending {
+                f.write_str(self.line_ending)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `key: value` line of a block mapping, which may also be the first line of a list item.
+struct Field<'line> {
+    /// The column that the key starts at.
+    indent: usize,
+    key: &'line str,
+    /// Everything after the colon, including any comment.
+    rest: &'line str,
+}
+
+impl<'line> Field<'line> {
+    fn parse(line: &'line str) -> Option<Self> {
+        if is_ignorable(line) {
+            return None;
+        }
+
+        let mut indent = leading_spaces(line);
+        let mut text = &line[indent..];
+
+        if let Some(item) = text.strip_prefix('-')
+            && (item.is_empty() || item.starts_with(' '))
+        {
+            let item_text = item.trim_start_matches(' ');
+            indent += text.len() - item_text.len();
+            text = item_text;
+        }
+
+        let (key, after_key) = match text.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let close = text[1..].find(quote)? + 1;
+                (&text[1..close], &text[close + 1..])
+            }
+            _ => {
+                let colon = text.match_indices(':').find_map(|(index, _)| {
+                    text[index + 1..]
+                        .chars()
+                        .next()
+                        .is_none_or(|next| next == ' ')
+                        .then_some(index)
+                })?;
+                (text[..colon].trim_end(), &text[colon..])
+            }
+        };
+
+        Some(Self {
+            indent,
+            key,
+            rest: after_key.trim_start_matches(' ').strip_prefix(':')?,
+        })
+    }
+}
+
+/// A scalar value along with the comment that follows it on the same line.
+struct Scalar<'line> {
+    value: String,
+    quote: Option<char>,
+    comment: &'line str,
+}
+
+impl<'line> Scalar<'line> {
+    fn parse(text: &'line str) -> Self {
+        let text = text.trim_start();
+
+        match text.chars().next() {
+            Some('"') => {
+                let mut value = String::new();
+                let mut chars = text.char_indices().skip(1);
+                while let Some((index, character)) = chars.next() {
+                    match character {
+                        '"' => {
+                            return Self {
+                                value,
+                                quote: Some('"'),
+                                comment: &text[index + 1..],
+                            };
+                        }
+                        '\\' => match chars.next() {
+                            Some((_, 'n')) => value.push('\n'),
+                            Some((_, 't')) => value.push('\t'),
+                            Some((_, escaped)) => value.push(escaped),
+                            None => break,
+                        },
+                        _ => value.push(character),
+                    }
+                }
+                Self {
+                    value,
+                    quote: Some('"'),
+                    comment: "",
+                }
+            }
+            Some('\'') => {
+                let mut value = String::new();
+                let mut index = 1;
+                while let Some(offset) = text[index..].find('\'') {
+                    value.push_str(&text[index..index + offset]);
+                    index += offset + 1;
+                    if text[index..].starts_with('\'') {
+                        value.push('\'');
+                        index += 1;
+                    } else {
+                        return Self {
+                            value,
+                            quote: Some('\''),
+                            comment: &text[index..],
+                        };
+                    }
+                }
+                value.push_str(&text[index..]);
+                Self {
+                    value,
+                    quote: Some('\''),
+                    comment: "",
+                }
+            }
+            _ => {
+                let value_end = if text.starts_with('#') {
+                    0
+                } else {
+                    text.find(" #").unwrap_or(text.len())
+                };
+                let value = text[..value_end].trim_end();
+                Self {
+                    value: value.to_owned(),
+                    quote: None,
+                    comment: &text[value.len()..],
+                }
+            }
+        }
+    }
+}
+
+/// Renders a string in the given quote style, falling back to double quotes for a plain string
+/// that YAML would read differently.
+fn render_scalar(value: &str, quote: Option<char>) -> String {
+    match quote {
+        Some('\'') if !value.contains('\n') => format!("'{}'", value.replace('\'', "''")),
+        None if is_plain_safe(value) => value.to_owned(),
+        _ => format!("\"{}\"", escape_yaml_string(value)),
+    }
+}
+
+/// Returns whether a string can be written unquoted and still be read back as the same string.
+///
+/// Besides the indicators that YAML would parse as syntax, a value such as `1.10`, `true`, `~` or
+/// `0x1F` would be read back as a number, boolean or null.
+fn is_plain_safe(value: &str) -> bool {
+    !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        && !value.ends_with(':')
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.contains(['\n', '\t'])
+        && matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(value),
+            Ok(serde_yaml::Value::String(parsed)) if parsed == value
+        )
+}
+
+fn escape_yaml_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn is_ignorable(line: &str) -> bool {
+    let line = line.trim_start();
+    line.is_empty() || line.starts_with('#')
+}
+
+fn leading_spaces(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use winget_types::PackageIdentifier;
+
+    use super::{RecipesDocument, render_scalar};
+    use crate::commands::autoupdate::recipe::{RecipeState, RecipeStateUpdate, parse_recipes};
+
+    fn package_identifier() -> PackageIdentifier {
+        "Example.Package".parse().unwrap()
+    }
+
+    fn state() -> RecipeState {
+        RecipeState {
+            last_version: Some("1.2.3".parse().unwrap()),
+            last_success: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn updates_source_values_and_keeps_formatting() {
+        let yaml = indoc! {"
+            # Vanity URLs
+            Example.Package:
+              - url: https://example.com/x64 # x64
+                header: Content-Length
+                value: '0' # updated by autoupdate
+              - url: 'https://example.com/arm64'
+                header: ETag
+
+            Another.Package: https://example.com/installer.exe
+        "};
+
+        let updates = [
+            RecipeStateUpdate {
+                url: "https://example.com/x64".parse().unwrap(),
+                value: "149218760".to_owned(),
+            },
+            RecipeStateUpdate {
+                url: "https://example.com/arm64".parse().unwrap(),
+                value: r#"W/"abc""#.to_owned(),
+            },
+        ];
+
+        let mut document = RecipesDocument::new(yaml);
+        assert_eq!(
+            document.set_source_values(&package_identifier(), &updates),
+            2
+        );
+        assert_eq!(
+            document.to_string(),
+            indoc! {r#"
+                # Vanity URLs
+                Example.Package:
+                  - url: https://example.com/x64 # x64
+                    header: Content-Length
+                    value: '149218760' # updated by autoupdate
+                  - url: 'https://example.com/arm64'
+                    header: ETag
+                    value: "W/\"abc\""
+
+                Another.Package: https://example.com/installer.exe
+            "#}
+        );
+        assert_eq!(
+            parse_recipes(&document.to_string()).unwrap()[0].1.sources[1]
+                .value
+                .as_deref(),
+            Some(r#"W/"abc""#)
+        );
+    }
+
+    #[test]
+    fn unchanged_source_values_leave_the_document_clean() {
+        let yaml = indoc! {"
+            Example.Package:
+              - url: https://example.com/x64
+                header: Content-Length
+                value: 123
+        "};
+
+        let mut document = RecipesDocument::new(yaml);
+        let updates = [RecipeStateUpdate {
+            url: "https://example.com/x64".parse().unwrap(),
+            value: "123".to_owned(),
+        }];
+
+        assert_eq!(
+            document.set_source_values(&package_identifier(), &updates),
+            0
+        );
+        assert!(!document.is_dirty());
+    }
+
+    #[test]
+    fn moves_url_entry_into_recipe_object() {
+        let yaml = indoc! {"
+            # Recipes
+            Example.Package: https://github.com/example/package # stable only
+            Another.Package: https://example.com/installer.exe
+        "};
+
+        let mut document = RecipesDocument::new(yaml);
+        document.set_state(&package_identifier(), &state()).unwrap();
+        assert_eq!(
+            document.to_string(),
+            indoc! {r#"
+                # Recipes
+                schema: 2
+                Example.Package:
+                  sources: https://github.com/example/package # stable only
+                  state:
+                    last-version: "1.2.3"
+                    last-success: "2026-01-01T00:00:00Z"
+                Another.Package: https://example.com/installer.exe
+            "#}
+        );
+
+        let recipes = parse_recipes(&document.to_string()).unwrap();
+        assert_eq!(recipes[0].1.state, state());
+        assert_eq!(recipes[1].1.sources.len(), 1);
+    }
+
+    #[test]
+    fn moves_source_list_into_recipe_object() {
+        let yaml = indoc! {"
+            schema: 1
+            Example.Package:
+              # Both architectures
+              - https://example.com/x64.exe
+              - url: https://example.com/arm64.exe
+                header: ETag
+                value: abc
+        "};
+
+        let mut document = RecipesDocument::new(yaml);
+        document.set_state(&package_identifier(), &state()).unwrap();
+        assert_eq!(
+            document.to_string(),
+            indoc! {r#"
+                schema: 2
+                Example.Package:
+                  # Both architectures
+                  sources:
+                    - https://example.com/x64.exe
+                    - url: https://example.com/arm64.exe
+                      header: ETag
+                      value: abc
+                  state:
+                    last-version: "1.2.3"
+                    last-success: "2026-01-01T00:00:00Z"
+            "#}
+        );
+
+        let recipes = parse_recipes(&document.to_string()).unwrap();
+        assert_eq!(recipes[0].1.sources.len(), 2);
+        assert_eq!(recipes[0].1.state, state());
+    }
+
+    #[test]
+    fn updates_existing_state() {
+        let yaml = indoc! {r#"
+            schema: 2
+            Example.Package:
+              sources: https://github.com/example/package
+              state:
+                last-version: "1.2.2"
+                last-error: "Failed to download" # cleared on success
+              strategy: github-releases
+        "#};
+
+        let mut document = RecipesDocument::new(yaml);
+        document.set_state(&package_identifier(), &state()).unwrap();
+        assert_eq!(
+            document.to_string(),
+            indoc! {r#"
+                schema: 2
+                Example.Package:
+                  sources: https://github.com/example/package
+                  state:
+                    last-version: "1.2.3"
+                    last-success: "2026-01-01T00:00:00Z"
+                  strategy: github-releases
+            "#}
+        );
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let yaml = "# Recipes\r\nExample.Package:\r\n  - url: https://example.com/x64\r\n    header: ETag\r\n";
+
+        let mut document = RecipesDocument::new(yaml);
+        let updates = [RecipeStateUpdate {
+            url: "https://example.com/x64".parse().unwrap(),
+            value: "abc".to_owned(),
+        }];
+
+        assert_eq!(
+            document.set_source_values(&package_identifier(), &updates),
+            1
+        );
+        assert_eq!(
+            document.to_string(),
+            "# Recipes\r\nExample.Package:\r\n  - url: https://example.com/x64\r\n    header: ETag\r\n    value: \"abc\"\r\n"
+        );
+    }
+
+    #[test]
+    fn rejects_flow_style_entries() {
+        let yaml = indoc! {"
+            Example.Package: [https://example.com/x64.exe, https://example.com/arm64.exe]
+        "};
+
+        let mut document = RecipesDocument::new(yaml);
+        assert!(document.set_state(&package_identifier(), &state()).is_err());
+        assert!(!document.is_dirty());
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.3")]
+    #[case("W/\"abc\"", "W/\"abc\"")]
+    #[case("1.10", "\"1.10\"")]
+    #[case("42", "\"42\"")]
+    #[case("0x1F", "\"0x1F\"")]
+    #[case("true", "\"true\"")]
+    #[case("null", "\"null\"")]
+    #[case("~", "\"~\"")]
+    fn quotes_plain_scalars_that_would_not_read_back_as_strings(
+        #[case] value: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(render_scalar(value, None), expected);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::HashSet, ffi::OsStr, path::Path};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::AsyncWriteExt,
+};
+use tracing::warn;
+use winget_types::PackageIdentifier;
+
+const JOURNAL_DIRECTORY: &str = ".komac/autoupdate";
+
+/// The number of bytes of the recipes file path hash to include in a journal's name.
+const PATH_HASH_LENGTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum JournalOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// A line of the journal, written once a package has been processed.
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalRecord {
+    package_identifier: String,
+    outcome: JournalOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    recorded_at: DateTime<Utc>,
+}
+
+/// An append-only record of the packages that an autoupdate run has processed, so that a run
+/// that stopped early can be resumed without processing them again. The journal is removed once
+/// its run finishes, so that only an unfinished run is ever resumed.
+///
+/// Each `--letter` and recipes file combination has its own journal, so shards that run in
+/// parallel don't share one.
+pub struct Journal {
+    path: Utf8PathBuf,
+    file: File,
+    completed: HashSet<String>,
+}
+
+impl Journal {
+    /// Returns the journal path for the packages that a run processes.
+    ///
+    /// A recipes run is told apart by the stem and a hash of the canonical path of its recipes
+    /// file, so that runs of different recipes files don't resume from each other's journal.
+    pub fn path_for(canonical_recipes_file: Option<&Path>, letter: Option<char>) -> Utf8PathBuf {
+        let mut name = String::from("journal");
+        if let Some(recipes_file) = canonical_recipes_file {
+            let stem = recipes_file
+                .file_stem()
+                .map(OsStr::to_string_lossy)
+                .unwrap_or_default();
+            let hash = Sha256::digest(recipes_file.as_os_str().as_encoded_bytes());
+            name.push_str("-recipes-");
+            name.push_str(&stem);
+            name.push('-');
+            for byte in &hash[..PATH_HASH_LENGTH] {
+                name.push_str(&format!("{byte:02x}"));
+            }
+        }
+        if let Some(letter) = letter {
+            name.push('-');
+            name.extend(letter.to_lowercase());
+        }
+        Utf8Path::new(JOURNAL_DIRECTORY).join(format!("{name}.jsonl"))
+    }
+
+    /// Opens the journal at the given path.
+    ///
+    /// When resuming, the packages that the previous run completed are read from the journal and
+    /// new records are appended to it. Otherwise, the journal is started over.
+    pub async fn open(path: Utf8PathBuf, resume: bool) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .wrap_err_with(|| format!("Failed to create journal directory {parent}"))?;
+        }
+
+        let completed = if resume {
+            match fs::read_to_string(&path).await {
+                Ok(content) => completed_packages(&content),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+                Err(error) => {
+                    return Err(error)
+                        .wrap_err_with(|| format!("Failed to read autoupdate journal at {path}"));
+                }
+            }
+        } else {
+            HashSet::new()
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(resume)
+            .write(true)
+            .truncate(!resume)
+            .open(&path)
+            .await
+            .wrap_err_with(|| format!("Failed to open autoupdate journal at {path}"))?;
+
+        Ok(Self {
+            path,
+            file,
+            completed,
+        })
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Returns the number of packages that the resumed run had already completed.
+    #[inline]
+    pub fn completed(&self) -> usize {
+        self.completed.len()
+    }
+
+    pub fn is_completed(&self, package_identifier: &PackageIdentifier) -> bool {
+        self.completed.contains(package_identifier.as_str())
+    }
+
+    /// Appends the outcome of processing a package to the journal.
+    ///
+    /// A journal that can't be written to only warns, as it shouldn't stop the run itself.
+    pub async fn record<T>(&mut self, package_identifier: &PackageIdentifier, result: &Result<T>) {
+        let record = JournalRecord {
+            package_identifier: package_identifier.to_string(),
+            outcome: if result.is_ok() {
+                JournalOutcome::Succeeded
+            } else {
+                JournalOutcome::Failed
+            },
+            error: result.as_ref().err().map(ToString::to_string),
+            recorded_at: Utc::now(),
+        };
+
+        if let Err(error) = self.append(&record).await {
+            warn!(
+                path = %self.path,
+                package = %package_identifier,
+                error = %error,
+                "Failed to write to the autoupdate journal"
+            );
+        }
+    }
+
+    /// Removes the journal once every package of the run has been processed.
+    ///
+    /// Like writing a record, failing to remove the journal only warns.
+    pub async fn finish(self) {
+        let Self { path, file, .. } = self;
+        drop(file);
+
+        if let Err(error) = fs::remove_file(&path).await
+            && error.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(
+                path = %path,
+                error = %error,
+                "Failed to remove the finished autoupdate journal"
+            );
+        }
+    }
+
+    async fn append(&mut self, record: &JournalRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes()).await?;
+        self.file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Returns the packages whose latest record in the journal succeeded.
+fn completed_packages(content: &str) -> HashSet<String> {
+    let mut completed = HashSet::new();
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        // A run that stopped while writing can leave a partial last line behind
+        let Ok(record) = serde_json::from_str::<JournalRecord>(line) else {
+            warn!(line, "Skipping unreadable autoupdate journal line");
+            continue;
+        };
+
+        match record.outcome {
+            JournalOutcome::Succeeded => completed.insert(record.package_identifier),
+            JournalOutcome::Failed => completed.remove(&record.package_identifier),
+        };
+    }
+
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use camino::Utf8Path;
+    use indoc::indoc;
+    use winget_types::PackageIdentifier;
+
+    use super::{Journal, completed_packages};
+
+    #[test]
+    fn later_records_replace_earlier_ones() {
+        let content = indoc! {r#"
+            {"package_identifier":"Example.A","outcome":"succeeded","recorded_at":"2026-01-01T00:00:00Z"}
+            {"package_identifier":"Example.B","outcome":"succeeded","recorded_at":"2026-01-01T00:00:00Z"}
+            {"package_identifier":"Example.B","outcome":"failed","error":"Rate limited","recorded_at":"2026-01-01T00:01:00Z"}
+            {"package_identifier":"Example.C","outcome":"failed","error":"Rate limited","recorded_at":"2026-01-01T00:01:00Z"}
+            {"package_identifier":"Example.C","outcome":"succeeded","recorded_at":"2026-01-01T00:02:00Z"}
+            {"package_identifier":"Example.D","outc
+        "#};
+
+        let mut completed = completed_packages(content).into_iter().collect::<Vec<_>>();
+        completed.sort_unstable();
+        assert_eq!(completed, ["Example.A", "Example.C"]);
+    }
+
+    #[test]
+    fn journal_path_per_shard() {
+        assert_eq!(
+            Journal::path_for(None, None),
+            ".komac/autoupdate/journal.jsonl"
+        );
+        assert_eq!(
+            Journal::path_for(None, Some('C')),
+            ".komac/autoupdate/journal-c.jsonl"
+        );
+    }
+
+    #[test]
+    fn journal_path_per_recipes_file() {
+        let recipes = Journal::path_for(Some(Path::new("/repo/recipes.yaml")), Some('C'));
+        let other_recipes = Journal::path_for(Some(Path::new("/other/recipes.yaml")), Some('C'));
+
+        assert!(
+            recipes
+                .as_str()
+                .starts_with(".komac/autoupdate/journal-recipes-recipes-")
+        );
+        assert!(recipes.as_str().ends_with("-c.jsonl"));
+        assert_ne!(recipes, other_recipes);
+        assert_eq!(
+            recipes,
+            Journal::path_for(Some(Path::new("/repo/recipes.yaml")), Some('C'))
+        );
+    }
+
+    #[tokio::test]
+    async fn finished_journal_is_not_resumed() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = Utf8Path::from_path(directory.path())
+            .unwrap()
+            .join("journal.jsonl");
+        let package_identifier = "Example.Package".parse::<PackageIdentifier>().unwrap();
+
+        let mut journal = Journal::open(path.clone(), false).await.unwrap();
+        journal.record(&package_identifier, &Ok(())).await;
+
+        let resumed = Journal::open(path.clone(), true).await.unwrap();
+        assert!(resumed.is_completed(&package_identifier));
+        resumed.finish().await;
+        assert!(!path.exists());
+
+        let resumed = Journal::open(path, true).await.unwrap();
+        assert_eq!(resumed.completed(), 0);
+    }
+}
@@ -0,0 +1,985 @@
+pub mod cache;
+pub mod document;
+pub mod journal;
+pub mod recipe;
+pub mod resolve;
+
+use std::{
+    collections::HashSet,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::Arc,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{Result, WrapErr, bail, ensure};
+use futures_util::{StreamExt, stream};
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use winget_types::{PackageIdentifier, PackageVersion, url::DecodedUrl};
+
+use secrecy::SecretString;
+
+use self::{
+    cache::{
+        DEFAULT_NO_STRATEGY_TTL_DAYS, NO_STRATEGY_CACHE_FILE_PATH, NoStrategyCache,
+        NoStrategyReason, commands::CacheArgs,
+    },
+    document::RecipesDocument,
+    journal::Journal,
+    recipe::{Recipe, RecipeSource, RecipeState, RecipeStateUpdate, parse_recipes},
+    resolve::{ResolvedRecipe, resolve_recipe},
+};
+use crate::{
+    commands::{
+        strategies::{AutoUpdateStrategy, StrategyResolveError},
+        update_version::UpdateVersion,
+    },
+    github::{client::GitHub, utils::PullRequestTemplate},
+    token::TokenManager,
+};
+
+const HTML_PAGE_STRATEGY: &str = "html-page";
+
+/// Auto-detect update parameters from an upstream source URL and run update
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct AutoUpdate {
+    #[command(subcommand)]
+    command: Option<AutoUpdateCommands>,
+
+    /// The package's unique identifier
+    package_identifier: Option<PackageIdentifier>,
+
+    /// Source URL used to detect and run an autoupdate strategy (inferred from the winget repo if omitted)
+    #[arg(requires = "package_identifier", value_hint = clap::ValueHint::Url)]
+    url: Option<DecodedUrl>,
+
+    /// YAML file containing `ID: URL`, `ID: [URL, URL, ...]` or `ID: { sources, ... }` recipe entries to process in batch mode
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    recipes: Option<Utf8PathBuf>,
+
+    /// File containing newline-separated package identifier substrings to skip (matches on contains)
+    #[arg(long = "exclude-file", value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    exclude_files: Vec<Utf8PathBuf>,
+
+    /// Restrict processing to a single first-letter manifest folder (for example, 'c' -> manifests/c)
+    #[arg(long = "letter", alias = "start-with", value_name = "LETTER")]
+    letter: Option<char>,
+
+    /// Explicit strategy to use (otherwise auto-detected from URL)
+    #[arg(long, value_enum)]
+    strategy: Option<AutoUpdateStrategy>,
+
+    /// Header name to compare for the vanity-url strategy
+    #[arg(long, requires = "state")]
+    header: Option<String>,
+
+    /// Expected previous value for the vanity-url strategy header check
+    #[arg(long, requires = "header")]
+    state: Option<String>,
+
+    /// Number of packages to process at the same time in batch mode
+    #[arg(long, default_value_t = NonZeroUsize::new(32).unwrap())]
+    concurrent_packages: NonZeroUsize,
+
+    /// Number of installers to download at the same time
+    #[arg(long, default_value_t = NonZeroUsize::new(num_cpus::get()).unwrap())]
+    concurrent_downloads: NonZeroUsize,
+
+    /// List of issues that updating this package would resolve
+    #[arg(long)]
+    resolves: Vec<NonZeroU32>,
+
+    /// Automatically submit a pull request
+    #[arg(short, long)]
+    submit: bool,
+
+    /// Name of external tool that invoked Komac
+    #[arg(long, env = "KOMAC_CREATED_WITH")]
+    created_with: Option<String>,
+
+    /// URL to external tool that invoked Komac
+    #[arg(long, env = "KOMAC_CREATED_WITH_URL", value_hint = clap::ValueHint::Url)]
+    created_with_url: Option<DecodedUrl>,
+
+    /// Directory to output the manifests to
+    #[arg(short, long, env = "OUTPUT_DIRECTORY", value_hint = clap::ValueHint::DirPath)]
+    output: Option<camino::Utf8PathBuf>,
+
+    /// Open pull request link automatically
+    #[arg(long, env = "OPEN_PR")]
+    open_pr: bool,
+
+    /// Run without submitting
+    #[arg(long, env = "DRY_RUN")]
+    dry_run: bool,
+
+    /// Package version to replace
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "latest")]
+    replace: Option<PackageVersion>,
+
+    /// Skip checking for existing pull requests
+    #[arg(long, env)]
+    skip_pr_check: bool,
+
+    /// Stop on the first entry that fails in --recipes mode
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Skip the packages that an unfinished previous run with the same --letter and --recipes
+    /// completed
+    #[arg(long)]
+    resume: bool,
+
+    /// Update recipes even if their sources resolve to the version of their last successful run
+    #[arg(long)]
+    ignore_recipe_state: bool,
+
+    /// Number of days after which a package in the no-strategy cache is tried again
+    #[arg(long, value_name = "DAYS", default_value_t = NonZeroU32::new(DEFAULT_NO_STRATEGY_TTL_DAYS).unwrap())]
+    no_strategy_ttl: NonZeroU32,
+
+    /// Number of days without a successful run after which a failing recipe is reported as stale
+    #[arg(long, value_name = "DAYS", default_value_t = NonZeroU32::new(30).unwrap())]
+    stale_after: NonZeroU32,
+
+    /// YAML file with templates for the commit title and pull request body
+    #[arg(long, env = "KOMAC_PR_TEMPLATE", value_parser = PullRequestTemplate::from_path, value_hint = clap::ValueHint::FilePath)]
+    pr_template: Option<PullRequestTemplate>,
+
+    /// GitHub personal access token with the `public_repo` scope
+    #[arg(short, long, env = "GITHUB_TOKEN")]
+    token: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum AutoUpdateCommands {
+    Cache(CacheArgs),
+}
+
+impl AutoUpdate {
+    pub async fn run(mut self) -> Result<()> {
+        if let Some(AutoUpdateCommands::Cache(cache_args)) = self.command.take() {
+            return cache_args.command.run().await;
+        }
+
+        let excluded_substrings = read_excluded_substrings(&self.exclude_files).await?;
+
+        if !self.exclude_files.is_empty() {
+            info!(
+                files = self.exclude_files.len(),
+                count = excluded_substrings.len(),
+                "Loaded package exclusions from files"
+            );
+        }
+
+        if !excluded_substrings.is_empty() {
+            info!(
+                count = excluded_substrings.len(),
+                "Applying package exclusions (substring match)"
+            );
+        }
+
+        if let Some(letter) = self.letter {
+            info!(
+                letter = letter.to_string(),
+                "Restricting package scan to this letter"
+            );
+        }
+
+        let token = TokenManager::handle(
+            self.token
+                .take()
+                .map(|t| SecretString::new(t.into_boxed_str())),
+        )
+        .await?;
+        let github = GitHub::new(&token)?;
+        let mut no_strategy_cache = NoStrategyCache::load().await?;
+        let expired = no_strategy_cache.prune_expired(
+            Utc::now(),
+            TimeDelta::days(i64::from(self.no_strategy_ttl.get())),
+        );
+        no_strategy_cache.persist_if_dirty().await?;
+
+        info!(
+            count = no_strategy_cache.len(),
+            expired,
+            path = NO_STRATEGY_CACHE_FILE_PATH,
+            "Loaded no-strategy cache entries"
+        );
+
+        let no_strategy_cache = Arc::new(Mutex::new(no_strategy_cache));
+
+        if let Some(recipes_file) = self.recipes.as_ref() {
+            let file_content = fs::read_to_string(recipes_file)
+                .await
+                .wrap_err_with(|| format!("Failed to read {recipes_file}"))?;
+            let recipes = parse_recipes(&file_content)?;
+            let total_recipe_entries = recipes.len();
+            let recipes = recipes
+                .into_iter()
+                .filter(|(package_identifier, _)| {
+                    !is_package_excluded(package_identifier, &excluded_substrings, self.letter)
+                })
+                .collect::<Vec<_>>();
+            let excluded_recipe_entries = total_recipe_entries.saturating_sub(recipes.len());
+
+            info!(
+                file = %recipes_file,
+                count = recipes.len(),
+                "Processing autoupdate recipes"
+            );
+
+            if excluded_recipe_entries > 0 {
+                info!(
+                    excluded = excluded_recipe_entries,
+                    "Skipped excluded recipe entries"
+                );
+            }
+
+            if recipes.is_empty() {
+                info!("No recipe entries left to process after filtering");
+                return Ok(());
+            }
+
+            let canonical_recipes_file = fs::canonicalize(recipes_file)
+                .await
+                .wrap_err_with(|| format!("Failed to resolve {recipes_file}"))?;
+            let mut journal = Journal::open(
+                Journal::path_for(Some(&canonical_recipes_file), self.letter),
+                self.resume,
+            )
+            .await?;
+            let recipes = skip_completed(recipes, &journal, |(package_identifier, _)| {
+                package_identifier
+            });
+
+            let mut document = RecipesDocument::new(&file_content);
+            let now = Utc::now();
+            let mut states = Vec::with_capacity(recipes.len());
+            let mut succeeded = 0usize;
+            let mut skipped = 0usize;
+            let mut failed = 0usize;
+
+            // Each result is saved to the recipes file before it is journaled, so a resumed run
+            // never skips a recipe whose state or source values weren't saved
+            if self.fail_fast {
+                for (package_identifier, recipe) in recipes {
+                    let state = recipe.state.clone();
+                    let result = self
+                        .run_entry(
+                            &github,
+                            token.as_ref(),
+                            Arc::clone(&no_strategy_cache),
+                            package_identifier.clone(),
+                            None,
+                            recipe,
+                        )
+                        .await;
+
+                    let state = self.record_entry_result(
+                        &mut document,
+                        &package_identifier,
+                        state,
+                        &result,
+                        now,
+                    );
+                    write_recipes_document(recipes_file, &mut document).await?;
+                    journal.record(&package_identifier, &result).await;
+
+                    match result {
+                        Ok(outcome) if outcome.no_strategy => skipped += 1,
+                        Ok(_) => succeeded += 1,
+                        Err(error) => {
+                            error!(
+                                package = %package_identifier,
+                                error = %error,
+                                "Autoupdate entry failed"
+                            );
+                            return Err(error);
+                        }
+                    }
+
+                    states.push((package_identifier, state));
+                }
+            } else {
+                let mut results = stream::iter(recipes.into_iter().map(
+                    |(package_identifier, recipe)| async {
+                        let state = recipe.state.clone();
+                        let result = self
+                            .run_entry(
+                                &github,
+                                token.as_ref(),
+                                Arc::clone(&no_strategy_cache),
+                                package_identifier.clone(),
+                                None,
+                                recipe,
+                            )
+                            .await;
+                        (package_identifier, state, result)
+                    },
+                ))
+                .buffer_unordered(self.concurrent_packages.get());
+
+                while let Some((package_identifier, state, result)) = results.next().await {
+                    let state = self.record_entry_result(
+                        &mut document,
+                        &package_identifier,
+                        state,
+                        &result,
+                        now,
+                    );
+                    write_recipes_document(recipes_file, &mut document).await?;
+                    journal.record(&package_identifier, &result).await;
+
+                    match result {
+                        Ok(outcome) if outcome.no_strategy => skipped += 1,
+                        Ok(_) => succeeded += 1,
+                        Err(error) => {
+                            failed += 1;
+                            error!(
+                                package = %package_identifier,
+                                error = %error,
+                                "Autoupdate entry failed"
+                            );
+                        }
+                    }
+
+                    states.push((package_identifier, state));
+                }
+            }
+
+            journal.finish().await;
+
+            let stale = report_stale_recipes(&states, now, self.stale_after);
+
+            info!(
+                succeeded,
+                skipped, failed, stale, "Autoupdate recipes summary"
+            );
+
+            return Ok(());
+        }
+
+        if let Some(package_identifier) = self.package_identifier.clone() {
+            if is_package_excluded(&package_identifier, &excluded_substrings, self.letter) {
+                info!(
+                    package = %package_identifier,
+                    "Package excluded from auto-update; skipping"
+                );
+                return Ok(());
+            }
+
+            let (latest_version, sources) = if let Some(url) = self.url.clone() {
+                (
+                    None,
+                    vec![RecipeSource {
+                        url: Some(url),
+                        page: None,
+                        header: self.header.clone(),
+                        value: self.state.clone(),
+                        primary: false,
+                    }],
+                )
+            } else {
+                let (latest_version, sources) =
+                    sources_from_manifest(&github, &package_identifier).await?;
+                (Some(latest_version), sources)
+            };
+
+            return self
+                .run_entry(
+                    &github,
+                    token.as_ref(),
+                    Arc::clone(&no_strategy_cache),
+                    package_identifier,
+                    latest_version,
+                    Recipe::new(sources),
+                )
+                .await
+                .map(|_| ());
+        }
+
+        info!(
+            letter = self.letter.map(|char| char.to_string()),
+            "Enumerating package identifiers from winget-pkgs"
+        );
+
+        let package_identifiers = github
+            .get_package_identifiers_for_letter(self.letter)
+            .await?;
+
+        info!(
+            count = package_identifiers.len(),
+            "Finished enumerating package identifiers"
+        );
+
+        let total_packages = package_identifiers.len();
+        let package_identifiers = package_identifiers
+            .into_iter()
+            .filter(|package_identifier| {
+                !is_package_excluded(package_identifier, &excluded_substrings, self.letter)
+            })
+            .collect::<Vec<_>>();
+        let excluded_count = total_packages.saturating_sub(package_identifiers.len());
+
+        info!(
+            count = package_identifiers.len(),
+            "No package identifier provided — processing all packages from winget-pkgs"
+        );
+
+        if excluded_count > 0 {
+            info!(excluded = excluded_count, "Skipped excluded packages");
+        }
+
+        if package_identifiers.is_empty() {
+            info!("No packages left to process after filtering");
+            return Ok(());
+        }
+
+        let journal = Journal::open(Journal::path_for(None, self.letter), self.resume).await?;
+        let package_identifiers =
+            skip_completed(package_identifiers, &journal, |package_identifier| {
+                package_identifier
+            });
+        let journal = Mutex::new(journal);
+
+        if self.fail_fast {
+            for package_identifier in package_identifiers {
+                let result = self
+                    .run_package(
+                        &github,
+                        token.as_ref(),
+                        Arc::clone(&no_strategy_cache),
+                        &package_identifier,
+                    )
+                    .await;
+                journal
+                    .lock()
+                    .await
+                    .record(&package_identifier, &result)
+                    .await;
+                result?;
+            }
+            journal.into_inner().finish().await;
+            return Ok(());
+        }
+
+        let results = stream::iter(package_identifiers.into_iter().map(
+            |package_identifier| async {
+                let result = self
+                    .run_package(
+                        &github,
+                        token.as_ref(),
+                        Arc::clone(&no_strategy_cache),
+                        &package_identifier,
+                    )
+                    .await;
+                journal
+                    .lock()
+                    .await
+                    .record(&package_identifier, &result)
+                    .await;
+                (package_identifier, result)
+            },
+        ))
+        .buffer_unordered(self.concurrent_packages.get())
+        .collect::<Vec<_>>()
+        .await;
+
+        journal.into_inner().finish().await;
+
+        let mut succeeded = 0usize;
+        let mut skipped = 0usize;
+        let mut failed = 0usize;
+
+        for (package_identifier, result) in results {
+            match result {
+                Ok(outcome) if outcome.no_strategy => skipped += 1,
+                Ok(_) => succeeded += 1,
+                Err(error) => {
+                    failed += 1;
+                    error!(
+                        package = %package_identifier,
+                        error = %error,
+                        "Autoupdate entry failed"
+                    );
+                }
+            }
+        }
+
+        info!(
+            succeeded,
+            skipped, failed, "Autoupdate all-packages summary"
+        );
+
+        Ok(())
+    }
+
+    /// Runs a package from winget-pkgs with the sources of its latest version.
+    async fn run_package(
+        &self,
+        github: &GitHub,
+        token: &SecretString,
+        no_strategy_cache: Arc<Mutex<NoStrategyCache>>,
+        package_identifier: &PackageIdentifier,
+    ) -> Result<EntryOutcome> {
+        if no_strategy_cache.lock().await.contains(package_identifier) {
+            info!(
+                package = %package_identifier,
+                "Skipping package because no-strategy cache entry exists"
+            );
+            return Ok(EntryOutcome::no_strategy());
+        }
+
+        let latest_version = latest_version_from_manifest(github, package_identifier).await?;
+        let sources =
+            sources_from_manifest_for_version(github, package_identifier, &latest_version).await?;
+
+        self.run_entry(
+            github,
+            token,
+            no_strategy_cache,
+            package_identifier.clone(),
+            Some(latest_version),
+            Recipe::new(sources),
+        )
+        .await
+    }
+
+    async fn run_entry(
+        &self,
+        github: &GitHub,
+        token: &SecretString,
+        no_strategy_cache: Arc<Mutex<NoStrategyCache>>,
+        package_identifier: PackageIdentifier,
+        latest_version: Option<PackageVersion>,
+        recipe: Recipe,
+    ) -> Result<EntryOutcome> {
+        if no_strategy_cache.lock().await.contains(&package_identifier) {
+            info!(
+                package = %package_identifier,
+                "Skipping package because no-strategy cache entry exists"
+            );
+            return Ok(EntryOutcome::no_strategy());
+        }
+
+        let latest_version = if let Some(version) = latest_version {
+            version
+        } else {
+            let versions = github.get_versions(&package_identifier).await?;
+            versions.last().cloned().unwrap_or_else(|| unreachable!())
+        };
+
+        let resolved = resolve_recipe()
+            .github(github)
+            .package_identifier(&package_identifier)
+            .latest_version(&latest_version)
+            .recipe(&recipe)
+            .maybe_strategy(self.strategy)
+            .maybe_header(self.header.as_deref())
+            .maybe_state(self.state.as_deref())
+            .resolve()
+            .await;
+
+        let ResolvedRecipe {
+            package_version,
+            urls: resolved_urls,
+            release_notes_url,
+            should_update,
+            skip_version_check,
+            state_updates,
+            strategy_source,
+            version_decision,
+        } = match resolved {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                let Some(StrategyResolveError::NoStrategyMatched(source_url)) =
+                    error.downcast_ref::<StrategyResolveError>()
+                else {
+                    return Err(error);
+                };
+
+                let mut cache = no_strategy_cache.lock().await;
+                cache.insert(
+                    &package_identifier,
+                    Some(source_url.clone()),
+                    NoStrategyReason::NoStrategyMatched,
+                    Utc::now(),
+                );
+                cache.persist_if_dirty().await?;
+
+                tracing::warn!(
+                    package = %package_identifier,
+                    latest_version = %latest_version,
+                    error = %error,
+                    "No autoupdate strategy matched; cached and skipping"
+                );
+                return Ok(EntryOutcome::no_strategy());
+            }
+        };
+
+        if let Some(version_decision) = &version_decision {
+            info!(
+                package = %package_identifier,
+                source_version = %package_version,
+                decision = %version_decision,
+                "Sources resolved different versions"
+            );
+        }
+
+        let resolved_outcome = EntryOutcome {
+            package_version: Some(package_version.clone()),
+            ..EntryOutcome::default()
+        };
+
+        if !should_update {
+            info!(
+                package = %package_identifier,
+                urls = %resolved_urls.len(),
+                "No update required"
+            );
+            return Ok(resolved_outcome);
+        }
+
+        if !skip_version_check && package_version <= latest_version {
+            info!(
+                package = %package_identifier,
+                latest_version = %latest_version,
+                source_version = %package_version,
+                "Source version is not newer; skipping download and submission"
+            );
+            return Ok(resolved_outcome);
+        }
+
+        if !self.ignore_recipe_state
+            && !skip_version_check
+            && recipe.state.last_error.is_none()
+            && recipe.state.last_version.as_ref() == Some(&package_version)
+        {
+            info!(
+                package = %package_identifier,
+                source_version = %package_version,
+                "Source version is unchanged since the last successful run; skipping download and submission"
+            );
+            return Ok(resolved_outcome);
+        }
+
+        ensure!(
+            !resolved_urls.is_empty(),
+            "None of the resolved URLs for {package_identifier} matched the recipe's asset filters"
+        );
+
+        let update_package_version = if skip_version_check {
+            None
+        } else {
+            Some(package_version)
+        };
+
+        // Sort URLs: non-zip files alphabetically, then zip files alphabetically
+        let (mut non_zip_urls, mut zip_urls): (Vec<_>, Vec<_>) = resolved_urls
+            .into_iter()
+            .partition(|url| !url.as_str().ends_with(".zip"));
+
+        non_zip_urls.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        zip_urls.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        non_zip_urls.extend(zip_urls);
+        let sorted_urls = non_zip_urls;
+
+        UpdateVersion {
+            package_identifier,
+            package_version: update_package_version,
+            urls: sorted_urls,
+            files: Vec::new(),
+            concurrent_downloads: self.concurrent_downloads,
+            resolves: self.resolves.clone(),
+            submit: self.submit,
+            release_notes_url,
+            created_with: self.created_with.clone(),
+            created_with_url: self.created_with_url.clone(),
+            output: self.output.clone(),
+            open_pr: self.open_pr,
+            dry_run: self.dry_run,
+            replace: self.replace.clone(),
+            amend_pr: None,
+            skip_pr_check: self.skip_pr_check,
+            pr_template: self.pr_template.clone(),
+            strategy: strategy_source.as_ref().map(|(strategy, _)| *strategy),
+            source_url: strategy_source.map(|(_, source_url)| source_url),
+            release_notes_rules: recipe.options.release_notes,
+            token: Some(token.clone()),
+        }
+        .run()
+        .await?;
+
+        Ok(EntryOutcome {
+            state_updates,
+            ..resolved_outcome
+        })
+    }
+
+    /// Records the result of running a recipe in the recipes document and returns the recipe's
+    /// new state.
+    fn record_entry_result(
+        &self,
+        document: &mut RecipesDocument,
+        package_identifier: &PackageIdentifier,
+        previous_state: RecipeState,
+        result: &Result<EntryOutcome>,
+        now: DateTime<Utc>,
+    ) -> RecipeState {
+        let mut state = previous_state.clone();
+        match result {
+            // Nothing was resolved, so the recipe neither succeeded nor failed
+            Ok(outcome) if outcome.no_strategy => {}
+            Ok(outcome) => {
+                document.set_source_values(package_identifier, &outcome.state_updates);
+                if let Some(package_version) = &outcome.package_version {
+                    state.last_version = Some(package_version.clone());
+                }
+                state.last_success = Some(now);
+                state.last_error = None;
+            }
+            Err(error) => state.last_error = Some(error.to_string()),
+        }
+
+        // A dry run submits nothing, so recording its version would skip it on the next real run.
+        // The state is only written when its outcome changes so that an unchanged recipe doesn't
+        // rewrite the file on every run.
+        if !self.dry_run
+            && state.has_changed_from(
+                &previous_state,
+                TimeDelta::days(i64::from(self.stale_after.get())),
+            )
+            && let Err(error) = document.set_state(package_identifier, &state)
+        {
+            warn!(
+                package = %package_identifier,
+                error = %error,
+                "Failed to record recipe state"
+            );
+        }
+
+        state
+    }
+}
+
+/// Leaves out the packages that the journal of a resumed run has already completed.
+fn skip_completed<T, F>(entries: Vec<T>, journal: &Journal, package_identifier: F) -> Vec<T>
+where
+    F: Fn(&T) -> &PackageIdentifier,
+{
+    if journal.completed() == 0 {
+        return entries;
+    }
+
+    let total = entries.len();
+    let entries = entries
+        .into_iter()
+        .filter(|entry| !journal.is_completed(package_identifier(entry)))
+        .collect::<Vec<_>>();
+
+    info!(
+        skipped = total - entries.len(),
+        journal = %journal.path(),
+        "Skipping packages that the resumed run already completed"
+    );
+
+    entries
+}
+
+/// What running a single entry observed, to be recorded in the recipes file.
+#[derive(Default)]
+struct EntryOutcome {
+    state_updates: Vec<RecipeStateUpdate>,
+    package_version: Option<PackageVersion>,
+    /// The entry was skipped because no strategy matches its sources.
+    no_strategy: bool,
+}
+
+impl EntryOutcome {
+    fn no_strategy() -> Self {
+        Self {
+            no_strategy: true,
+            ..Self::default()
+        }
+    }
+}
+
+async fn write_recipes_document(
+    recipes_file: &Utf8Path,
+    document: &mut RecipesDocument,
+) -> Result<()> {
+    if document.is_dirty() {
+        fs::write(recipes_file, document.to_string())
+            .await
+            .wrap_err_with(|| format!("Failed to write {recipes_file}"))?;
+        document.mark_saved();
+        info!(file = %recipes_file, "Updated recipe states");
+    }
+
+    Ok(())
+}
+
+/// Warns about every recipe that hasn't run successfully within the given number of days and
+/// returns how many there are.
+fn report_stale_recipes(
+    states: &[(PackageIdentifier, RecipeState)],
+    now: DateTime<Utc>,
+    stale_after: NonZeroU32,
+) -> usize {
+    let max_age = TimeDelta::days(i64::from(stale_after.get()));
+
+    states
+        .iter()
+        .filter(|(_, state)| state.is_stale(now, max_age))
+        .inspect(|(package_identifier, state)| {
+            warn!(
+                package = %package_identifier,
+                last_success = ?state.last_success,
+                last_error = state.last_error.as_deref(),
+                "Recipe has not run successfully in {stale_after} days"
+            );
+        })
+        .count()
+}
+
+fn is_package_excluded(
+    package_identifier: &PackageIdentifier,
+    excluded_substrings: &[String],
+    letter: Option<char>,
+) -> bool {
+    let id_str = package_identifier.as_str();
+    let id_lower = id_str.to_lowercase();
+
+    // Restrict processing to a single first-letter bucket when requested.
+    if let Some(letter) = letter {
+        let first_char = id_lower.chars().next().unwrap_or('a');
+        if first_char != letter.to_lowercase().next().unwrap_or('a') {
+            return true;
+        }
+    }
+
+    // Check if matches any excluded substring
+    excluded_substrings
+        .iter()
+        .any(|substring| id_lower.contains(&substring.to_lowercase()))
+}
+
+async fn read_excluded_substrings(files: &[Utf8PathBuf]) -> Result<Vec<String>> {
+    let mut excluded_substrings = Vec::new();
+
+    for file in files {
+        let file_content = fs::read_to_string(file)
+            .await
+            .wrap_err_with(|| format!("Failed to read exclusion file {file}"))?;
+
+        let parsed = parse_excluded_substrings(&file_content)
+            .wrap_err_with(|| format!("Failed to parse exclusion file {file}"))?;
+
+        excluded_substrings.extend(parsed);
+    }
+
+    Ok(excluded_substrings)
+}
+
+fn parse_excluded_substrings(file_content: &str) -> Result<Vec<String>> {
+    let mut excluded_substrings = Vec::new();
+
+    for line in file_content.lines() {
+        let trimmed_line = line.trim();
+
+        if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+            continue;
+        }
+
+        excluded_substrings.push(trimmed_line.to_string());
+    }
+
+    Ok(excluded_substrings)
+}
+
+/// Fetch the latest installer manifest from the winget-pkgs repository and
+/// build [`RecipeSource`] entries from the unique installer URLs it contains.
+async fn sources_from_manifest(
+    github: &GitHub,
+    package_identifier: &PackageIdentifier,
+) -> Result<(PackageVersion, Vec<RecipeSource>)> {
+    let latest_version = latest_version_from_manifest(github, package_identifier).await?;
+    let sources =
+        sources_from_manifest_for_version(github, package_identifier, &latest_version).await?;
+    Ok((latest_version, sources))
+}
+
+async fn latest_version_from_manifest(
+    github: &GitHub,
+    package_identifier: &PackageIdentifier,
+) -> Result<PackageVersion> {
+    let versions = github.get_versions(package_identifier).await?;
+    Ok(versions.last().cloned().unwrap_or_else(|| unreachable!()))
+}
+
+async fn sources_from_manifest_for_version(
+    github: &GitHub,
+    package_identifier: &PackageIdentifier,
+    latest_version: &PackageVersion,
+) -> Result<Vec<RecipeSource>> {
+    let manifests = github
+        .get_manifests(package_identifier, latest_version)
+        .await?;
+
+    let mut seen = HashSet::new();
+    let sources: Vec<RecipeSource> = manifests
+        .installer
+        .installers
+        .iter()
+        .filter(|installer| seen.insert(installer.url.as_str().to_owned()))
+        .map(|installer| RecipeSource::from_url(installer.url.clone()))
+        .collect();
+
+    ensure!(
+        !sources.is_empty(),
+        "Installer manifest for {package_identifier} {latest_version} contains no installer URLs"
+    );
+
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_excluded_substrings;
+
+    #[test]
+    fn parses_excluded_substrings_from_lines() {
+        let content = r#"
+# comment
+Example.Package
+
+Another.Package
+"#;
+
+        let excluded = parse_excluded_substrings(content).unwrap();
+
+        assert_eq!(excluded.len(), 2);
+        assert!(excluded.iter().any(|s| s == "Example.Package"));
+        assert!(excluded.iter().any(|s| s == "Another.Package"));
+    }
+
+    #[test]
+    fn parses_excluded_substrings_for_substring_match() {
+        let content = r#"
+beta
+alpha.rc
+"#;
+
+        let excluded = parse_excluded_substrings(content).unwrap();
+
+        assert_eq!(excluded.len(), 2);
+        assert!(excluded.iter().any(|s| s == "beta"));
+        assert!(excluded.iter().any(|s| s == "alpha.rc"));
+    }
+}
@@ -0,0 +1,778 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use color_eyre::eyre::{Result, WrapErr, bail, ensure, eyre};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, de::Error as DeError};
+use serde_with::{DisplayFromStr, Map, serde_as};
+use winget_types::{
+    PackageIdentifier, PackageVersion,
+    installer::Architecture,
+    url::{DecodedUrl, ReleaseNotesUrl},
+};
+
+use crate::{
+    commands::strategies::AutoUpdateStrategy,
+    manifests::{ReleaseNotesRules, Url},
+};
+
+/// The root key that declares which version of the recipe schema a file uses.
+pub(super) const SCHEMA_KEY: &str = "schema";
+
+/// The key that turns a recipe entry into a recipe object with options.
+pub(super) const SOURCES_KEY: &str = "sources";
+
+/// The key of a recipe object that autoupdate records the outcome of its last run in.
+pub(super) const STATE_KEY: &str = "state";
+
+/// The latest recipe schema. Schema 1 only has sources, whereas schema 2 adds recipe objects
+/// with per-recipe options.
+pub const LATEST_SCHEMA: u64 = 2;
+
+/// The placeholder that is replaced with the package version in a release notes URL template.
+const VERSION_PLACEHOLDER: &str = "{version}";
+
+#[derive(Debug, Clone)]
+pub struct RecipeStateUpdate {
+    pub url: DecodedUrl,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeSource {
+    pub url: Option<DecodedUrl>,
+    pub page: Option<DecodedUrl>,
+    pub header: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_scalar_string")]
+    pub value: Option<String>,
+    /// Whether the version is taken from this source under the `primary-source` version policy.
+    #[serde(default)]
+    pub primary: bool,
+}
+
+fn deserialize_optional_scalar_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_yaml::Value>::deserialize(deserializer)?;
+
+    match value {
+        None | Some(serde_yaml::Value::Null) => Ok(None),
+        Some(serde_yaml::Value::String(text)) => Ok(Some(text)),
+        Some(serde_yaml::Value::Number(number)) => Ok(Some(number.to_string())),
+        Some(serde_yaml::Value::Bool(value)) => Ok(Some(value.to_string())),
+        Some(_) => Err(D::Error::custom(
+            "value must be a scalar (string/number/bool)",
+        )),
+    }
+}
+
+impl RecipeSource {
+    pub const fn from_url(url: DecodedUrl) -> Self {
+        Self {
+            url: Some(url),
+            page: None,
+            header: None,
+            value: None,
+            primary: false,
+        }
+    }
+
+    /// Returns the URL or page that the source is resolved from.
+    pub fn location(&self) -> Option<&DecodedUrl> {
+        self.url.as_ref().or(self.page.as_ref())
+    }
+}
+
+/// The sources of a package and the options that adjust how its update is resolved.
+#[derive(Debug, Clone, Default)]
+pub struct Recipe {
+    pub sources: Vec<RecipeSource>,
+    pub options: RecipeOptions,
+    pub state: RecipeState,
+}
+
+impl Recipe {
+    pub fn new(sources: Vec<RecipeSource>) -> Self {
+        Self {
+            sources,
+            options: RecipeOptions::default(),
+            state: RecipeState::default(),
+        }
+    }
+
+    /// Returns the index of the source that the `primary-source` version policy takes the version
+    /// from, which is the first source unless another one is marked as primary.
+    pub fn primary_source(&self) -> usize {
+        self.sources
+            .iter()
+            .position(|source| source.primary)
+            .unwrap_or_default()
+    }
+}
+
+/// Options of a schema 2 recipe object.
+///
+/// ```yaml
+/// schema: 2
+/// Example.Package:
+///   sources:
+///     - https://github.com/example/package
+///   strategy: github-releases
+///   channel: beta
+///   assets:
+///     include: '\.msi$'
+///     exclude: 'debug'
+///   architectures:
+///     '-arm64\.': arm64
+///     '-x64\.': x64
+///   version:
+///     strip-prefix: release-
+///     pattern: '_'
+///     replacement: '.'
+///   version-policy: max
+///   release-notes-url: https://example.com/changelog/{version}
+///   release-notes:
+///     exclude-sections:
+///       - '^Dependencies$'
+/// ```
+#[serde_as]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RecipeOptions {
+    /// The strategy to resolve every URL source with, instead of detecting it from the URL.
+    pub strategy: Option<AutoUpdateStrategy>,
+
+    /// The prerelease channel to follow, such as `beta`, instead of the one detected from the
+    /// package identifier.
+    pub channel: Option<String>,
+
+    pub assets: AssetFilter,
+
+    /// Architectures to set on the resolved URLs that match each pattern, as if the URL had an
+    /// `|arch` suffix.
+    #[serde_as(as = "Map<DisplayFromStr, _>")]
+    pub architectures: Vec<(Regex, Architecture)>,
+
+    pub version: VersionTransform,
+
+    /// How the version is chosen when the sources resolve to different versions.
+    pub version_policy: VersionPolicy,
+
+    /// A release notes URL in which `{version}` is replaced with the resolved package version.
+    pub release_notes_url: Option<String>,
+
+    /// Rules for removing content from the release notes.
+    pub release_notes: ReleaseNotesRules,
+}
+
+impl RecipeOptions {
+    /// Filters the resolved URLs by the asset patterns and sets the mapped architecture on each
+    /// URL that doesn't already have one.
+    pub fn select_urls(&self, urls: Vec<Url>) -> Vec<Url> {
+        urls.into_iter()
+            .filter(|url| self.assets.matches(url.as_str()))
+            .map(|mut url| {
+                if url.override_architecture().is_none()
+                    && let Some((_, architecture)) = self
+                        .architectures
+                        .iter()
+                        .find(|(pattern, _)| pattern.is_match(url.as_str()))
+                {
+                    url.overrides_mut().architecture = Some(*architecture);
+                }
+                url
+            })
+            .collect()
+    }
+
+    /// Returns the release notes URL for the given version if the recipe has a template.
+    pub fn release_notes_url(&self, package_version: &PackageVersion) -> Option<ReleaseNotesUrl> {
+        self.release_notes_url.as_deref().and_then(|template| {
+            template
+                .replace(VERSION_PLACEHOLDER, package_version.as_str())
+                .parse()
+                .ok()
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(template) = self.release_notes_url.as_deref() {
+            template
+                .replace(VERSION_PLACEHOLDER, "1.0.0")
+                .parse::<ReleaseNotesUrl>()
+                .wrap_err_with(|| format!("Invalid release notes URL template: {template}"))?;
+        }
+
+        ensure!(
+            self.version.pattern.is_some() == self.version.replacement.is_some(),
+            "Version transform must include both pattern and replacement"
+        );
+
+        Ok(())
+    }
+}
+
+/// How the version of a recipe is chosen when its sources resolve to different versions, such as
+/// when a vendor publishes each architecture on its own cadence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionPolicy {
+    /// Every source must resolve to the same version.
+    #[default]
+    RequireEqual,
+
+    /// The highest version that any source resolved to is used.
+    Max,
+
+    /// The version is taken from the primary source, while the URLs are still collected from every
+    /// source.
+    PrimarySource,
+}
+
+impl VersionPolicy {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::RequireEqual => "require-equal",
+            Self::Max => "max",
+            Self::PrimarySource => "primary-source",
+        }
+    }
+}
+
+/// What autoupdate recorded the last time it ran a recipe.
+///
+/// ```yaml
+/// Example.Package:
+///   sources: https://github.com/example/package
+///   state:
+///     last-version: "1.2.3"
+///     last-success: "2026-01-01T00:00:00Z"
+///     last-error: "Failed to download https://example.com/app.exe"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RecipeState {
+    /// The version that the sources resolved to.
+    pub last_version: Option<PackageVersion>,
+
+    /// When the recipe last ran without an error. An unchanged recipe only writes it back once it
+    /// is a quarter of `--stale-after` old, so it may lag behind the last successful run by that
+    /// much but a recipe that keeps succeeding never becomes stale.
+    pub last_success: Option<DateTime<Utc>>,
+
+    /// The error that the last run failed with.
+    pub last_error: Option<String>,
+}
+
+impl RecipeState {
+    /// Returns whether the recipe hasn't run successfully within the given age.
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: TimeDelta) -> bool {
+        self.last_success
+            .is_none_or(|last_success| now - last_success > max_age)
+    }
+
+    /// Returns whether the state differs enough from the previous state to be written back.
+    ///
+    /// A different version or error always is. `last-success` alone would change on every run, so
+    /// it is only written back once the saved one is a quarter of the stale age old.
+    pub fn has_changed_from(&self, previous: &Self, max_age: TimeDelta) -> bool {
+        self.last_version != previous.last_version
+            || self.last_error != previous.last_error
+            || self.last_success.is_some_and(|last_success| {
+                previous
+                    .last_success
+                    .is_none_or(|previous_success| last_success - previous_success >= max_age / 4)
+            })
+    }
+}
+
+/// Regular expressions that the resolved URLs must match, and must not match, to be used.
+#[serde_as]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AssetFilter {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub include: Option<Regex>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub exclude: Option<Regex>,
+}
+
+impl AssetFilter {
+    pub fn matches(&self, url: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(url))
+            && self
+                .exclude
+                .as_ref()
+                .is_none_or(|exclude| !exclude.is_match(url))
+    }
+}
+
+/// Rewrites a resolved version whose upstream format doesn't match the versions in winget-pkgs.
+///
+/// The prefix is stripped first, then every match of the pattern is replaced.
+#[serde_as]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct VersionTransform {
+    pub strip_prefix: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub pattern: Option<Regex>,
+    pub replacement: Option<String>,
+}
+
+impl VersionTransform {
+    pub fn apply(&self, package_version: PackageVersion) -> Result<PackageVersion> {
+        if self.strip_prefix.is_none() && self.pattern.is_none() {
+            return Ok(package_version);
+        }
+
+        let mut version = package_version.as_str();
+        if let Some(prefix) = self.strip_prefix.as_deref() {
+            version = version.strip_prefix(prefix).unwrap_or(version);
+        }
+
+        let version = match (&self.pattern, self.replacement.as_deref()) {
+            (Some(pattern), Some(replacement)) => pattern.replace_all(version, replacement),
+            _ => version.into(),
+        };
+
+        version.parse().wrap_err_with(|| {
+            format!("Transformed version {version} of {package_version} is not a valid version")
+        })
+    }
+}
+
+pub fn parse_recipes(file_content: &str) -> Result<Vec<(PackageIdentifier, Recipe)>> {
+    parse_recipe_entries(file_content)?
+        .into_iter()
+        .map(|(_key, entry)| entry)
+        .collect()
+}
+
+/// Parses each entry of a recipes file on its own, so that an invalid entry doesn't hide the
+/// problems of the entries after it.
+///
+/// Returns the key of every entry along with the result of parsing it, in file order.
+pub fn parse_recipe_entries(
+    file_content: &str,
+) -> Result<Vec<(String, Result<(PackageIdentifier, Recipe)>)>> {
+    let root = serde_yaml::from_str::<serde_yaml::Value>(file_content)
+        .wrap_err("Failed to parse recipes YAML")?;
+    let mapping = root
+        .as_mapping()
+        .ok_or_else(|| eyre!("Recipes YAML root must be a mapping"))?;
+
+    let schema = match mapping.get(SCHEMA_KEY) {
+        None => 1,
+        Some(schema) => schema
+            .as_u64()
+            .filter(|schema| (1..=LATEST_SCHEMA).contains(schema))
+            .ok_or_else(|| {
+                eyre!("Unsupported recipe schema {schema:?}. The latest schema is {LATEST_SCHEMA}")
+            })?,
+    };
+
+    Ok(mapping
+        .iter()
+        .filter(|(key, _)| key.as_str() != Some(SCHEMA_KEY))
+        .map(|(key, value)| match key.as_str() {
+            Some(package_identifier) => (
+                package_identifier.to_owned(),
+                parse_entry(package_identifier, value.clone(), schema),
+            ),
+            None => (
+                format!("{key:?}"),
+                Err(eyre!("Recipe package identifier keys must be strings")),
+            ),
+        })
+        .collect())
+}
+
+fn parse_entry(
+    package_identifier: &str,
+    value: serde_yaml::Value,
+    schema: u64,
+) -> Result<(PackageIdentifier, Recipe)> {
+    let recipe = parse_recipe(value, schema)
+        .wrap_err_with(|| format!("Invalid recipe entry for {package_identifier}"))?;
+    ensure!(
+        !recipe.sources.is_empty(),
+        "Recipe entry has no URLs: {package_identifier}"
+    );
+
+    ensure!(
+        recipe
+            .sources
+            .iter()
+            .filter(|source| source.primary)
+            .count()
+            <= 1,
+        "Recipe entry for {package_identifier} can only have one primary source"
+    );
+    ensure!(
+        recipe.options.version_policy == VersionPolicy::PrimarySource
+            || recipe.sources.iter().all(|source| !source.primary),
+        "Recipe entry for {package_identifier} marks a primary source without 'version-policy: {}'",
+        VersionPolicy::PrimarySource.as_str()
+    );
+
+    for source in &recipe.sources {
+        ensure!(
+            source.url.is_some() != source.page.is_some(),
+            "Recipe source for {package_identifier} must have exactly one of 'url' or 'page'"
+        );
+        ensure!(
+            source.header.is_some() == source.value.is_some(),
+            "Recipe source for {package_identifier} must include both header and value"
+        );
+    }
+
+    package_identifier
+        .parse::<PackageIdentifier>()
+        .map(|identifier| (identifier, recipe))
+        .wrap_err_with(|| format!("Invalid package identifier: {package_identifier}"))
+}
+
+fn parse_recipe(value: serde_yaml::Value, schema: u64) -> Result<Recipe> {
+    let serde_yaml::Value::Mapping(mut mapping) = value else {
+        return parse_recipe_sources(value).map(Recipe::new);
+    };
+
+    let Some(sources) = mapping.remove(SOURCES_KEY) else {
+        return parse_recipe_sources(serde_yaml::Value::Mapping(mapping)).map(Recipe::new);
+    };
+
+    ensure!(
+        schema >= 2,
+        "Recipe objects with '{SOURCES_KEY}' require '{SCHEMA_KEY}: 2' at the top of the recipes file"
+    );
+
+    let state = mapping
+        .remove(STATE_KEY)
+        .map(serde_yaml::from_value::<RecipeState>)
+        .transpose()
+        .wrap_err("Invalid recipe state")?
+        .unwrap_or_default();
+
+    let options = serde_yaml::from_value::<RecipeOptions>(serde_yaml::Value::Mapping(mapping))
+        .wrap_err("Invalid recipe options")?;
+    options.validate()?;
+
+    Ok(Recipe {
+        sources: parse_recipe_sources(sources)?,
+        options,
+        state,
+    })
+}
+
+fn parse_recipe_sources(value: serde_yaml::Value) -> Result<Vec<RecipeSource>> {
+    match value {
+        serde_yaml::Value::String(url) => {
+            let url = url.parse::<DecodedUrl>().wrap_err("Invalid recipe URL")?;
+            Ok(vec![RecipeSource::from_url(url)])
+        }
+        serde_yaml::Value::Mapping(_) => {
+            let source = serde_yaml::from_value::<RecipeSource>(value)
+                .wrap_err("Invalid structured recipe source")?;
+            Ok(vec![source])
+        }
+        serde_yaml::Value::Sequence(values) => values
+            .into_iter()
+            .map(|item| match item {
+                serde_yaml::Value::String(url) => url
+                    .parse::<DecodedUrl>()
+                    .map(RecipeSource::from_url)
+                    .wrap_err("Invalid recipe URL"),
+                serde_yaml::Value::Mapping(_) => serde_yaml::from_value::<RecipeSource>(item)
+                    .wrap_err("Invalid structured recipe source"),
+                _ => bail!("Recipe list items must be URL strings or source objects"),
+            })
+            .collect(),
+        _ => bail!("Recipe entry must be a URL string, source object, or list"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, Utc};
+    use indoc::indoc;
+    use winget_types::{PackageVersion, installer::Architecture};
+
+    use super::{RecipeState, VersionPolicy, VersionTransform, parse_recipes};
+    use crate::{commands::strategies::AutoUpdateStrategy, manifests::Url};
+
+    #[test]
+    fn parses_yaml_mapping_with_comments() {
+        let yaml = r#"
+# comment
+Example.Package: https://sourceforge.net/projects/example/
+Another.Package: https://example.com/installer.exe
+"#;
+
+        let recipes = parse_recipes(yaml).unwrap();
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[0].0.as_str(), "Example.Package");
+        assert_eq!(recipes[0].1.sources.len(), 1);
+        assert_eq!(recipes[1].0.as_str(), "Another.Package");
+        assert_eq!(recipes[1].1.sources.len(), 1);
+    }
+
+    #[test]
+    fn parses_yaml_mapping_with_url_list() {
+        let yaml = r#"
+Example.Package:
+  - https://example.com/installer-x64.exe
+  - https://example.com/installer-arm64.exe
+"#;
+
+        let recipes = parse_recipes(yaml).unwrap();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].0.as_str(), "Example.Package");
+        assert_eq!(recipes[0].1.sources.len(), 2);
+    }
+
+    #[test]
+    fn parses_yaml_mapping_with_structured_sources() {
+        let yaml = r#"
+Example.Package:
+  - url: https://example.com/installer-x64.exe
+    header: Content-Length
+    value: 123
+  - url: https://example.com/installer-arm64.exe
+    header: Content-Length
+    value: 123
+"#;
+
+        let recipes = parse_recipes(yaml).unwrap();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].0.as_str(), "Example.Package");
+        assert_eq!(recipes[0].1.sources.len(), 2);
+        assert_eq!(recipes[0].1.sources[0].value.as_deref(), Some("123"));
+    }
+
+    #[test]
+    fn parses_recipe_object_with_options() {
+        let yaml = indoc! {r#"
+            schema: 2
+            Example.Package:
+              sources:
+                - https://github.com/example/package
+              strategy: github-releases
+              channel: beta
+              assets:
+                exclude: '\.dll$'
+              architectures:
+                '-arm64\.': arm64
+              version:
+                strip-prefix: release-
+              release-notes-url: https://example.com/changelog/{version}
+        "#};
+
+        let recipes = parse_recipes(yaml).unwrap();
+        assert_eq!(recipes.len(), 1);
+
+        let (package_identifier, recipe) = &recipes[0];
+        assert_eq!(package_identifier.as_str(), "Example.Package");
+        assert_eq!(recipe.sources.len(), 1);
+        assert_eq!(
+            recipe.options.strategy,
+            Some(AutoUpdateStrategy::GithubReleases)
+        );
+        assert_eq!(recipe.options.channel.as_deref(), Some("beta"));
+
+        let urls = recipe.options.select_urls(vec![
+            "https://example.com/app-arm64.exe".parse::<Url>().unwrap(),
+            "https://example.com/app.dll".parse::<Url>().unwrap(),
+        ]);
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].override_architecture(), Some(Architecture::Arm64));
+
+        let version = "1.2.3".parse::<PackageVersion>().unwrap();
+        assert_eq!(
+            recipe
+                .options
+                .release_notes_url(&version)
+                .as_ref()
+                .map(|url| url.as_str()),
+            Some("https://example.com/changelog/1.2.3")
+        );
+    }
+
+    #[test]
+    fn parses_primary_source_version_policy() {
+        let yaml = indoc! {"
+            schema: 2
+            Example.Package:
+              sources:
+                - https://example.com/installer-x64.msi
+                - url: https://example.com/installer-arm64.msi
+                  primary: true
+              version-policy: primary-source
+        "};
+
+        let recipes = parse_recipes(yaml).unwrap();
+        let recipe = &recipes[0].1;
+        assert_eq!(recipe.options.version_policy, VersionPolicy::PrimarySource);
+        assert_eq!(recipe.primary_source(), 1);
+    }
+
+    #[test]
+    fn primary_sources_require_primary_source_policy() {
+        let yaml = indoc! {"
+            schema: 2
+            Example.Package:
+              sources:
+                - url: https://example.com/installer-x64.msi
+                  primary: true
+                - https://example.com/installer-arm64.msi
+              version-policy: max
+        "};
+
+        assert!(parse_recipes(yaml).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_recipe_keys() {
+        let yaml = indoc! {"
+            schema: 2
+            Example.Package:
+              sources: https://github.com/example/package
+              stratgy: github-releases
+        "};
+
+        assert!(parse_recipes(yaml).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_source_keys() {
+        let yaml = indoc! {"
+            Example.Package:
+              - url: https://example.com/installer.exe
+                headr: Content-Length
+        "};
+
+        assert!(parse_recipes(yaml).is_err());
+    }
+
+    #[test]
+    fn recipe_objects_require_schema_2() {
+        let yaml = indoc! {"
+            Example.Package:
+              sources: https://github.com/example/package
+              strategy: github-releases
+        "};
+
+        assert!(parse_recipes(yaml).is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_schema() {
+        let yaml = indoc! {"
+            schema: 3
+            Example.Package: https://github.com/example/package
+        "};
+
+        assert!(parse_recipes(yaml).is_err());
+    }
+
+    #[test]
+    fn transforms_version() {
+        let transform = VersionTransform {
+            strip_prefix: Some("release-".to_owned()),
+            pattern: Some(regex::Regex::new("_").unwrap()),
+            replacement: Some(".".to_owned()),
+        };
+
+        let version = transform
+            .apply("release-1_2_3".parse::<PackageVersion>().unwrap())
+            .unwrap();
+        assert_eq!(version.as_str(), "1.2.3");
+    }
+
+    #[test]
+    fn parses_recipe_state() {
+        let yaml = indoc! {r#"
+            schema: 2
+            Example.Package:
+              sources: https://github.com/example/package
+              state:
+                last-version: "1.2.3"
+                last-success: "2026-01-01T00:00:00Z"
+        "#};
+
+        let recipes = parse_recipes(yaml).unwrap();
+        let state = &recipes[0].1.state;
+        assert_eq!(
+            state.last_version,
+            Some("1.2.3".parse::<PackageVersion>().unwrap())
+        );
+        assert_eq!(
+            state.last_success,
+            Some("2026-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(state.last_error, None);
+    }
+
+    #[test]
+    fn recipe_state_is_stale_without_a_recent_success() {
+        let now = "2026-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let max_age = TimeDelta::days(30);
+
+        assert!(RecipeState::default().is_stale(now, max_age));
+        assert!(
+            RecipeState {
+                last_success: Some(now - TimeDelta::days(31)),
+                ..RecipeState::default()
+            }
+            .is_stale(now, max_age)
+        );
+        assert!(
+            !RecipeState {
+                last_success: Some(now - TimeDelta::days(1)),
+                ..RecipeState::default()
+            }
+            .is_stale(now, max_age)
+        );
+    }
+
+    #[test]
+    fn recipe_state_changes_with_version_error_or_old_success() {
+        let max_age = TimeDelta::days(30);
+        let previous = RecipeState {
+            last_version: Some("1.2.3".parse().unwrap()),
+            last_success: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            last_error: None,
+        };
+
+        assert!(
+            !RecipeState {
+                last_success: Some("2026-01-02T00:00:00Z".parse().unwrap()),
+                ..previous.clone()
+            }
+            .has_changed_from(&previous, max_age)
+        );
+        assert!(
+            RecipeState {
+                last_version: Some("1.2.4".parse().unwrap()),
+                ..previous.clone()
+            }
+            .has_changed_from(&previous, max_age)
+        );
+        assert!(
+            RecipeState {
+                last_error: Some("Failed to download".to_owned()),
+                ..previous.clone()
+            }
+            .has_changed_from(&previous, max_age)
+        );
+        assert!(
+            RecipeState {
+                last_success: Some("2026-01-09T00:00:00Z".parse().unwrap()),
+                ..previous.clone()
+            }
+            .has_changed_from(&previous, max_age)
+        );
+    }
+}
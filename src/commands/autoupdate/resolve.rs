@@ -0,0 +1,356 @@
+use bon::builder;
+use color_eyre::eyre::{Result, bail, ensure};
+use itertools::Itertools;
+use winget_types::{
+    PackageIdentifier, PackageVersion,
+    url::{DecodedUrl, ReleaseNotesUrl},
+};
+
+use super::{
+    HTML_PAGE_STRATEGY,
+    recipe::{Recipe, RecipeStateUpdate, VersionPolicy},
+};
+use crate::{
+    commands::strategies::{AutoUpdateStrategy, StrategyResolveError, html_page},
+    github::client::GitHub,
+    manifests::Url,
+};
+
+/// The version and URLs that the sources of a recipe resolved to.
+pub struct ResolvedRecipe {
+    pub package_version: PackageVersion,
+    pub urls: Vec<Url>,
+    pub release_notes_url: Option<ReleaseNotesUrl>,
+    pub should_update: bool,
+    pub skip_version_check: bool,
+    pub state_updates: Vec<RecipeStateUpdate>,
+    /// The strategy and URL of the first source that resolved, for use in templates.
+    pub strategy_source: Option<(&'static str, DecodedUrl)>,
+    /// How the version was chosen when the sources resolved to different versions.
+    pub version_decision: Option<String>,
+}
+
+/// The version that a single source of a recipe resolved to.
+struct SourceVersion {
+    location: DecodedUrl,
+    package_version: PackageVersion,
+    release_notes_url: Option<ReleaseNotesUrl>,
+    should_update: bool,
+}
+
+/// Resolves every source of a recipe without downloading any installers.
+///
+/// The strategy, header and state are used for sources that don't set their own.
+#[builder(finish_fn = resolve)]
+pub async fn resolve_recipe(
+    github: &GitHub,
+    package_identifier: &PackageIdentifier,
+    latest_version: &PackageVersion,
+    recipe: &Recipe,
+    strategy: Option<AutoUpdateStrategy>,
+    header: Option<&str>,
+    state: Option<&str>,
+) -> Result<ResolvedRecipe> {
+    let Recipe {
+        sources, options, ..
+    } = recipe;
+
+    ensure!(
+        !sources.is_empty(),
+        "No source URLs were provided for {package_identifier}"
+    );
+
+    let mut source_versions = Vec::with_capacity(sources.len());
+    let mut resolved_urls = Vec::new();
+    let mut skip_version_check = true;
+    let mut state_updates = Vec::new();
+    let mut strategy_source = None;
+
+    for source in sources {
+        let mut strategy_result = if let Some(page_url) = &source.page {
+            strategy_source.get_or_insert_with(|| (HTML_PAGE_STRATEGY, page_url.clone()));
+            html_page::resolve(latest_version, page_url).await?
+        } else {
+            let source_url = source.url.as_ref().unwrap_or_else(|| unreachable!());
+            let effective_header = source.header.as_deref().or(header);
+            let effective_value = source.value.as_deref().or(state);
+
+            ensure!(
+                effective_header.is_some() == effective_value.is_some(),
+                "Recipe source for {package_identifier} must provide both header and value"
+            );
+
+            let result = AutoUpdateStrategy::resolve(
+                github,
+                package_identifier,
+                latest_version,
+                source_url,
+                options.strategy.or(strategy),
+                options.channel.as_deref(),
+                effective_header,
+                effective_value,
+            )
+            .await;
+
+            let result = match result {
+                Ok((strategy, result)) => {
+                    strategy_source.get_or_insert_with(|| (strategy.as_str(), source_url.clone()));
+                    result
+                }
+                Err(error) => {
+                    if matches!(
+                        error.downcast_ref::<StrategyResolveError>(),
+                        Some(StrategyResolveError::NoStrategyMatched(_))
+                    ) {
+                        return Err(error);
+                    }
+
+                    let error_msg = error.to_string();
+
+                    tracing::error!(
+                        package = %package_identifier,
+                        url = %source_url,
+                        error = %error,
+                        "Strategy resolution failed"
+                    );
+
+                    // Keep troubleshooting hints at debug level so normal info output stays concise.
+                    if error_msg.contains("No suitable release found") {
+                        tracing::debug!(
+                            package = %package_identifier,
+                            url = %source_url,
+                            "Hint: release tags may not be parseable semver; consider vanity_url/html_page strategy or channel-specific package"
+                        );
+                    } else if error_msg.contains("HTTP status client error (404") {
+                        tracing::debug!(
+                            package = %package_identifier,
+                            url = %source_url,
+                            "Hint: verify owner/repo path or use a non-github_releases strategy"
+                        );
+                    } else if error_msg.contains("Not a github-releases URL") {
+                        tracing::debug!(
+                            package = %package_identifier,
+                            url = %source_url,
+                            "Hint: expected github_releases source format is https://github.com/OWNER/REPO"
+                        );
+                    }
+
+                    return Err(error);
+                }
+            };
+
+            if effective_header.is_some()
+                && let Some(observed_state) = result.observed_state.clone()
+            {
+                state_updates.push(RecipeStateUpdate {
+                    url: source_url.clone(),
+                    value: observed_state,
+                });
+            }
+
+            result
+        };
+
+        strategy_result.package_version = options.version.apply(strategy_result.package_version)?;
+
+        skip_version_check &= strategy_result.skip_version_check;
+
+        for resolved_url in options.select_urls(strategy_result.urls) {
+            if !resolved_urls.contains(&resolved_url) {
+                resolved_urls.push(resolved_url);
+            }
+        }
+
+        source_versions.push(SourceVersion {
+            location: source.location().cloned().unwrap_or_else(|| unreachable!()),
+            package_version: strategy_result.package_version,
+            release_notes_url: strategy_result.release_notes_url,
+            should_update: strategy_result.should_update,
+        });
+    }
+
+    let chosen = choose_version(
+        package_identifier,
+        options.version_policy,
+        recipe.primary_source(),
+        &source_versions,
+    )?;
+    let version_decision =
+        describe_version_decision(options.version_policy, &source_versions, chosen);
+    let SourceVersion {
+        package_version,
+        release_notes_url,
+        ..
+    } = &source_versions[chosen];
+
+    // Sources that resolved to another version don't decide whether the package is updated
+    let should_update = source_versions
+        .iter()
+        .filter(|source| &source.package_version == package_version)
+        .any(|source| source.should_update);
+
+    let release_notes_url = options
+        .release_notes_url(package_version)
+        .or_else(|| release_notes_url.clone())
+        .or_else(|| {
+            source_versions
+                .iter()
+                .find_map(|source| source.release_notes_url.clone())
+        });
+    let package_version = package_version.clone();
+
+    Ok(ResolvedRecipe {
+        package_version,
+        urls: resolved_urls,
+        release_notes_url,
+        should_update,
+        skip_version_check,
+        state_updates,
+        strategy_source,
+        version_decision,
+    })
+}
+
+/// Returns the index of the source whose version the recipe is updated to.
+fn choose_version(
+    package_identifier: &PackageIdentifier,
+    policy: VersionPolicy,
+    primary_source: usize,
+    source_versions: &[SourceVersion],
+) -> Result<usize> {
+    match policy {
+        VersionPolicy::RequireEqual => {
+            if let Some((first, other)) = source_versions
+                .iter()
+                .tuple_windows()
+                .find(|(first, other)| first.package_version != other.package_version)
+            {
+                bail!(
+                    "Resolved URLs for {package_identifier} produced different versions: {} and {}. Set 'version-policy' to '{}' or '{}' if this is expected",
+                    first.package_version,
+                    other.package_version,
+                    VersionPolicy::Max.as_str(),
+                    VersionPolicy::PrimarySource.as_str()
+                );
+            }
+            Ok(0)
+        }
+        VersionPolicy::Max => Ok(source_versions
+            .iter()
+            .position_max_by(|first, other| first.package_version.cmp(&other.package_version))
+            .unwrap_or_default()),
+        VersionPolicy::PrimarySource => Ok(primary_source),
+    }
+}
+
+/// Describes which version the policy chose if the sources resolved to different versions.
+fn describe_version_decision(
+    policy: VersionPolicy,
+    source_versions: &[SourceVersion],
+    chosen: usize,
+) -> Option<String> {
+    let chosen = &source_versions[chosen];
+    let others = source_versions
+        .iter()
+        .filter(|source| source.package_version != chosen.package_version)
+        .map(|source| format!("{} from {}", source.package_version, source.location))
+        .collect::<Vec<_>>();
+
+    (!others.is_empty()).then(|| {
+        format!(
+            "{} chose {} from {} over {}",
+            policy.as_str(),
+            chosen.package_version,
+            chosen.location,
+            others.join(", ")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winget_types::PackageIdentifier;
+
+    use super::{SourceVersion, VersionPolicy, choose_version, describe_version_decision};
+
+    fn source_versions(versions: &[&str]) -> Vec<SourceVersion> {
+        versions
+            .iter()
+            .enumerate()
+            .map(|(index, version)| SourceVersion {
+                location: format!("https://example.com/installer-{index}.msi")
+                    .parse()
+                    .unwrap(),
+                package_version: version.parse().unwrap(),
+                release_notes_url: None,
+                should_update: true,
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::max(VersionPolicy::Max, 0, 1)]
+    #[case::first_primary(VersionPolicy::PrimarySource, 0, 0)]
+    #[case::marked_primary(VersionPolicy::PrimarySource, 2, 2)]
+    fn chooses_version_by_policy(
+        #[case] policy: VersionPolicy,
+        #[case] primary_source: usize,
+        #[case] expected: usize,
+    ) {
+        let package_identifier = "Example.Package".parse::<PackageIdentifier>().unwrap();
+        let source_versions = source_versions(&["1.2.0", "1.10.0", "1.3.0"]);
+
+        assert_eq!(
+            choose_version(
+                &package_identifier,
+                policy,
+                primary_source,
+                &source_versions
+            )
+            .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn require_equal_rejects_different_versions() {
+        let package_identifier = "Example.Package".parse::<PackageIdentifier>().unwrap();
+
+        assert!(
+            choose_version(
+                &package_identifier,
+                VersionPolicy::RequireEqual,
+                0,
+                &source_versions(&["1.2.0", "1.2.0", "1.3.0"])
+            )
+            .is_err()
+        );
+        assert_eq!(
+            choose_version(
+                &package_identifier,
+                VersionPolicy::RequireEqual,
+                0,
+                &source_versions(&["1.2.0", "1.2.0"])
+            )
+            .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn describes_version_decision() {
+        let source_versions = source_versions(&["1.2.0", "1.3.0", "1.3.0"]);
+
+        assert_eq!(
+            describe_version_decision(VersionPolicy::Max, &source_versions, 1).as_deref(),
+            Some(
+                "max chose 1.3.0 from https://example.com/installer-1.msi over 1.2.0 from https://example.com/installer-0.msi"
+            )
+        );
+        assert_eq!(
+            describe_version_decision(VersionPolicy::Max, &source_versions[1..], 0),
+            None
+        );
+    }
+}
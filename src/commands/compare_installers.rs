@@ -1,4 +1,8 @@
-use std::{collections::BTreeSet, mem, num::NonZeroUsize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem,
+    num::NonZeroUsize,
+};
 
 use anstream::println;
 use clap::Parser;
@@ -190,7 +194,8 @@ async fn compare_version(
             })
             .collect::<Vec<_>>();
 
-        let matched_installers = match_installers(previous_installers, &installer_results);
+        let matched_installers =
+            match_installers(previous_installers, &installer_results, &HashMap::new());
         let installers = matched_installers
             .into_iter()
             .map(|(previous_installer, new_installer)| {
@@ -1,10 +1,13 @@
 pub mod analyze;
+pub mod audit_hashes;
 pub mod autoupdate;
 pub mod cleanup;
 pub mod compare_installers;
 pub mod complete;
 pub mod list_versions;
 pub mod new_version;
+pub mod pull_requests;
+pub mod recipes;
 pub mod remove_dead_versions;
 pub mod remove_version;
 pub mod show_version;
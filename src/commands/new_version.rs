@@ -7,8 +7,7 @@
 use anstream::println;
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
-use color_eyre::eyre::Result;
-use color_eyre::eyre::ensure;
+use color_eyre::eyre::{Result, ensure};
 use indicatif::ProgressBar;
 use inquire::CustomType;
 use ordinal::Ordinal;
@@ -33,6 +32,7 @@
 };
 
 use crate::{
+    analysis::installers::Classification,
     commands::utils::{
         SPINNER_TICK_RATE, SubmitOption, prompt_existing_pull_request, write_changes_to_dir,
     },
@@ -41,7 +41,7 @@
     github::{
         GITHUB_HOST,
         client::GitHub,
-        utils::{PackagePath, pull_request::pr_changes},
+        utils::{PackagePath, PullRequestTemplate, pull_request::pr_changes},
     },
     manifests::{Manifests, Url},
     prompts::{
@@ -153,6 +153,14 @@ pub struct NewVersion {
     #[arg(long, env)]
     skip_pr_check: bool,
 
+    /// Push the manifests to the branch of an existing pull request instead of creating a new one
+    #[arg(long, value_name = "NUMBER")]
+    amend_pr: Option<NonZeroU32>,
+
+    /// YAML file with templates for the commit title and pull request body
+    #[arg(long, env = "KOMAC_PR_TEMPLATE", value_parser = PullRequestTemplate::from_path, value_hint = clap::ValueHint::FilePath)]
+    pr_template: Option<PullRequestTemplate>,
+
     /// GitHub personal access token with the `public_repo` scope
     #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
     token: Option<SecretString>,
@@ -189,6 +197,7 @@ pub async fn run(self) -> Result<()> {
 
         if !self.skip_pr_check
             && !self.dry_run
+            && self.amend_pr.is_none()
             && let Some(pull_request) = github
                 .get_existing_pull_request(&package_identifier, &package_version)
                 .await?
@@ -227,7 +236,7 @@ pub async fn run(self) -> Result<()> {
                 .cloned();
             async move {
                 github_url
-                    .map(|url| github.get_all_values_from_url(url.into_inner()))
+                    .map(|url| github.get_all_values_from_url(url.into_inner(), None))
                     .unwrap_or_default()
                     .await
             }
@@ -254,7 +263,13 @@ pub async fn run(self) -> Result<()> {
             if analyzer.installers.iter().any(|installer| {
                 installer.r#type == Some(InstallerType::Exe) && installer.switches.is_empty()
             }) {
-                if confirm_prompt(&format!("Is {} a portable exe?", analyzer.file_name))? {
+                // Only ask when the exe could not be confidently classified as an installer
+                if !analyzer
+                    .classification
+                    .as_ref()
+                    .is_some_and(Classification::is_confident)
+                    && confirm_prompt(&format!("Is {} a portable exe?", analyzer.file_name))?
+                {
                     for installer in &mut analyzer.installers {
                         installer.r#type = Some(InstallerType::Portable);
                     }
@@ -375,7 +390,14 @@ pub async fn run(self) -> Result<()> {
             )?,
             package_url: optional_prompt(
                 self.package_url,
-                github_values.as_ref().map(|values| &values.package_url),
+                github_values
+                    .as_ref()
+                    .map(|values| &values.package_url)
+                    .or_else(|| {
+                        download_results
+                            .values()
+                            .find_map(|analyzer| analyzer.package_url.as_ref())
+                    }),
             )?,
             license: required_prompt(
                 self.license,
@@ -484,6 +506,44 @@ pub async fn run(self) -> Result<()> {
             return Ok(());
         }
 
+        if let Some(number) = self.amend_pr {
+            let pr_progress = ProgressBar::new_spinner().with_message(format!(
+                "Amending pull request #{number} for {package_identifier} {package_version}"
+            ));
+            pr_progress.enable_steady_tick(SPINNER_TICK_RATE);
+
+            let pull_request = github
+                .amend_pull_request()
+                .number(number)
+                .identifier(&package_identifier)
+                .version(&package_version)
+                .maybe_versions(versions.as_ref())
+                .changes(changes)
+                .issue_resolves(&self.resolves)
+                .maybe_created_with(self.created_with.as_deref())
+                .maybe_created_with_url(self.created_with_url.as_ref())
+                .maybe_template(self.pr_template.as_ref())
+                .maybe_release_notes(
+                    manifests
+                        .default_locale
+                        .release_notes
+                        .as_ref()
+                        .map(AsRef::<str>::as_ref),
+                )
+                .send()
+                .await?;
+
+            pr_progress.finish_and_clear();
+
+            pull_request.print_success();
+
+            if self.open_pr {
+                open::that(pull_request.url.as_str())?;
+            }
+
+            return Ok(());
+        }
+
         // Create an indeterminate progress bar to show as a pull request is being created
         let pr_progress = ProgressBar::new_spinner().with_message(format!(
             "Creating a pull request for {package_identifier} {package_version}"
@@ -499,6 +559,14 @@ pub async fn run(self) -> Result<()> {
             .issue_resolves(&self.resolves)
             .maybe_created_with(self.created_with.as_deref())
             .maybe_created_with_url(self.created_with_url.as_ref())
+            .maybe_template(self.pr_template.as_ref())
+            .maybe_release_notes(
+                manifests
+                    .default_locale
+                    .release_notes
+                    .as_ref()
+                    .map(AsRef::<str>::as_ref),
+            )
             .send()
             .await?;
 
@@ -0,0 +1,191 @@
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser};
+use color_eyre::Result;
+use indicatif::ProgressBar;
+use owo_colors::OwoColorize;
+use secrecy::SecretString;
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    commands::utils::SPINNER_TICK_RATE,
+    github::{
+        WINGET_PKGS_FULL_NAME,
+        client::GitHub,
+        graphql::get_open_pull_requests::{PullRequest, PullRequestReviewDecision, StatusState},
+    },
+    token::TokenManager,
+};
+
+/// Lists open pull requests to winget-pkgs from your fork along with their checks, labels and
+/// review state
+#[derive(Parser)]
+#[clap(visible_alias = "prs")]
+pub struct PullRequests {
+    /// Only show pull requests whose checks are failing
+    #[arg(long)]
+    failing: bool,
+
+    /// Only show pull requests with the given label (for example, Needs-Author-Feedback)
+    #[arg(short, long)]
+    label: Vec<String>,
+
+    /// Only show pull requests with the given review state
+    #[arg(long, value_enum)]
+    review: Option<PullRequestReviewDecision>,
+
+    /// Only show pull requests that were opened at least this many days ago
+    #[arg(long, value_name = "DAYS")]
+    older_than: Option<u32>,
+
+    #[command(flatten)]
+    output_type: OutputType,
+
+    /// GitHub personal access token with the `public_repo` scope
+    #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    token: Option<SecretString>,
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct OutputType {
+    /// Output the pull requests as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Output the pull requests as prettified JSON
+    #[arg(long)]
+    pretty_json: bool,
+}
+
+impl PullRequests {
+    pub async fn run(mut self) -> Result<()> {
+        let token_manager = TokenManager::handle(self.token.take()).await?;
+        let github = GitHub::new(token_manager)?;
+
+        let pb = ProgressBar::new_spinner().with_message(format!(
+            "Retrieving open pull requests to {WINGET_PKGS_FULL_NAME}"
+        ));
+        pb.enable_steady_tick(SPINNER_TICK_RATE);
+
+        let username = github.get_username().await?;
+        let pull_requests = github.get_open_pull_requests(&username).await?;
+
+        pb.finish_and_clear();
+
+        let now = Utc::now();
+        let summaries = pull_requests
+            .iter()
+            .filter(|pull_request| self.is_match(pull_request, now))
+            .map(|pull_request| PullRequestSummary::new(pull_request, now))
+            .collect::<Vec<_>>();
+
+        let mut stdout_lock = anstream::stdout().lock();
+        match self.output_type {
+            OutputType {
+                pretty_json: true, ..
+            } => serde_json::to_writer_pretty(&mut stdout_lock, &summaries)?,
+            OutputType { json: true, .. } => serde_json::to_writer(&mut stdout_lock, &summaries)?,
+            _ => {
+                if summaries.is_empty() {
+                    writeln!(
+                        stdout_lock,
+                        "There are no open pull requests by {username} that match"
+                    )?;
+                }
+                for summary in &summaries {
+                    summary.write_to(&mut stdout_lock)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_match(&self, pull_request: &PullRequest, now: DateTime<Utc>) -> bool {
+        (!self.failing
+            || pull_request
+                .checks_state()
+                .is_some_and(StatusState::is_failing))
+            && self.label.iter().all(|label| {
+                pull_request
+                    .label_names()
+                    .any(|name| name.eq_ignore_ascii_case(label))
+            })
+            && self
+                .review
+                .is_none_or(|review| pull_request.review_decision == Some(review))
+            && self
+                .older_than
+                .is_none_or(|days| (now - pull_request.created_at).num_days() >= i64::from(days))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestSummary<'pr> {
+    number: i32,
+    title: &'pr str,
+    url: &'pr Url,
+    is_draft: bool,
+    checks: Option<StatusState>,
+    review_decision: Option<PullRequestReviewDecision>,
+    labels: Vec<&'pr str>,
+    created_at: DateTime<Utc>,
+    age_days: i64,
+}
+
+impl<'pr> PullRequestSummary<'pr> {
+    fn new(pull_request: &'pr PullRequest, now: DateTime<Utc>) -> Self {
+        Self {
+            number: pull_request.number,
+            title: &pull_request.title,
+            url: &pull_request.url,
+            is_draft: pull_request.is_draft,
+            checks: pull_request.checks_state(),
+            review_decision: pull_request.review_decision,
+            labels: pull_request.label_names().collect(),
+            created_at: pull_request.created_at,
+            age_days: (now - pull_request.created_at).num_days(),
+        }
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(writer, "#{} {}", self.number.bold(), self.title)?;
+        if self.is_draft {
+            write!(writer, " {}", "(draft)".dimmed())?;
+        }
+        writeln!(writer)?;
+
+        write!(writer, "  checks: ")?;
+        match self.checks {
+            Some(state @ StatusState::Success) => write!(writer, "{}", state.as_str().green())?,
+            Some(state) if state.is_failing() => write!(writer, "{}", state.as_str().red())?,
+            Some(state) => write!(writer, "{}", state.as_str().yellow())?,
+            None => write!(writer, "{}", "none".dimmed())?,
+        }
+
+        write!(writer, " | review: ")?;
+        match self.review_decision {
+            Some(review @ PullRequestReviewDecision::Approved) => {
+                write!(writer, "{}", review.as_str().green())?;
+            }
+            Some(review @ PullRequestReviewDecision::ChangesRequested) => {
+                write!(writer, "{}", review.as_str().red())?;
+            }
+            Some(review) => write!(writer, "{}", review.as_str().yellow())?,
+            None => write!(writer, "{}", "none".dimmed())?,
+        }
+
+        write!(writer, " | age: {}d", self.age_days)?;
+
+        if !self.labels.is_empty() {
+            write!(writer, " | labels: {}", self.labels.join(", ").cyan())?;
+        }
+        writeln!(writer)?;
+
+        writeln!(writer, "  {}", self.url.blue())
+    }
+}
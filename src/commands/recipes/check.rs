@@ -0,0 +1,324 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    num::NonZeroUsize,
+};
+
+use anstream::println;
+use camino::Utf8PathBuf;
+use chrono::{TimeDelta, Utc};
+use clap::Parser;
+use color_eyre::eyre::{Result, WrapErr, bail};
+use futures_util::{StreamExt, stream};
+use owo_colors::OwoColorize;
+use secrecy::SecretString;
+use tokio::fs;
+use winget_types::{PackageIdentifier, PackageVersion};
+
+use crate::{
+    commands::autoupdate::{
+        cache::{DEFAULT_NO_STRATEGY_TTL_DAYS, NoStrategyCache},
+        recipe::{Recipe, parse_recipe_entries},
+        resolve::{ResolvedRecipe, resolve_recipe},
+    },
+    github::client::GitHub,
+    token::TokenManager,
+};
+
+/// Validate a recipes file and show what each recipe currently resolves to
+#[derive(Parser)]
+pub struct CheckRecipes {
+    /// The recipes file to check
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    file: Utf8PathBuf,
+
+    /// Only validate the file without resolving any sources
+    #[arg(long)]
+    no_resolve: bool,
+
+    /// Number of recipes to resolve at the same time
+    #[arg(long, default_value_t = NonZeroUsize::new(8).unwrap())]
+    concurrent_packages: NonZeroUsize,
+
+    /// GitHub personal access token with the `public_repo` scope
+    #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    token: Option<SecretString>,
+}
+
+impl CheckRecipes {
+    pub async fn run(self) -> Result<()> {
+        let file_content = fs::read_to_string(&self.file)
+            .await
+            .wrap_err_with(|| format!("Failed to read {}", self.file))?;
+
+        let mut no_strategy_cache = NoStrategyCache::load().await?;
+        no_strategy_cache.prune_expired(
+            Utc::now(),
+            TimeDelta::days(i64::from(DEFAULT_NO_STRATEGY_TTL_DAYS)),
+        );
+
+        let checks = lint_entries(
+            parse_recipe_entries(&file_content)
+                .wrap_err_with(|| format!("Failed to parse {}", self.file))?,
+            |package_identifier| no_strategy_cache.contains(package_identifier),
+        );
+
+        let resolutions = if self.no_resolve {
+            checks.iter().map(|_| None).collect::<Vec<_>>()
+        } else {
+            let token_manager = TokenManager::handle(self.token).await?;
+            let github = GitHub::new(&token_manager)?;
+
+            stream::iter(&checks)
+                .map(|check| {
+                    let github = &github;
+                    async move {
+                        let (package_identifier, recipe) = check.recipe.as_ref()?;
+                        if !check.errors.is_empty() {
+                            return None;
+                        }
+                        Some(resolve(github, package_identifier, recipe).await)
+                    }
+                })
+                .buffered(self.concurrent_packages.get())
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        let total_recipes = checks.len();
+        println!("\n{} ({total_recipes})", self.file.bold());
+
+        let mut total_errors = 0usize;
+        let mut total_warnings = 0usize;
+
+        for (check, resolution) in checks.into_iter().zip(resolutions) {
+            let resolution_error = match &resolution {
+                Some(Err(error)) => Some(format!("{error:#}")),
+                Some(Ok((_, resolved))) if resolved.should_update && resolved.urls.is_empty() => {
+                    Some(String::from("No resolved URLs matched the asset filters"))
+                }
+                _ => None,
+            };
+
+            let has_errors = !check.errors.is_empty() || resolution_error.is_some();
+            if has_errors {
+                total_errors += 1;
+                println!("  {} {}", "✗".red(), check.key);
+            } else if !check.warnings.is_empty() {
+                total_warnings += 1;
+                println!("  {} {}", "⚠".yellow(), check.key);
+            } else {
+                println!("  {} {}", "✓".green(), check.key);
+            }
+
+            for error in check.errors.iter().chain(&resolution_error) {
+                println!("    {}", error.red());
+            }
+
+            for warning in &check.warnings {
+                println!("    {}", warning.yellow());
+            }
+
+            if let Some(Ok((latest_version, resolved))) = resolution {
+                print_resolved(&latest_version, &resolved);
+            }
+        }
+
+        println!("\n{}", "═".repeat(60));
+        println!(
+            "Recipes: {}  Errors: {}  Warnings: {}",
+            total_recipes,
+            total_errors.red(),
+            total_warnings.yellow()
+        );
+
+        if total_errors > 0 {
+            bail!("{total_errors} recipe entries in {} have errors", self.file);
+        }
+
+        Ok(())
+    }
+}
+
+/// The problems found in a single recipe entry without resolving it.
+#[derive(Debug)]
+struct EntryCheck {
+    key: String,
+    recipe: Option<(PackageIdentifier, Recipe)>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// Checks the parsed entries for problems that span entries, such as duplicate identifiers, and
+/// for entries that autoupdate would skip because they're in the no-strategy cache.
+fn lint_entries<F>(
+    entries: Vec<(String, Result<(PackageIdentifier, Recipe)>)>,
+    is_cached: F,
+) -> Vec<EntryCheck>
+where
+    F: Fn(&PackageIdentifier) -> bool,
+{
+    // Package identifiers are case-insensitive in winget-pkgs
+    let mut identifiers = HashMap::new();
+    let mut sources = HashMap::new();
+
+    entries
+        .into_iter()
+        .map(|(key, entry)| {
+            let mut check = EntryCheck {
+                key,
+                recipe: None,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            };
+
+            let (package_identifier, recipe) = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    check.errors.push(format!("{error:#}"));
+                    return check;
+                }
+            };
+
+            match identifiers.entry(package_identifier.as_str().to_ascii_lowercase()) {
+                Entry::Occupied(first) => {
+                    check.errors.push(format!("Duplicate of {}", first.get()));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(check.key.clone());
+                }
+            }
+
+            for location in recipe.sources.iter().filter_map(|source| source.location()) {
+                match sources.get(location.as_str()) {
+                    Some(other) if *other == check.key => check
+                        .warnings
+                        .push(format!("{location} is listed more than once")),
+                    Some(other) => check
+                        .warnings
+                        .push(format!("{location} is also used by {other}")),
+                    None => {
+                        sources.insert(location.as_str().to_owned(), check.key.clone());
+                    }
+                }
+            }
+
+            if is_cached(&package_identifier) {
+                check.warnings.push(String::from(
+                    "In the no-strategy cache, so autoupdate skips it",
+                ));
+            }
+
+            check.recipe = Some((package_identifier, recipe));
+            check
+        })
+        .collect()
+}
+
+async fn resolve(
+    github: &GitHub,
+    package_identifier: &PackageIdentifier,
+    recipe: &Recipe,
+) -> Result<(PackageVersion, ResolvedRecipe)> {
+    let versions = github.get_versions(package_identifier).await?;
+    let latest_version = versions.last().cloned().unwrap_or_else(|| unreachable!());
+
+    let resolved = resolve_recipe()
+        .github(github)
+        .package_identifier(package_identifier)
+        .latest_version(&latest_version)
+        .recipe(recipe)
+        .resolve()
+        .await?;
+
+    Ok((latest_version, resolved))
+}
+
+fn print_resolved(latest_version: &PackageVersion, resolved: &ResolvedRecipe) {
+    let strategy = resolved
+        .strategy_source
+        .as_ref()
+        .map_or("unknown", |(strategy, _)| *strategy);
+
+    if resolved.should_update {
+        println!(
+            "    {latest_version} → {} via {}",
+            resolved.package_version.blue(),
+            strategy
+        );
+    } else {
+        println!("    {latest_version} is up to date via {strategy}");
+    }
+
+    if let Some(version_decision) = &resolved.version_decision {
+        println!("      Version policy: {version_decision}");
+    }
+
+    for url in &resolved.urls {
+        match url.override_architecture() {
+            Some(architecture) => println!("      {url} ({architecture})"),
+            None => println!("      {url}"),
+        }
+    }
+
+    if let Some(release_notes_url) = &resolved.release_notes_url {
+        println!("      Release notes: {release_notes_url}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::lint_entries;
+    use crate::commands::autoupdate::recipe::parse_recipe_entries;
+
+    #[test]
+    fn flags_duplicate_identifiers_case_insensitively() {
+        let yaml = indoc! {"
+            Example.Package: https://github.com/example/package
+            example.package: https://github.com/example/other
+        "};
+
+        let checks = lint_entries(parse_recipe_entries(yaml).unwrap(), |_| false);
+
+        assert!(checks[0].errors.is_empty());
+        assert_eq!(checks[1].errors, ["Duplicate of Example.Package"]);
+    }
+
+    #[test]
+    fn warns_about_shared_sources_and_cached_entries() {
+        let yaml = indoc! {"
+            Example.Package: https://github.com/example/package
+            Example.Package.Beta: https://github.com/example/package
+        "};
+
+        let checks = lint_entries(parse_recipe_entries(yaml).unwrap(), |package_identifier| {
+            package_identifier.as_str() == "Example.Package"
+        });
+
+        assert_eq!(
+            checks[0].warnings,
+            ["In the no-strategy cache, so autoupdate skips it"]
+        );
+        assert_eq!(
+            checks[1].warnings,
+            ["https://github.com/example/package is also used by Example.Package"]
+        );
+        assert!(checks.iter().all(|check| check.errors.is_empty()));
+    }
+
+    #[test]
+    fn keeps_checking_after_an_invalid_entry() {
+        let yaml = indoc! {"
+            Example.Package: []
+            Another.Package: https://example.com/installer.exe
+        "};
+
+        let checks = lint_entries(parse_recipe_entries(yaml).unwrap(), |_| false);
+
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].recipe.is_none());
+        assert!(!checks[0].errors.is_empty());
+        assert!(checks[1].recipe.is_some());
+    }
+}
@@ -0,0 +1,14 @@
+use clap::{Args, Subcommand};
+
+use crate::commands::recipes::check::CheckRecipes;
+
+/// Work with autoupdate recipe files
+#[derive(Args)]
+pub struct RecipesArgs {
+    #[command(subcommand)]
+    pub command: RecipesCommands,
+}
+#[derive(Subcommand)]
+pub enum RecipesCommands {
+    Check(CheckRecipes),
+}
@@ -1,40 +1,71 @@
-use std::num::NonZeroU32;
+use std::{collections::BTreeSet, fs, num::NonZeroU32};
 
 use anstream::println;
+use camino::Utf8PathBuf;
 use clap::Parser;
-use color_eyre::eyre::{Result, bail};
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
 use futures_util::TryFutureExt;
 use inquire::{
     Text,
     validator::{MaxLengthValidator, MinLengthValidator},
 };
+use itertools::Itertools;
 use owo_colors::OwoColorize;
 use secrecy::SecretString;
 use tokio::try_join;
 use winget_types::{PackageIdentifier, PackageVersion};
 
 use crate::{
+    commands::utils::RateLimit,
     github::{WINGET_PKGS_FULL_NAME, client::GitHub},
     prompts::{handle_inquire_error, text::confirm_prompt},
     token::TokenManager,
 };
 
-/// Remove a version from winget-pkgs
+/// Remove one or more versions from winget-pkgs
 ///
 /// To remove a package, all versions of that package must be removed
+#[expect(clippy::struct_excessive_bools)]
 #[derive(Parser)]
 pub struct RemoveVersion {
     /// The package's unique identifier
     #[arg()]
     package_identifier: PackageIdentifier,
 
-    /// The package's version
-    #[arg(short = 'v', long = "version")]
-    package_version: PackageVersion,
+    /// The package's version(s)
+    #[arg(
+        short = 'v',
+        long = "version",
+        num_args = 1..,
+        required_unless_present_any = ["before", "after", "versions_file"]
+    )]
+    package_versions: Vec<PackageVersion>,
 
-    #[arg(short = 'r', long = "reason")]
+    /// Remove all versions lesser than a given version
+    #[arg(long)]
+    before: Option<PackageVersion>,
+
+    /// Remove all versions greater than a given version
+    #[arg(long)]
+    after: Option<PackageVersion>,
+
+    /// Path to a file listing the versions to remove, one per line
+    ///
+    /// Empty lines and lines starting with `#` are ignored
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    versions_file: Option<Utf8PathBuf>,
+
+    /// Reason for removing the version(s)
+    ///
+    /// `{identifier}` and `{version}` are replaced with the package identifier and the version(s)
+    /// being removed
+    #[arg(short = 'r', long = "reason", env = "KOMAC_REMOVAL_REASON")]
     deletion_reason: Option<String>,
 
+    /// Create one pull request that removes all the versions instead of one per version
+    #[arg(long)]
+    single_pr: bool,
+
     /// List of issues that removing this version would resolve
     #[arg(long)]
     resolves: Vec<NonZeroU32>,
@@ -50,6 +81,10 @@ pub struct RemoveVersion {
     #[arg(long, env = "OPEN_PR")]
     open_pr: bool,
 
+    /// Use the per-minute rate limit, potentially hitting the hourly rate limit in 7.5 minutes
+    #[arg(long, hide = true)]
+    fast: bool,
+
     /// GitHub personal access token with the `public_repo` scope
     #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
     token: Option<SecretString>,
@@ -59,8 +94,11 @@ impl RemoveVersion {
     const MIN_REASON_LENGTH: usize = 4;
     const MAX_REASON_LENGTH: usize = 1000;
 
-    pub async fn run(self) -> Result<()> {
-        let token_manager = TokenManager::handle(self.token).await?;
+    const IDENTIFIER_PLACEHOLDER: &'static str = "{identifier}";
+    const VERSION_PLACEHOLDER: &'static str = "{version}";
+
+    pub async fn run(mut self) -> Result<()> {
+        let token_manager = TokenManager::handle(self.token.take()).await?;
 
         if !self.no_warning {
             println!(
@@ -79,55 +117,224 @@ pub async fn run(self) -> Result<()> {
             github.get_versions(&self.package_identifier)
         )?;
 
-        if !versions.contains(&self.package_version) {
-            bail!(
-                "{} version {} does not exist in {WINGET_PKGS_FULL_NAME}",
-                self.package_identifier,
-                self.package_version,
-            );
-        }
-
         let latest_version = versions.last().unwrap_or_else(|| unreachable!());
         println!(
             "Latest version of {}: {latest_version}",
             &self.package_identifier
         );
-        let deletion_reason = match self.deletion_reason {
-            Some(reason) => reason,
+
+        let versions_to_remove = self.versions_to_remove(&versions)?;
+
+        if self.is_in_range(latest_version) {
+            println!(
+                "Keeping {latest_version} as it is the latest version of {}",
+                self.package_identifier
+            );
+        }
+
+        if versions_to_remove.is_empty() {
+            println!(
+                "No versions of {} matched the given criteria",
+                self.package_identifier
+            );
+            return Ok(());
+        }
+
+        // Removing the latest version as part of a bulk removal is almost always a mistake, so it
+        // is only removed in bulk when it is listed explicitly
+        if self.is_bulk() && versions_to_remove.contains(latest_version) {
+            bail!(
+                "{latest_version} is the latest version of {} and cannot be removed in bulk. Remove it separately with --version {latest_version}",
+                self.package_identifier
+            );
+        }
+
+        let joined_versions = versions_to_remove.iter().join(", ");
+        if self.is_bulk() {
+            println!(
+                "Versions to remove: {}",
+                versions_to_remove
+                    .iter()
+                    .map(|version| version.blue())
+                    .join(", ")
+            );
+        }
+
+        let deletion_reason = match &self.deletion_reason {
+            Some(reason) => reason.clone(),
             None => Text::new(&format!(
-                "Give a reason for removing {} version {}",
-                &self.package_identifier, &self.package_version
+                "Give a reason for removing {} version {joined_versions}",
+                &self.package_identifier
             ))
             .with_validator(MinLengthValidator::new(Self::MIN_REASON_LENGTH))
             .with_validator(MaxLengthValidator::new(Self::MAX_REASON_LENGTH))
             .prompt()
             .map_err(handle_inquire_error)?,
         };
+
         let should_remove_manifest = self.submit
-            || confirm_prompt(&format!(
-                "Would you like to make a pull request to remove {} {}?",
-                self.package_identifier, self.package_version
-            ))?;
+            || confirm_prompt(&if self.single_pr || versions_to_remove.len() == 1 {
+                format!(
+                    "Would you like to make a pull request to remove {} {joined_versions}?",
+                    self.package_identifier
+                )
+            } else {
+                format!(
+                    "Would you like to make {} pull requests to remove {} {joined_versions}?",
+                    versions_to_remove.len(),
+                    self.package_identifier
+                )
+            })?;
 
         if !should_remove_manifest {
             return Ok(());
         }
 
-        let pull_request = github
-            .remove_version()
-            .identifier(&self.package_identifier)
-            .version(&self.package_version)
-            .reason(&deletion_reason)
-            .fork(&fork)
-            .winget_pkgs(&winget_pkgs)
-            .issue_resolves(&self.resolves)
-            .send()
-            .await?;
-
-        if self.open_pr {
-            open::that(pull_request.url().as_str())?;
+        let rate_limit = RateLimit::new(self.fast);
+
+        let batches = if self.single_pr {
+            vec![versions_to_remove.iter().collect::<Vec<_>>()]
+        } else {
+            versions_to_remove
+                .iter()
+                .map(|version| vec![version])
+                .collect::<Vec<_>>()
+        };
+
+        for batch in batches {
+            let reason = self.render_reason(&deletion_reason, &batch);
+
+            rate_limit.wait().await;
+
+            let pull_request = github
+                .remove_versions()
+                .identifier(&self.package_identifier)
+                .versions(&batch)
+                .reason(&reason)
+                .fork(&fork)
+                .winget_pkgs(&winget_pkgs)
+                .issue_resolves(&self.resolves)
+                .send()
+                .await?;
+
+            rate_limit.record().await;
+
+            if self.open_pr {
+                open::that(pull_request.url().as_str())?;
+            }
         }
 
         Ok(())
     }
+
+    /// Returns whether more than a single explicitly given version is being removed.
+    fn is_bulk(&self) -> bool {
+        self.package_versions.len() > 1
+            || self.before.is_some()
+            || self.after.is_some()
+            || self.versions_file.is_some()
+    }
+
+    /// Returns whether a version falls within the range given by `--before` and `--after`.
+    fn is_in_range(&self, version: &PackageVersion) -> bool {
+        (self.before.is_some() || self.after.is_some())
+            && self.before.as_ref().is_none_or(|before| version < before)
+            && self.after.as_ref().is_none_or(|after| version > after)
+    }
+
+    /// Resolves the versions given explicitly, through a file and through a range into the versions
+    /// that exist in winget-pkgs.
+    ///
+    /// The latest version is never selected through a range, as a package always needs one
+    /// version left.
+    fn versions_to_remove(
+        &self,
+        versions: &BTreeSet<PackageVersion>,
+    ) -> Result<BTreeSet<PackageVersion>> {
+        let mut requested_versions = self
+            .package_versions
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        if let Some(versions_file) = &self.versions_file {
+            let content = fs::read_to_string(versions_file)
+                .wrap_err_with(|| format!("Failed to read {versions_file}"))?;
+            for line in content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            {
+                requested_versions.insert(
+                    line.parse::<PackageVersion>().map_err(|err| {
+                        eyre!("Invalid version `{line}` in {versions_file}: {err}")
+                    })?,
+                );
+            }
+        }
+
+        for version in &requested_versions {
+            if !versions.contains(version) {
+                bail!(
+                    "{} version {version} does not exist in {WINGET_PKGS_FULL_NAME}",
+                    self.package_identifier,
+                );
+            }
+        }
+
+        let latest_version = versions.last();
+        requested_versions.extend(
+            versions
+                .iter()
+                .filter(|&version| self.is_in_range(version) && Some(version) != latest_version)
+                .cloned(),
+        );
+
+        Ok(requested_versions)
+    }
+
+    fn render_reason(&self, template: &str, versions: &[&PackageVersion]) -> String {
+        template
+            .replace(
+                Self::IDENTIFIER_PLACEHOLDER,
+                self.package_identifier.as_str(),
+            )
+            .replace(Self::VERSION_PLACEHOLDER, &versions.iter().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use clap::Parser;
+    use rstest::rstest;
+    use winget_types::PackageVersion;
+
+    use super::RemoveVersion;
+
+    fn versions(versions: &[&str]) -> BTreeSet<PackageVersion> {
+        versions
+            .iter()
+            .map(|version| version.parse().unwrap())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::after(&["--after", "1.0.0"], &["1.1.0", "1.2.0"])]
+    #[case::before(&["--before", "1.1.0"], &["0.9.0", "1.0.0"])]
+    #[case::between(&["--after", "0.9.0", "--before", "1.2.0"], &["1.0.0", "1.1.0"])]
+    #[case::explicit_latest(&["--version", "1.3.0"], &["1.3.0"])]
+    fn range_keeps_latest_version(#[case] args: &[&str], #[case] expected: &[&str]) {
+        let remove_version =
+            RemoveVersion::try_parse_from(["remove-version", "Example.Package"].iter().chain(args))
+                .unwrap();
+
+        assert_eq!(
+            remove_version
+                .versions_to_remove(&versions(&["0.9.0", "1.0.0", "1.1.0", "1.2.0", "1.3.0"]))
+                .unwrap(),
+            versions(expected)
+        );
+    }
 }
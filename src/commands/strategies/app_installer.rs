@@ -0,0 +1,61 @@
+use camino::Utf8Path;
+use color_eyre::Report;
+use reqwest::Client;
+use thiserror::Error;
+use winget_types::url::DecodedUrl;
+
+use super::UpdateVersionStrategyResult;
+use crate::{
+    analysis::{extensions::APP_INSTALLER, installers::msix_family::app_installer::AppInstaller},
+    manifests::Url,
+};
+
+#[derive(Error, Debug)]
+pub enum AppInstallerError {
+    #[error("Not an App Installer URL (expected a URL to a .appinstaller file)")]
+    NotAppInstallerUrl,
+    #[error("The App Installer file at {0} does not have a valid version")]
+    MissingVersion(DecodedUrl),
+    #[error(transparent)]
+    Parse(#[from] Report),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Resolves the package that an App Installer file currently points to, using the version declared
+/// in the file.
+pub async fn resolve(
+    source_url: &DecodedUrl,
+) -> Result<UpdateVersionStrategyResult, AppInstallerError> {
+    if !source_url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|file_name| Utf8Path::new(file_name).extension())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(APP_INSTALLER))
+    {
+        return Err(AppInstallerError::NotAppInstallerUrl);
+    }
+
+    let xml = Client::new()
+        .get(source_url.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let app_installer = AppInstaller::new(&xml)?;
+
+    let package_version = app_installer
+        .package_version()
+        .ok_or_else(|| AppInstallerError::MissingVersion(source_url.clone()))?;
+
+    Ok(UpdateVersionStrategyResult {
+        package_version,
+        urls: vec![Url::from(app_installer.main_package.uri)],
+        release_notes_url: None,
+        observed_state: None,
+        should_update: true,
+        skip_version_check: false,
+    })
+}
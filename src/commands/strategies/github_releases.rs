@@ -205,6 +205,7 @@ fn package_version_from_tag(tag: &str) -> Option<PackageVersion> {
 pub async fn resolve(
     github: &GitHub,
     package_identifier: &PackageIdentifier,
+    channel: Option<&str>,
     source_url: &DecodedUrl,
 ) -> Result<UpdateVersionStrategyResult, GithubReleasesError> {
     if source_url.scheme() != "https" || source_url.host_str() != Some(GITHUB_HOST) {
@@ -222,7 +223,7 @@ pub async fn resolve(
         .next()
         .ok_or(GithubReleasesError::NotGithubReleasesUrl)?;
 
-    let channel = detect_channel(package_identifier.as_str());
+    let channel = channel.or_else(|| detect_channel(package_identifier.as_str()));
     let cache_key = {
         let base = format!("{owner}/{repo}").to_ascii_lowercase();
         match channel {
@@ -1,10 +1,13 @@
+pub mod app_installer;
 pub mod github_releases;
 pub mod html_page;
+pub mod release_notes;
 pub mod sourceforge;
 pub mod vanity_url;
 
 use clap::ValueEnum;
 use color_eyre::eyre::Result;
+use serde::Deserialize;
 use winget_types::{
     PackageIdentifier, PackageVersion,
     url::{DecodedUrl, ReleaseNotesUrl},
@@ -22,25 +25,43 @@ pub struct UpdateVersionStrategyResult {
     pub skip_version_check: bool,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum AutoUpdateStrategy {
     GithubReleases,
     SourceForge,
     VanityUrl,
+    AppInstaller,
 }
 
 impl AutoUpdateStrategy {
-    const AUTO_STRATEGIES: [Self; 2] = [Self::GithubReleases, Self::SourceForge];
+    const AUTO_STRATEGIES: [Self; 3] =
+        [Self::GithubReleases, Self::SourceForge, Self::AppInstaller];
 
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GithubReleases => "github-releases",
+            Self::SourceForge => "source-forge",
+            Self::VanityUrl => "vanity-url",
+            Self::AppInstaller => "app-installer",
+        }
+    }
+
+    /// Resolves the update using the given strategy, or the first automatic strategy that
+    /// matches the source URL, and returns the strategy that was used along with its result.
+    ///
+    /// The channel overrides the prerelease channel that is otherwise detected from the package
+    /// identifier.
     pub async fn resolve(
         github: &GitHub,
         package_identifier: &PackageIdentifier,
         latest_version: &PackageVersion,
         source_url: &DecodedUrl,
         strategy: Option<Self>,
+        channel: Option<&str>,
         header: Option<&str>,
         state: Option<&str>,
-    ) -> Result<UpdateVersionStrategyResult> {
+    ) -> Result<(Self, UpdateVersionStrategyResult)> {
         if let Some(strategy) = strategy {
             return Self::resolve_with(
                 strategy,
@@ -48,10 +69,12 @@ pub async fn resolve(
                 package_identifier,
                 latest_version,
                 source_url,
+                channel,
                 header,
                 state,
             )
             .await
+            .map(|result| (strategy, result))
             .map_err(Into::into);
         }
 
@@ -62,23 +85,27 @@ pub async fn resolve(
                 package_identifier,
                 latest_version,
                 source_url,
+                channel,
                 header,
                 state,
             )
             .await
             {
-                Ok(result) => return Ok(result),
+                Ok(result) => return Ok((strategy, result)),
                 Err(StrategyResolveError::GithubReleases(
                     github_releases::GithubReleasesError::NotGithubReleasesUrl,
                 )) => {}
                 Err(StrategyResolveError::SourceForge(
                     sourceforge::SourceForgeError::NotSourceForgeProjectUrl,
                 )) => {}
+                Err(StrategyResolveError::AppInstaller(
+                    app_installer::AppInstallerError::NotAppInstallerUrl,
+                )) => {}
                 Err(error) => return Err(error.into()),
             }
         }
 
-        Err(StrategyResolveError::NoStrategyMatched(source_url.to_string()).into())
+        Err(StrategyResolveError::NoStrategyMatched(source_url.clone()).into())
     }
 
     async fn resolve_with(
@@ -87,12 +114,13 @@ async fn resolve_with(
         package_identifier: &PackageIdentifier,
         latest_version: &PackageVersion,
         source_url: &DecodedUrl,
+        channel: Option<&str>,
         header: Option<&str>,
         state: Option<&str>,
     ) -> Result<UpdateVersionStrategyResult, StrategyResolveError> {
         match strategy {
             Self::GithubReleases => {
-                github_releases::resolve(github, package_identifier, source_url)
+                github_releases::resolve(github, package_identifier, channel, source_url)
                     .await
                     .map_err(StrategyResolveError::GithubReleases)
             }
@@ -106,23 +134,29 @@ async fn resolve_with(
                 package_identifier,
                 latest_version,
                 source_url,
+                channel,
                 header,
                 state,
             )
             .await
             .map_err(StrategyResolveError::VanityUrl),
+            Self::AppInstaller => app_installer::resolve(source_url)
+                .await
+                .map_err(StrategyResolveError::AppInstaller),
         }
     }
 }
 
 #[derive(Debug, thiserror::Error)]
-enum StrategyResolveError {
+pub enum StrategyResolveError {
     #[error(transparent)]
     GithubReleases(#[from] github_releases::GithubReleasesError),
     #[error(transparent)]
     SourceForge(#[from] sourceforge::SourceForgeError),
     #[error(transparent)]
     VanityUrl(#[from] vanity_url::VanityUrlError),
+    #[error(transparent)]
+    AppInstaller(#[from] app_installer::AppInstallerError),
     #[error("No autoupdate strategy matched URL: {0}")]
-    NoStrategyMatched(String),
+    NoStrategyMatched(DecodedUrl),
 }
@@ -0,0 +1,346 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use reqwest::{Client, header::CONTENT_TYPE};
+use thiserror::Error;
+use url::Url;
+use winget_types::{PackageVersion, locale::ReleaseNotes, url::ReleaseNotesUrl};
+
+use crate::{
+    github::{GITHUB_HOST, graphql::types::Html},
+    manifests::{ReleaseNotesRules, markdown_to_release_notes},
+    token::default_headers,
+    traits::html_to_text,
+};
+
+#[derive(Error, Debug)]
+pub enum ReleaseNotesError {
+    #[error("Invalid release notes URL {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// How the content at a release notes URL is written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum NotesFormat {
+    Html,
+    Markdown,
+    PlainText,
+}
+
+impl NotesFormat {
+    fn detect(url: &Url, content_type: Option<&str>) -> Self {
+        // SourceForge file URLs end with a `/download` segment after the file name
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| {
+                segments.rfind(|segment| !segment.is_empty() && *segment != "download")
+            })
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if file_name.ends_with(".md") || file_name.ends_with(".markdown") {
+            Self::Markdown
+        } else if content_type.is_some_and(|content_type| content_type.contains("html")) {
+            Self::Html
+        } else {
+            Self::PlainText
+        }
+    }
+}
+
+/// Fetches the release notes at a release notes URL for the strategies that only resolve where
+/// the release notes are, such as SourceForge file notes, a `CHANGELOG.md`, or a section of an HTML
+/// page.
+///
+/// Markdown, HTML and plain text files usually cover every release, so only the section under the
+/// heading that the URL fragment points to, or the heading that mentions the version, is used. The
+/// section is then cleaned up by the given rules. Returns `None` if there is no such section.
+pub async fn fetch(
+    release_notes_url: &ReleaseNotesUrl,
+    package_version: &PackageVersion,
+    rules: &ReleaseNotesRules,
+) -> Result<Option<ReleaseNotes>, ReleaseNotesError> {
+    let mut url = Url::parse(release_notes_url.as_str())?;
+    let fragment = url.fragment().map(str::to_owned);
+    url.set_fragment(None);
+
+    // Files viewed on GitHub are rendered in a page, so request the raw file instead
+    if url.host_str() == Some(GITHUB_HOST) && url.path().contains("/blob/") {
+        url.query_pairs_mut().append_pair("raw", "true");
+    }
+
+    let client = Client::builder()
+        .default_headers(default_headers(None))
+        .build()?;
+    let response = client.get(url.as_str()).send().await?.error_for_status()?;
+    let format = NotesFormat::detect(
+        &url,
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok()),
+    );
+    let content = response.text().await?;
+
+    let text = match format {
+        NotesFormat::Html => html_section(&content, fragment.as_deref(), package_version)
+            .and_then(|section| html_to_text(&Html::new(section))),
+        NotesFormat::Markdown => markdown_section(&content, package_version),
+        NotesFormat::PlainText => plain_text_section(&content, package_version),
+    };
+
+    Ok(text.and_then(|text| markdown_to_release_notes(&text, rules)))
+}
+
+/// Returns whether a heading mentions the version on its own, so that `1.2.3` doesn't match a
+/// heading for `1.2.30`, `11.2.3` or `1.2.3-beta`.
+fn mentions_version(heading: &str, package_version: &PackageVersion) -> bool {
+    let version = package_version.as_str();
+
+    heading.match_indices(version).any(|(index, _)| {
+        let before = heading[..index].chars().next_back();
+        let mut after = heading[index + version.len()..].chars();
+
+        before.is_none_or(|char| !char.is_ascii_digit() && char != '.')
+            && match after.next() {
+                None => true,
+                Some('.') => after.next().is_none_or(|char| !char.is_alphanumeric()),
+                Some(char) => !char.is_alphanumeric() && char != '-' && char != '+',
+            }
+    })
+}
+
+/// Returns the body of the Markdown section whose heading mentions the version.
+fn markdown_section(markdown: &str, package_version: &PackageVersion) -> Option<String> {
+    fn heading_level(line: &str) -> Option<usize> {
+        let level = line.chars().take_while(|&char| char == '#').count();
+        ((1..=6).contains(&level) && line[level..].starts_with([' ', '\t'])).then_some(level)
+    }
+
+    let mut lines = markdown.lines();
+    let level = lines
+        .by_ref()
+        .find_map(|line| heading_level(line).filter(|_| mentions_version(line, package_version)))?;
+
+    let section = lines
+        .take_while(|line| heading_level(line).is_none_or(|other| other > level))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(section)
+}
+
+/// Returns the lines under the plain text heading that mentions the version, up to the next
+/// heading.
+///
+/// Plain text has no markup, so a heading is taken to be an unindented line that starts with a
+/// version, optionally after a single word such as `Version`, so that a line like `Fixed a crash
+/// in 2.1 projects` isn't mistaken for one. A line that underlines the heading is skipped.
+fn plain_text_section(text: &str, package_version: &PackageVersion) -> Option<String> {
+    static HEADING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(?:[[:alpha:]][\w-]*\s+)?[\[(]?[vV]?\d+(?:\.\d+)+").unwrap()
+    });
+
+    fn is_underline(line: &str) -> bool {
+        let line = line.trim();
+        !line.is_empty()
+            && line
+                .chars()
+                .all(|char| matches!(char, '=' | '-' | '~' | '*'))
+    }
+
+    let mut lines = text.lines();
+    lines.find(|line| HEADING_REGEX.is_match(line) && mentions_version(line, package_version))?;
+
+    let section = lines
+        .skip_while(|line| is_underline(line))
+        .take_while(|line| !HEADING_REGEX.is_match(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(section)
+}
+
+/// Returns the HTML of the section under the heading that the fragment points to, or under the
+/// first heading that mentions the version if there is no fragment.
+fn html_section(
+    html: &str,
+    fragment: Option<&str>,
+    package_version: &PackageVersion,
+) -> Option<String> {
+    static HEADING_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?i)<h([1-6])\b[^>]*>").unwrap());
+    static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+    let anchor = fragment
+        .map(|fragment| {
+            Regex::new(&format!(
+                r#"(?i)\b(?:id|name)\s*=\s*["']?{}(?:["'\s/>])"#,
+                regex::escape(fragment)
+            ))
+        })
+        .transpose()
+        .ok()?;
+    let lowercase = html.to_ascii_lowercase();
+
+    let (level, body_start) = HEADING_REGEX.captures_iter(html).find_map(|captures| {
+        let heading = captures.get(0)?;
+        let level = captures[1].parse::<usize>().ok()?;
+        let close = format!("</h{level}>");
+        let end = heading.end() + lowercase[heading.end()..].find(&close)?;
+
+        // The anchor can be on the heading itself or on an element inside it
+        let is_match = match &anchor {
+            Some(anchor) => anchor.is_match(&html[heading.start()..end]),
+            None => {
+                let text = TAG_REGEX.replace_all(&html[heading.end()..end], "");
+                mentions_version(&html_escape::decode_html_entities(&text), package_version)
+            }
+        };
+
+        is_match.then_some((level, end + close.len()))
+    })?;
+
+    let body_end = HEADING_REGEX
+        .captures_iter(&html[body_start..])
+        .find(|captures| {
+            captures[1]
+                .parse::<usize>()
+                .is_ok_and(|other| other <= level)
+        })
+        .and_then(|captures| captures.get(0))
+        .map_or(html.len(), |heading| body_start + heading.start());
+
+    Some(html[body_start..body_end].to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use url::Url;
+    use winget_types::PackageVersion;
+
+    use super::{
+        NotesFormat, html_section, markdown_section, mentions_version, plain_text_section,
+    };
+
+    fn version(version: &str) -> PackageVersion {
+        version.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("## 1.2.3 - 2026-01-01", true)]
+    #[case("## [v1.2.3]", true)]
+    #[case("Version 1.2.3.", true)]
+    #[case("## 1.2.30", false)]
+    #[case("## 11.2.3", false)]
+    #[case("## 1.2.3-beta", false)]
+    #[case("## 1.2.3.4", false)]
+    fn matches_version_in_heading(#[case] heading: &str, #[case] expected: bool) {
+        assert_eq!(mentions_version(heading, &version("1.2.3")), expected);
+    }
+
+    #[test]
+    fn selects_markdown_section_for_version() {
+        let changelog = indoc! {"
+            # Changelog
+
+            ## 1.2.4
+            - Unreleased fix
+
+            ## 1.2.3
+            ### Fixed
+            - Crash on start
+
+            ## 1.2.2
+            - Older fix
+        "};
+
+        assert_eq!(
+            markdown_section(changelog, &version("1.2.3")).as_deref(),
+            Some("### Fixed\n- Crash on start\n")
+        );
+        assert_eq!(markdown_section(changelog, &version("2.0.0")), None);
+    }
+
+    #[test]
+    fn selects_plain_text_section_for_version() {
+        let readme = indoc! {"
+            Release 1.2.4
+            =============
+            - Updated from 1.2.3
+
+            Version 1.2.3 (2026-01-01)
+            --------------------------
+            - Crash on start
+              with version 2.0 projects
+
+            v1.2.2
+            - Older fix
+        "};
+
+        assert_eq!(
+            plain_text_section(readme, &version("1.2.3")).as_deref(),
+            Some("- Crash on start\n  with version 2.0 projects\n")
+        );
+        assert_eq!(
+            plain_text_section(readme, &version("1.2.2")).as_deref(),
+            Some("- Older fix")
+        );
+        assert_eq!(plain_text_section(readme, &version("2.0.0")), None);
+    }
+
+    #[test]
+    fn selects_html_section_by_anchor_or_version() {
+        let html = indoc! {r#"
+            <h1>Release notes</h1>
+            <h2><a id="v1-2-3"></a>Version 1.2.3</h2>
+            <ul><li>Crash on start</li></ul>
+            <h3>Known issues</h3>
+            <p>None</p>
+            <h2 id="v1-2-2">Version 1.2.2</h2>
+            <p>Older fix</p>
+        "#};
+
+        let section = html_section(html, Some("v1-2-3"), &version("0.0.0")).unwrap();
+        assert!(section.contains("Crash on start"));
+        assert!(section.contains("Known issues"));
+        assert!(!section.contains("Older fix"));
+
+        assert_eq!(
+            html_section(html, None, &version("1.2.2")).as_deref(),
+            Some("\n<p>Older fix</p>\n")
+        );
+        assert_eq!(html_section(html, Some("missing"), &version("1.2.3")), None);
+    }
+
+    #[rstest]
+    #[case(
+        "https://example.com/CHANGELOG.md",
+        Some("text/plain"),
+        NotesFormat::Markdown
+    )]
+    #[case(
+        "https://sourceforge.net/projects/example/files/1.2.3/readme.txt/download",
+        Some("application/octet-stream"),
+        NotesFormat::PlainText
+    )]
+    #[case(
+        "https://example.com/news",
+        Some("text/html; charset=utf-8"),
+        NotesFormat::Html
+    )]
+    fn detects_format(
+        #[case] url: &str,
+        #[case] content_type: Option<&str>,
+        #[case] expected: NotesFormat,
+    ) {
+        assert_eq!(
+            NotesFormat::detect(&Url::parse(url).unwrap(), content_type),
+            expected
+        );
+    }
+}
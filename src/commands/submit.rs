@@ -16,7 +16,7 @@
     commands::utils::{RateLimit, SPINNER_TICK_RATE, SubmitOption},
     github::{
         client::GitHub,
-        utils::{PackagePath, pull_request::pr_changes},
+        utils::{PackagePath, PullRequestTemplate, pull_request::pr_changes},
     },
     manifests::{Manifests, manifest::Manifest},
     prompts::handle_inquire_error,
@@ -51,6 +51,10 @@ pub struct Submit {
     #[arg(long, env = "DRY_RUN")]
     dry_run: bool,
 
+    /// YAML file with templates for the commit title and pull request body
+    #[arg(long, env = "KOMAC_PR_TEMPLATE", value_parser = PullRequestTemplate::from_path, value_hint = clap::ValueHint::FilePath)]
+    pr_template: Option<PullRequestTemplate>,
+
     /// GitHub personal access token with the `public_repo` scope
     #[arg(short, long, env = "GITHUB_TOKEN", hide_env_values = true)]
     token: Option<SecretString>,
@@ -184,6 +188,7 @@ pub async fn run(mut self) -> Result<()> {
                 .versions(&versions)
                 .changes(changes)
                 .issue_resolves(&self.resolves)
+                .maybe_template(self.pr_template.as_ref())
                 .send()
                 .await?;
 
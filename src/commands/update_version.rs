@@ -1,7 +1,5 @@
 use std::{
-    collections::BTreeSet,
-    collections::HashMap,
-    collections::HashSet,
+    collections::{BTreeSet, HashMap, HashSet},
     io::{Read, Seek},
     mem,
     num::{NonZeroU32, NonZeroUsize},
@@ -26,17 +24,20 @@
 
 use crate::{
     analysis::{Analyzer, installers::Zip},
-    commands::utils::{
-        SPINNER_TICK_RATE, SubmitOption, prompt_existing_pull_request, write_changes_to_dir,
+    commands::{
+        strategies::release_notes,
+        utils::{
+            SPINNER_TICK_RATE, SubmitOption, prompt_existing_pull_request, write_changes_to_dir,
+        },
     },
     download::{DownloadedFile, Downloader},
     download_file::process_files,
     github::{
         GITHUB_HOST, GitHubError, WINGET_PKGS_FULL_NAME,
         client::{GitHub, GitHubValues},
-        utils::{PackagePath, pull_request::pr_changes},
+        utils::{PackagePath, PullRequestTemplate, pull_request::pr_changes},
     },
-    manifests::Url,
+    manifests::{ReleaseNotesRules, Url},
     match_installers::match_installers,
     prompts::text::optional_prompt,
     token::TokenManager,
@@ -56,6 +57,10 @@ pub struct UpdateVersion {
     pub(super) package_version: Option<PackageVersion>,
 
     /// The list of package installers
+    ///
+    /// Installer properties can be set explicitly by appending them to a URL, such as
+    /// `url|arch=x64,scope=user,type=wix,locale=de-DE`. These also decide which previous
+    /// installer the URL replaces.
     #[arg(short, long, num_args = 1.., required = true, value_hint = clap::ValueHint::Url)]
     pub(super) urls: Vec<Url>,
 
@@ -103,10 +108,30 @@ pub struct UpdateVersion {
     #[arg(short, long, num_args = 0..=1, default_missing_value = "latest")]
     pub(super) replace: Option<PackageVersion>,
 
+    /// Push the manifests to the branch of an existing pull request instead of creating a new one
+    #[arg(long, value_name = "NUMBER", conflicts_with = "replace")]
+    pub(super) amend_pr: Option<NonZeroU32>,
+
     /// Skip checking for existing pull requests
     #[arg(long, env)]
     pub(super) skip_pr_check: bool,
 
+    /// YAML file with templates for the commit title and pull request body
+    #[arg(long, env = "KOMAC_PR_TEMPLATE", value_parser = PullRequestTemplate::from_path, value_hint = clap::ValueHint::FilePath)]
+    pub(super) pr_template: Option<PullRequestTemplate>,
+
+    /// The autoupdate strategy that found this version, for use in templates
+    #[arg(skip)]
+    pub(super) strategy: Option<&'static str>,
+
+    /// The URL the autoupdate strategy resolved this version from, for use in templates
+    #[arg(skip)]
+    pub(super) source_url: Option<DecodedUrl>,
+
+    /// Rules for removing content from the release notes, set by autoupdate recipes
+    #[arg(skip)]
+    pub(super) release_notes_rules: ReleaseNotesRules,
+
     /// GitHub personal access token with the `public_repo` scope
     #[arg(short, long, env = "GITHUB_TOKEN")]
     pub(super) token: Option<SecretString>,
@@ -163,6 +188,12 @@ pub async fn run(mut self) -> Result<()> {
             },
         )?;
 
+        let installer_overrides = files
+            .iter()
+            .filter(|file| !file.url.overrides().is_empty())
+            .map(|file| (file.url.inner().clone(), file.url.overrides().clone()))
+            .collect::<HashMap<_, _>>();
+
         let mut download_results = process_files(&mut files).await?;
         if self.package_version.is_none() {
             self.package_version = Some(self.infer_package_version(&download_results)?);
@@ -217,12 +248,20 @@ pub async fn run(mut self) -> Result<()> {
             .collect::<Vec<_>>();
 
         manifests.default_locale.package_version = self.package_version.as_ref().unwrap().clone();
-        let matched_installers = match_installers(previous_installers, &installer_results);
+        let matched_installers = match_installers(
+            previous_installers,
+            &installer_results,
+            &installer_overrides,
+        );
         let mut installers = matched_installers
             .into_iter()
             .map(|(previous_installer, new_installer)| {
                 let analyzer = &download_results[&new_installer.url];
+                let overrides = installer_overrides.get(&new_installer.url);
                 let installer_type = match previous_installer.r#type {
+                    _ if overrides.is_some_and(|overrides| overrides.installer_type.is_some()) => {
+                        new_installer.r#type
+                    }
                     Some(InstallerType::Portable) => previous_installer.r#type,
                     _ => match new_installer.r#type {
                         Some(InstallerType::Portable) => previous_installer.r#type,
@@ -252,7 +291,9 @@ pub async fn run(mut self) -> Result<()> {
                         fix_relative_paths(nested_files, analyzer.zip.as_ref());
                 }
 
-                if duplicate_urls.contains(&previous_url) {
+                if duplicate_urls.contains(&previous_url)
+                    && overrides.is_none_or(|overrides| overrides.architecture.is_none())
+                {
                     installer.architecture = previous_architecture;
                 }
 
@@ -311,6 +352,26 @@ pub async fn run(mut self) -> Result<()> {
             );
         });
 
+        // Strategies outside GitHub only know where the release notes are
+        if manifests.default_locale.release_notes.is_none()
+            && let Some(release_notes_url) = release_notes_url.as_ref()
+        {
+            manifests.default_locale.release_notes = release_notes::fetch(
+                release_notes_url,
+                package_version,
+                &self.release_notes_rules,
+            )
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!(
+                    url = %release_notes_url,
+                    error = %error,
+                    "Failed to fetch release notes"
+                );
+                None
+            });
+        }
+
         manifests.version.update(package_version);
 
         let package_path = PackagePath::new(&self.package_identifier, Some(package_version), None);
@@ -346,6 +407,47 @@ pub async fn run(mut self) -> Result<()> {
             return Ok(());
         }
 
+        if let Some(number) = self.amend_pr {
+            let pr_progress = ProgressBar::new_spinner().with_message(format!(
+                "Amending pull request #{number} for {} {}",
+                self.package_identifier, package_version
+            ));
+            pr_progress.enable_steady_tick(SPINNER_TICK_RATE);
+
+            let pull_request = github
+                .amend_pull_request()
+                .number(number)
+                .identifier(&self.package_identifier)
+                .version(package_version)
+                .versions(&versions)
+                .changes(changes)
+                .issue_resolves(&self.resolves)
+                .maybe_created_with(self.created_with.as_deref())
+                .maybe_created_with_url(self.created_with_url.as_ref())
+                .maybe_template(self.pr_template.as_ref())
+                .maybe_strategy(self.strategy)
+                .maybe_source_url(self.source_url.as_ref())
+                .maybe_release_notes(
+                    manifests
+                        .default_locale
+                        .release_notes
+                        .as_ref()
+                        .map(AsRef::<str>::as_ref),
+                )
+                .send()
+                .await?;
+
+            pr_progress.finish_and_clear();
+
+            pull_request.print_success();
+
+            if self.open_pr {
+                open::that(pull_request.url.as_str())?;
+            }
+
+            return Ok(());
+        }
+
         // Create an indeterminate progress bar to show as a pull request is being created
         let pr_progress = ProgressBar::new_spinner().with_message(format!(
             "Creating a pull request for {} {}",
@@ -363,6 +465,16 @@ pub async fn run(mut self) -> Result<()> {
             .issue_resolves(&self.resolves)
             .maybe_created_with(self.created_with.as_deref())
             .maybe_created_with_url(self.created_with_url.as_ref())
+            .maybe_template(self.pr_template.as_ref())
+            .maybe_strategy(self.strategy)
+            .maybe_source_url(self.source_url.as_ref())
+            .maybe_release_notes(
+                manifests
+                    .default_locale
+                    .release_notes
+                    .as_ref()
+                    .map(AsRef::<str>::as_ref),
+            )
             .send()
             .await?;
 
@@ -412,6 +524,11 @@ async fn should_abort_for_existing_pr(
         github: &GitHub,
         package_version: &PackageVersion,
     ) -> Result<bool> {
+        // The pull request being amended is expected to exist
+        if self.amend_pr.is_some() {
+            return Ok(false);
+        }
+
         if let Some(ref pull_request) = github
             .get_existing_pull_request(&self.package_identifier, package_version)
             .await?
@@ -474,7 +591,7 @@ async fn fetch_github_values(
             .find(|url| url.host_str() == Some(GITHUB_HOST))
         {
             github
-                .get_all_values_from_url(url.clone().into_inner())
+                .get_all_values_from_url(url.clone().into_inner(), Some(&self.release_notes_rules))
                 .await
                 .transpose()
         } else {
@@ -1,7 +1,11 @@
 use std::{fmt, num::NonZeroUsize};
 
+use camino::Utf8Path;
 use chrono::DateTime;
-use color_eyre::{Result, eyre::bail};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail},
+};
 use futures_util::{StreamExt, TryStreamExt, stream};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use itertools::{Itertools, Position};
@@ -22,6 +26,9 @@
 use winget_types::Sha256String;
 
 use super::{Download, DownloadedFile};
+use crate::analysis::{
+    extensions::APP_INSTALLER, installers::msix_family::app_installer::AppInstaller,
+};
 
 pub struct Downloader {
     client: Client,
@@ -84,7 +91,7 @@ pub async fn download<I, D>(&self, downloads: I) -> Result<Vec<DownloadedFile>>
         let multi_progress = crate::terminal::multi_progress();
 
         let downloaded_files = stream::iter(downloads.into_iter().map(D::into).unique())
-            .map(|download| self.fetch(&self.client, download, multi_progress))
+            .map(|download| self.fetch(&self.client, download, multi_progress, true))
             .buffer_unordered(self.concurrent_downloads.get())
             .try_collect::<Vec<_>>()
             .await?;
@@ -120,11 +127,14 @@ fn check_content_types(
         Ok(())
     }
 
+    /// Downloads a file, following an App Installer file to the package it points to if
+    /// `follow_app_installer` is true.
     pub async fn fetch(
         &self,
         client: &Client,
         mut download: Download,
         multi_progress: &MultiProgress,
+        follow_app_installer: bool,
     ) -> Result<DownloadedFile> {
         download.convert_to_github_versioned().await?;
 
@@ -140,13 +150,38 @@ pub async fn fetch(
             )
         }
 
-        // Check that we're downloading an application
-        Self::check_content_types(&download, res.headers().get_all(CONTENT_TYPE))?;
-
         let file_name = download
             .file_name(res.url(), res.headers().get(CONTENT_DISPOSITION))
             .into_owned();
 
+        // App Installer files point to the current package, so download that instead. They are
+        // XML, so this happens before checking that the content is an application.
+        if Utf8Path::new(&file_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(APP_INSTALLER))
+        {
+            // An App Installer file should point to a package, not to another App Installer file
+            if !follow_app_installer {
+                bail!(
+                    "{download} is an App Installer file pointed to by another App Installer file"
+                );
+            }
+
+            let app_installer = AppInstaller::new(&res.text().await?)
+                .wrap_err_with(|| format!("Failed to parse App Installer file from {download}"))?;
+
+            let mut url = download.into_url();
+            *url.inner_mut() = app_installer.main_package.uri.clone();
+
+            let mut downloaded_file =
+                Box::pin(self.fetch(client, Download::new(url), multi_progress, false)).await?;
+            downloaded_file.package_version = app_installer.package_version();
+            return Ok(downloaded_file);
+        }
+
+        // Check that we're downloading an application
+        Self::check_content_types(&download, res.headers().get_all(CONTENT_TYPE))?;
+
         let last_modified = res
             .headers()
             .get(LAST_MODIFIED)
@@ -218,6 +253,7 @@ pub async fn fetch(
             sha_256: Sha256String::from_digest(&sha_256),
             file_name,
             last_modified,
+            package_version: None,
         })
     }
 }
@@ -5,7 +5,7 @@
 use chrono::NaiveDate;
 use color_eyre::eyre::Result;
 use sha2::{Digest, Sha256};
-use winget_types::Sha256String;
+use winget_types::{PackageVersion, Sha256String};
 
 use crate::manifests::Url;
 
@@ -15,6 +15,8 @@ pub struct DownloadedFile {
     pub sha_256: Sha256String,
     pub file_name: String,
     pub last_modified: Option<NaiveDate>,
+    /// The version given by the App Installer file the file was resolved from, if any.
+    pub package_version: Option<PackageVersion>,
 }
 
 impl DownloadedFile {
@@ -30,6 +32,7 @@ pub fn from_local(path: &Utf8Path, url: Url) -> Result<Self> {
             sha_256,
             file_name,
             last_modified: None,
+            package_version: None,
         })
     }
 }
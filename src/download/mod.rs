@@ -11,7 +11,7 @@
 use uuid::Uuid;
 use winget_types::installer::VALID_FILE_EXTENSIONS;
 
-use crate::{github::GITHUB_HOST, manifests::Url};
+use crate::{analysis::extensions::APP_INSTALLER, github::GITHUB_HOST, manifests::Url};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct Download(Url);
@@ -85,7 +85,10 @@ fn file_name<'a>(
             .filter(|last_segment| {
                 Utf8Path::new(last_segment)
                     .extension()
-                    .is_some_and(|extension| VALID_FILE_EXTENSIONS.contains(&extension))
+                    .is_some_and(|extension| {
+                        VALID_FILE_EXTENSIONS.contains(&extension)
+                            || extension.eq_ignore_ascii_case(APP_INSTALLER)
+                    })
             })
             .or_else(|| {
                 final_url
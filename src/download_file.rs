@@ -22,6 +22,7 @@ pub async fn process_files(
              sha_256,
              file_name,
              last_modified,
+             package_version,
              ..
          }| async move {
             let mut file_analyser = match Analyzer::new(file, file_name) {
@@ -45,12 +46,16 @@ pub async fn process_files(
                 if let Some(architecture) = architecture {
                     installer.architecture = architecture;
                 }
+                url.overrides().apply(installer);
                 debug!("{url}: {architecture:?}");
                 installer.url = url.inner().clone();
                 installer.sha_256 = sha_256.clone();
                 installer.release_date = *last_modified;
             }
             file_analyser.file_name = mem::take(file_name);
+            if file_analyser.package_version.is_none() {
+                file_analyser.package_version = package_version.take();
+            }
             Ok(Some((mem::take(url.inner_mut()), file_analyser)))
         },
     ))
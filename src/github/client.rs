@@ -1,15 +1,18 @@
-use std::{borrow::Cow, collections::BTreeSet, num::NonZeroU32, str::FromStr};
+use std::{borrow::Cow, collections::BTreeSet, num::NonZeroU32, str::FromStr, time::SystemTime};
 
 use bon::bon;
 use color_eyre::eyre::eyre;
-use cynic::{GraphQlResponse, Id, MutationBuilder, QueryBuilder, http::ReqwestExt};
+use cynic::{
+    GraphQlResponse, Id, MutationBuilder, Operation, QueryBuilder, http::CynicReqwestError,
+};
 use futures_util::future::OptionFuture;
 use indexmap::IndexMap;
 use indicatif::ProgressBar;
 use itertools::Itertools;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use secrecy::SecretString;
-use serde::de::DeserializeOwned;
+use serde::{Serialize, de::DeserializeOwned};
+use tracing::warn;
 use url::Url;
 use winget_types::{
     Manifest, ManifestType, ManifestTypeWithLocale, PackageIdentifier, PackageVersion,
@@ -19,7 +22,14 @@
     version::VersionManifest,
 };
 
-use super::{GitHubError, graphql::create_pull_request};
+use super::{
+    GitHubError,
+    graphql::create_pull_request,
+    rate_limit::{
+        MAX_RATE_LIMIT_RETRIES, RATE_LIMIT_GOVERNOR, RateLimitResource, SECONDARY_RATE_LIMIT_DELAY,
+        retry_delay, secondary_rate_limit_delay,
+    },
+};
 use crate::{
     commands::{cleanup::MergeState, utils::SPINNER_TICK_RATE},
     github::{
@@ -32,18 +42,19 @@
             get_branches::{GetBranches, GetBranchesVariables, PullRequest, RefConnection},
             get_directory_content::GetDirectoryContentVariables,
             get_directory_content_with_text::{GetDirectoryContentWithText, TreeEntry},
+            get_pull_request,
             get_repository_info::{GetRepositoryInfo, RepositoryVariables, TargetGitObject},
             types::GitObjectId,
             update_refs::{RefUpdate, UpdateRefs, UpdateRefsInput},
         },
         utils::{
-            CommitTitle, PackagePath, branch_name, commit_title, is_manifest_file,
-            pull_request_body,
+            CommitTitle, PackagePath, PullRequestTemplate, TemplateValues, branch_name,
+            commit_title, is_manifest_file, pull_request_body, pull_request_headline,
         },
     },
-    manifests::Manifests,
+    manifests::{Manifests, ReleaseNotesRules, markdown_to_release_notes},
     token::default_headers,
-    traits::FromHtml,
+    traits::html_to_text,
     update_state::UpdateState,
 };
 
@@ -64,6 +75,95 @@ pub fn new<T>(token: T) -> Result<Self, GitHubError>
         ))
     }
 
+    /// Sends a request once the rate limit allows it, retrying it while GitHub rate limits it.
+    ///
+    /// A forbidden response that isn't rate limited is returned as an error with its body, as the
+    /// body had to be read to tell it apart from a secondary rate limit.
+    pub(super) async fn send(&self, request: RequestBuilder) -> Result<Response, GitHubError> {
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        let resource = if request.url().as_str() == GRAPHQL_URL {
+            RateLimitResource::GraphQl
+        } else {
+            RateLimitResource::Core
+        };
+
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            RATE_LIMIT_GOVERNOR.wait(resource).await;
+            let response = client.execute(request).await?;
+            RATE_LIMIT_GOVERNOR.observe(response.headers()).await;
+
+            let status = response.status();
+            let delay = retry_delay(status, response.headers(), attempt, SystemTime::now());
+            let Some(retry) = retry.filter(|_| attempt < MAX_RATE_LIMIT_RETRIES) else {
+                return Ok(response);
+            };
+
+            let delay = match delay {
+                Some(delay) => delay,
+                None if status == StatusCode::FORBIDDEN => {
+                    let body = response.text().await?;
+                    secondary_rate_limit_delay(&body, attempt)
+                        .ok_or_else(|| CynicReqwestError::ErrorResponse(status, body))?
+                }
+                None => return Ok(response),
+            };
+
+            warn!(
+                url = %retry.url(),
+                status = %status,
+                "Retrying request after GitHub rate limited it"
+            );
+            RATE_LIMIT_GOVERNOR.pause(delay).await;
+            request = retry;
+            attempt += 1;
+        }
+    }
+
+    /// Runs a GraphQL operation, retrying it while GitHub rate limits it.
+    pub(super) async fn run_graphql<ResponseData, Vars>(
+        &self,
+        operation: Operation<ResponseData, Vars>,
+    ) -> Result<GraphQlResponse<ResponseData>, GitHubError>
+    where
+        ResponseData: DeserializeOwned + 'static,
+        Vars: Serialize,
+    {
+        let mut attempt = 0;
+        loop {
+            let response = self.send(self.0.post(GRAPHQL_URL).json(&operation)).await?;
+
+            let status = response.status();
+            let response = if status.is_success() {
+                response.json::<GraphQlResponse<ResponseData>>().await?
+            } else {
+                let body = response.text().await?;
+                match serde_json::from_str::<GraphQlResponse<ResponseData>>(&body) {
+                    Ok(response) => response,
+                    Err(_) => return Err(CynicReqwestError::ErrorResponse(status, body).into()),
+                }
+            };
+
+            // GitHub reports an exhausted GraphQL budget as an error in a successful response
+            let is_rate_limited = response.data.is_none()
+                && response
+                    .errors
+                    .iter()
+                    .flatten()
+                    .any(|error| error.message.to_ascii_lowercase().contains("rate limit"));
+
+            if !is_rate_limited || attempt >= MAX_RATE_LIMIT_RETRIES {
+                return Ok(response);
+            }
+
+            warn!("Retrying GraphQL operation after GitHub rate limited it");
+            RATE_LIMIT_GOVERNOR.pause(SECONDARY_RATE_LIMIT_DELAY).await;
+            attempt += 1;
+        }
+    }
+
     pub async fn get_manifests(
         &self,
         identifier: &PackageIdentifier,
@@ -135,8 +235,6 @@ async fn get_directory_content_with_text(
         path: &PackagePath,
     ) -> Result<impl Iterator<Item = GitHubFile>, GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(GetDirectoryContentWithText::build(
                 GetDirectoryContentVariables::new(&owner, &repo, &format!("HEAD:{path}")),
             ))
@@ -184,8 +282,6 @@ async fn get_repository_info(
         name: &str,
     ) -> Result<RepositoryData, GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(GetRepositoryInfo::build(RepositoryVariables::new(
                 owner, name,
             )))
@@ -234,8 +330,6 @@ pub async fn create_branch(
         oid: GitObjectId,
     ) -> Result<CreateBranchRef, GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(CreateRef::build(
                 CreateRefVariables::builder()
                     .name(&format!("refs/heads/{branch_name}"))
@@ -262,8 +356,6 @@ pub async fn get_branches<T: AsRef<str>>(
 
         loop {
             let GraphQlResponse { data, errors } = self
-                .0
-                .post(GRAPHQL_URL)
                 .run_graphql(GetBranches::build(GetBranchesVariables {
                     owner: user,
                     name: WINGET_PKGS,
@@ -332,8 +424,6 @@ pub async fn delete_branches<I, T>(
         T: Into<String>,
     {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(UpdateRefs::build(UpdateRefsInput::new(
                 RefUpdate::delete_branches(branch_names),
                 repository_id,
@@ -353,6 +443,7 @@ pub async fn delete_branches<I, T>(
     pub fn get_all_values_from_url(
         &self,
         url: DecodedUrl,
+        release_notes_rules: Option<&ReleaseNotesRules>,
     ) -> OptionFuture<impl Future<Output = Result<GitHubValues, GitHubError>> + Sized> {
         url.path_segments()
             .and_then(|mut parts| {
@@ -363,7 +454,12 @@ pub fn get_all_values_from_url(
                     .repo(parts.next()?.to_owned());
                 let _releases = parts.next()?;
                 let _download = parts.next()?;
-                Some(builder.tag_name(parts.join("/")).send())
+                Some(
+                    builder
+                        .tag_name(parts.join("/"))
+                        .maybe_release_notes_rules(release_notes_rules)
+                        .send(),
+                )
             })
             .into()
     }
@@ -374,10 +470,9 @@ pub async fn get_all_values<'a>(
         #[builder(into)] owner: Cow<'a, str>,
         #[builder(into)] repo: Cow<'a, str>,
         #[builder(into)] tag_name: Cow<'a, str>,
+        release_notes_rules: Option<&'a ReleaseNotesRules>,
     ) -> Result<GitHubValues, GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(GetAllValues::build(GetAllValuesVariables {
                 name: &repo,
                 owner: &owner,
@@ -449,7 +544,18 @@ pub async fn get_all_values<'a>(
             package_url: repository.url.as_str().parse::<PackageUrl>()?,
             release_notes: release
                 .as_ref()
-                .and_then(|release| ReleaseNotes::from_html(release.description_html.as_ref()?)),
+                .and_then(|release| {
+                    release
+                        .description
+                        .clone()
+                        .or_else(|| release.description_html.as_ref().and_then(html_to_text))
+                })
+                .and_then(|markdown| {
+                    markdown_to_release_notes(
+                        &markdown,
+                        release_notes_rules.unwrap_or(&ReleaseNotesRules::default()),
+                    )
+                }),
             release_notes_url: release
                 .and_then(|release| release.url.as_str().parse::<ReleaseNotesUrl>().ok()),
             topics,
@@ -466,13 +572,41 @@ pub async fn remove_version(
         winget_pkgs: &RepositoryData,
         #[builder(default)] issue_resolves: &[NonZeroU32],
     ) -> Result<create_pull_request::PullRequest, GitHubError> {
+        self.remove_versions()
+            .identifier(identifier)
+            .versions(&[version])
+            .reason(reason)
+            .fork(fork)
+            .winget_pkgs(winget_pkgs)
+            .issue_resolves(issue_resolves)
+            .send()
+            .await
+    }
+
+    /// Creates a single pull request that removes all the given versions of a package.
+    #[builder(finish_fn = send)]
+    pub async fn remove_versions(
+        &self,
+        identifier: &PackageIdentifier,
+        versions: &[&PackageVersion],
+        reason: &str,
+        fork: &RepositoryData,
+        winget_pkgs: &RepositoryData,
+        #[builder(default)] issue_resolves: &[NonZeroU32],
+    ) -> Result<create_pull_request::PullRequest, GitHubError> {
+        let (first_version, other_versions) = versions
+            .split_first()
+            .ok_or_else(|| eyre!("no versions of {identifier} were given to remove"))?;
+
+        let joined_versions = versions.iter().join(", ");
+
         // Create an indeterminate progress bar to show as a pull request is being created
         let pr_progress = ProgressBar::new_spinner().with_message(format!(
-            "Creating a pull request to remove {identifier} {version}",
+            "Creating a pull request to remove {identifier} {joined_versions}",
         ));
         pr_progress.enable_steady_tick(SPINNER_TICK_RATE);
 
-        let branch_name = branch_name(identifier, version);
+        let branch_name = branch_name(identifier, first_version);
         let pull_request_branch = self
             .create_branch(
                 &fork.id,
@@ -480,16 +614,26 @@ pub async fn remove_version(
                 winget_pkgs.default_branch_oid.clone(),
             )
             .await?;
-        let commit_title = CommitTitle::remove(identifier, version).to_string();
-        let deletions = self
-            .get_directory_content()
-            .owner(&fork.owner)
-            .branch_name(&branch_name)
-            .path(&PackagePath::new(identifier, Some(version), None))
-            .call()
-            .await?
-            .map(FileDeletion::new)
-            .collect::<Vec<_>>();
+        let commit_title = if other_versions.is_empty() {
+            CommitTitle::remove(identifier, first_version).to_string()
+        } else {
+            format!(
+                "{}: {identifier} versions {joined_versions}",
+                UpdateState::RemoveVersion
+            )
+        };
+        let mut deletions = Vec::new();
+        for version in versions {
+            deletions.extend(
+                self.get_directory_content()
+                    .owner(&fork.owner)
+                    .branch_name(&branch_name)
+                    .path(&PackagePath::new(identifier, Some(version), None))
+                    .call()
+                    .await?
+                    .map(FileDeletion::new),
+            );
+        }
         let _commit_url = self
             .commit()
             .branch_id(&pull_request_branch.id)
@@ -530,6 +674,10 @@ pub async fn add_version(
         issue_resolves: &[NonZeroU32],
         created_with: Option<&str>,
         created_with_url: Option<&DecodedUrl>,
+        template: Option<&PullRequestTemplate>,
+        strategy: Option<&str>,
+        source_url: Option<&DecodedUrl>,
+        release_notes: Option<&str>,
     ) -> Result<create_pull_request::PullRequest, GitHubError> {
         let (current_user, winget_pkgs) =
             tokio::try_join!(self.get_username(), self.get_winget_pkgs().send())?;
@@ -538,7 +686,21 @@ pub async fn add_version(
         let pull_request_branch = self
             .create_branch(&fork.id, &branch_name, winget_pkgs.default_branch_oid)
             .await?;
-        let commit_title = commit_title(identifier, version, UpdateState::get(version, versions));
+        let update_state = UpdateState::get(version, versions);
+        let headline = pull_request_headline(created_with, created_with_url);
+        let template_values = TemplateValues::builder()
+            .identifier(identifier)
+            .version(version)
+            .update_state(update_state)
+            .headline(&headline)
+            .maybe_strategy(strategy)
+            .maybe_source_url(source_url)
+            .maybe_release_notes(release_notes)
+            .issue_resolves(issue_resolves)
+            .build();
+        let commit_title = template
+            .and_then(|template| template.commit_title(&template_values))
+            .unwrap_or_else(|| commit_title(identifier, version, update_state));
         let additions = changes
             .iter()
             .map(|(path, content)| FileAddition::new(path, content))
@@ -570,14 +732,142 @@ pub async fn add_version(
             &format!("{current_user}:{}", pull_request_branch.name),
             &winget_pkgs.default_branch_name,
             &commit_title,
-            &pull_request_body()
-                .issue_resolves(issue_resolves)
-                .maybe_created_with(created_with)
-                .maybe_created_with_url(created_with_url)
-                .build(),
+            &template
+                .and_then(|template| template.pull_request_body(&template_values))
+                .unwrap_or_else(|| {
+                    pull_request_body()
+                        .issue_resolves(issue_resolves)
+                        .maybe_created_with(created_with)
+                        .maybe_created_with_url(created_with_url)
+                        .build()
+                }),
         )
         .await
     }
+
+    /// Pushes a new commit with the given changes to the branch of an existing pull request.
+    ///
+    /// Files in the version's directory on the pull request branch that are not part of the
+    /// changes are deleted.
+    #[builder(finish_fn = send)]
+    pub async fn amend_pull_request(
+        &self,
+        number: NonZeroU32,
+        identifier: &PackageIdentifier,
+        version: &PackageVersion,
+        versions: Option<&BTreeSet<PackageVersion>>,
+        changes: Vec<(String, String)>,
+        issue_resolves: &[NonZeroU32],
+        created_with: Option<&str>,
+        created_with_url: Option<&DecodedUrl>,
+        template: Option<&PullRequestTemplate>,
+        strategy: Option<&str>,
+        source_url: Option<&DecodedUrl>,
+        release_notes: Option<&str>,
+    ) -> Result<get_pull_request::PullRequest, GitHubError> {
+        let (current_user, pull_request) =
+            tokio::try_join!(self.get_username(), self.get_pull_request(number))?;
+        let pull_request = pull_request.ok_or(GitHubError::PullRequestNonExistent(number))?;
+
+        if !pull_request.state.is_open() {
+            return Err(GitHubError::PullRequestNotOpen {
+                number,
+                state: pull_request.state,
+            });
+        }
+
+        let head_owner = pull_request.head_owner().unwrap_or_default();
+        if !head_owner.eq_ignore_ascii_case(&current_user) {
+            return Err(GitHubError::PullRequestNotOwned {
+                number,
+                owner: head_owner.to_owned(),
+                user: current_user,
+            });
+        }
+
+        let head_ref = pull_request
+            .head_ref
+            .as_ref()
+            .ok_or(GitHubError::PullRequestBranchDeleted(number))?;
+        let head_sha = head_ref
+            .target
+            .as_ref()
+            .map(|target| target.oid.clone())
+            .ok_or(GitHubError::PullRequestBranchDeleted(number))?;
+
+        // The version directory may not exist on the branch yet if the pull request was for a
+        // different version, in which case there is nothing in it to delete
+        let version_files = match self
+            .get_directory_content()
+            .owner(&current_user)
+            .branch_name(&pull_request.head_ref_name)
+            .path(&PackagePath::new(identifier, Some(version), None))
+            .call()
+            .await
+        {
+            Ok(paths) => paths.collect::<BTreeSet<_>>(),
+            Err(GitHubError::PathNotFound { .. }) => BTreeSet::new(),
+            Err(error) => return Err(error),
+        };
+
+        // Delete the files that the pull request added for a previous version as well, so that it
+        // doesn't end up adding two versions
+        let deletions = pull_request
+            .added_files()
+            .map(str::to_owned)
+            .chain(version_files)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|path| !changes.iter().any(|(change_path, _)| change_path == path))
+            .map(FileDeletion::new)
+            .collect::<Vec<_>>();
+        let additions = changes
+            .iter()
+            .map(|(path, content)| FileAddition::new(path, content))
+            .collect::<Vec<_>>();
+        let update_state = UpdateState::get(version, versions);
+        let headline = pull_request_headline(created_with, created_with_url);
+        let template_values = TemplateValues::builder()
+            .identifier(identifier)
+            .version(version)
+            .update_state(update_state)
+            .headline(&headline)
+            .maybe_strategy(strategy)
+            .maybe_source_url(source_url)
+            .maybe_release_notes(release_notes)
+            .issue_resolves(issue_resolves)
+            .build();
+        let commit_title = template
+            .and_then(|template| template.commit_title(&template_values))
+            .unwrap_or_else(|| commit_title(identifier, version, update_state));
+        let _commit_url = self
+            .commit()
+            .branch_id(&head_ref.id)
+            .head_sha(head_sha)
+            .message(&commit_title)
+            .additions(additions)
+            .deletions(deletions)
+            .create()
+            .await?;
+
+        // Render the title and body the same way as when the pull request was created, so that
+        // they describe the amended version
+        let body = template
+            .and_then(|template| template.pull_request_body(&template_values))
+            .unwrap_or_else(|| {
+                pull_request_body()
+                    .issue_resolves(issue_resolves)
+                    .maybe_created_with(created_with)
+                    .maybe_created_with_url(created_with_url)
+                    .build()
+            });
+        if pull_request.title != commit_title || pull_request.body != body {
+            self.update_pull_request(&pull_request.id, &commit_title, &body)
+                .await?;
+        }
+
+        Ok(pull_request)
+    }
 }
 
 pub struct GitHubValues {
@@ -1,9 +1,11 @@
+use std::num::NonZeroU32;
+
 use color_eyre::{Report, eyre};
 use cynic::http::CynicReqwestError;
 use thiserror::Error;
 use winget_types::{ManifestType, PackageIdentifier};
 
-use super::{WINGET_PKGS_FULL_NAME, utils::PackagePath};
+use super::{WINGET_PKGS_FULL_NAME, graphql::types::PullRequestState, utils::PackagePath};
 
 #[derive(Debug, Error)]
 pub enum GitHubError {
@@ -16,8 +18,25 @@ pub enum GitHubError {
         r#type: ManifestType,
         path: PackagePath,
     },
+    #[error("{path} does not exist on {branch}")]
+    PathNotFound { path: PackagePath, branch: String },
     #[error("No valid files were found for {path}")]
     NoValidFiles { path: PackagePath },
+    #[error("Pull request #{0} does not exist in {WINGET_PKGS_FULL_NAME}")]
+    PullRequestNonExistent(NonZeroU32),
+    #[error("#{number} is a {state} pull request and can no longer be amended")]
+    PullRequestNotOpen {
+        number: NonZeroU32,
+        state: PullRequestState,
+    },
+    #[error("#{number} was opened from {owner}'s fork and cannot be amended by {user}")]
+    PullRequestNotOwned {
+        number: NonZeroU32,
+        owner: String,
+        user: String,
+    },
+    #[error("The branch of pull request #{0} no longer exists")]
+    PullRequestBranchDeleted(NonZeroU32),
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
     #[error(transparent)]
@@ -69,6 +69,7 @@ pub struct Topic {
 /// <https://docs.github.com/graphql/reference/objects#release>
 #[derive(cynic::QueryFragment)]
 pub struct Release {
+    pub description: Option<String>,
     #[cynic(rename = "descriptionHTML")]
     pub description_html: Option<Html>,
     pub url: Url,
@@ -134,6 +135,7 @@ fn get_all_values_output() {
                   }
                 }
                 release(tagName: $tagName) {
+                  description
                   descriptionHTML
                   url
                 }
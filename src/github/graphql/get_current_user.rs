@@ -1,11 +1,11 @@
 use std::env;
 
 use color_eyre::eyre::eyre;
-use cynic::{GraphQlResponse, QueryBuilder, http::ReqwestExt};
+use cynic::{GraphQlResponse, QueryBuilder};
 
 use super::{
     super::{GitHubError, client::GitHub},
-    GRAPHQL_URL, github_schema as schema,
+    github_schema as schema,
 };
 
 /// <https://docs.github.com/graphql/reference/queries#viewer>
@@ -40,11 +40,8 @@ pub async fn get_username(&self) -> Result<String, GitHubError> {
         if let Ok(login) = env::var(KOMAC_FORK_OWNER) {
             Ok(login)
         } else {
-            let GraphQlResponse { data, errors } = self
-                .0
-                .post(GRAPHQL_URL)
-                .run_graphql(GetCurrentUserLogin::build(()))
-                .await?;
+            let GraphQlResponse { data, errors } =
+                self.run_graphql(GetCurrentUserLogin::build(())).await?;
 
             let Some(data) = data else {
                 return Err(GitHubError::graphql_errors(
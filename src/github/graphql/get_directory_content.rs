@@ -2,11 +2,11 @@
 
 use bon::bon;
 use color_eyre::eyre::eyre;
-use cynic::{GraphQlResponse, QueryBuilder, http::ReqwestExt};
+use cynic::{GraphQlResponse, QueryBuilder};
 
 use super::{
     super::{GitHubError, MICROSOFT, WINGET_PKGS, client::GitHub, utils::PackagePath},
-    GRAPHQL_URL, GetFileContent, github_schema as schema,
+    GetFileContent, github_schema as schema,
 };
 
 #[derive(cynic::QueryVariables)]
@@ -87,8 +87,6 @@ pub async fn get_file_content<O, R, P>(
         P: fmt::Display,
     {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(GetFileContent::build(GetDirectoryContentVariables::new(
                 &owner,
                 &repo,
@@ -100,6 +98,8 @@ pub async fn get_file_content<O, R, P>(
             .ok_or_else(|| GitHubError::graphql_errors(eyre!("failed to get {path}"), errors))
     }
 
+    /// Returns the paths of the files in a directory, or [`GitHubError::PathNotFound`] if the
+    /// directory doesn't exist on the branch.
     #[builder]
     pub async fn get_directory_content(
         &self,
@@ -109,20 +109,26 @@ pub async fn get_directory_content(
         path: &PackagePath,
     ) -> Result<impl Iterator<Item = String>, GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(GetDirectoryContent::build(
                 GetDirectoryContentVariables::new(&owner, &repo, &format!("{branch_name}:{path}")),
             ))
             .await?;
-        let entries = data
-            .and_then(|data| data.repository?.object?.into_entries())
-            .ok_or_else(|| {
-                GitHubError::graphql_errors(
-                    eyre!("failed to get {path} in {branch_name} from {owner}/{repo}"),
-                    errors,
-                )
-            })?;
+        let error = || {
+            GitHubError::graphql_errors(
+                eyre!("failed to get {path} in {branch_name} from {owner}/{repo}"),
+                errors,
+            )
+        };
+        let Some(repository) = data.and_then(|data| data.repository) else {
+            return Err(error());
+        };
+        let Some(object) = repository.object else {
+            return Err(GitHubError::PathNotFound {
+                path: path.clone(),
+                branch: branch_name.to_owned(),
+            });
+        };
+        let entries = object.into_entries().ok_or_else(error)?;
 
         Ok(entries.into_iter().filter_map(|entry| entry.path))
     }
@@ -0,0 +1,285 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use cynic::{GraphQlResponse, QueryBuilder};
+use serde::Serialize;
+use url::Url;
+
+use super::{
+    super::{GitHubError, WINGET_PKGS_FULL_NAME, client::GitHub},
+    get_branches::PageInfo,
+    github_schema as schema,
+};
+
+#[derive(cynic::QueryVariables)]
+pub struct GetOpenPullRequestsVariables<'a> {
+    pub query: &'a str,
+    pub cursor: Option<&'a str>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "GetOpenPullRequestsVariables")]
+pub struct GetOpenPullRequests {
+    #[arguments(first: 100, after: $cursor, type: ISSUE, query: $query)]
+    pub search: SearchResultItemConnection,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#searchresultitemconnection>
+#[derive(cynic::QueryFragment)]
+pub struct SearchResultItemConnection {
+    #[cynic(flatten)]
+    pub nodes: Vec<SearchResultItem>,
+    pub page_info: PageInfo,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequest>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequest {
+    pub number: i32,
+    pub title: String,
+    pub url: Url,
+    pub created_at: DateTime<Utc>,
+    pub is_draft: bool,
+    pub review_decision: Option<PullRequestReviewDecision>,
+    #[arguments(first: 20)]
+    pub labels: Option<LabelConnection>,
+    #[arguments(last: 1)]
+    pub commits: PullRequestCommitConnection,
+}
+
+impl PullRequest {
+    /// Returns the names of the labels on the pull request.
+    pub fn label_names(&self) -> impl Iterator<Item = &str> {
+        self.labels
+            .iter()
+            .flat_map(|labels| labels.nodes.iter())
+            .map(|label| label.name.as_str())
+    }
+
+    /// Returns the combined state of the checks and statuses on the head commit of the pull
+    /// request, or `None` if no checks have been reported yet.
+    pub fn checks_state(&self) -> Option<StatusState> {
+        self.commits
+            .nodes
+            .last()?
+            .commit
+            .status_check_rollup
+            .as_ref()
+            .map(|rollup| rollup.state)
+    }
+}
+
+/// <https://docs.github.com/graphql/reference/objects#labelconnection>
+#[derive(cynic::QueryFragment)]
+pub struct LabelConnection {
+    #[cynic(flatten)]
+    pub nodes: Vec<Label>,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#label>
+#[derive(cynic::QueryFragment)]
+pub struct Label {
+    pub name: String,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequestcommitconnection>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequestCommitConnection {
+    #[cynic(flatten)]
+    pub nodes: Vec<PullRequestCommit>,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequestcommit>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequestCommit {
+    pub commit: Commit,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#commit>
+#[derive(cynic::QueryFragment)]
+pub struct Commit {
+    pub status_check_rollup: Option<StatusCheckRollup>,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#statuscheckrollup>
+#[derive(cynic::QueryFragment)]
+pub struct StatusCheckRollup {
+    pub state: StatusState,
+}
+
+/// The possible commit status states.
+///
+/// See <https://docs.github.com/graphql/reference/enums#statusstate>.
+#[derive(cynic::Enum, Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusState {
+    /// Status is errored.
+    Error,
+
+    /// Status is expected.
+    Expected,
+
+    /// Status is failing.
+    Failure,
+
+    /// Status is pending.
+    Pending,
+
+    /// Status is successful.
+    Success,
+}
+
+impl StatusState {
+    /// Returns `true` if the status has errored or is failing.
+    #[inline]
+    pub const fn is_failing(self) -> bool {
+        matches!(self, Self::Error | Self::Failure)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Expected => "expected",
+            Self::Failure => "failure",
+            Self::Pending => "pending",
+            Self::Success => "success",
+        }
+    }
+}
+
+/// The review status of a pull request.
+///
+/// See <https://docs.github.com/graphql/reference/enums#pullrequestreviewdecision>.
+#[derive(cynic::Enum, Clone, Copy, Debug, Eq, PartialEq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PullRequestReviewDecision {
+    /// The pull request has received an approving review.
+    Approved,
+
+    /// Changes have been requested on the pull request.
+    ChangesRequested,
+
+    /// A review is required before the pull request can be merged.
+    ReviewRequired,
+}
+
+impl PullRequestReviewDecision {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes requested",
+            Self::ReviewRequired => "review required",
+        }
+    }
+}
+
+#[derive(cynic::InlineFragments)]
+pub enum SearchResultItem {
+    PullRequest(PullRequest),
+    #[cynic(fallback)]
+    Unknown,
+}
+
+impl SearchResultItem {
+    pub fn into_pull_request(self) -> Option<PullRequest> {
+        match self {
+            Self::PullRequest(pull_request) => Some(pull_request),
+            Self::Unknown => None,
+        }
+    }
+}
+
+impl GitHub {
+    /// Returns all open pull requests to winget-pkgs that were authored by the given user.
+    pub async fn get_open_pull_requests(
+        &self,
+        author: &str,
+    ) -> Result<Vec<PullRequest>, GitHubError> {
+        let query = format!("repo:{WINGET_PKGS_FULL_NAME} is:pr is:open author:{author}");
+
+        let mut pull_requests = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let GraphQlResponse { data, errors } = self
+                .run_graphql(GetOpenPullRequests::build(GetOpenPullRequestsVariables {
+                    query: &query,
+                    cursor: cursor.as_deref(),
+                }))
+                .await?;
+
+            let search = data.map(|data| data.search).ok_or_else(|| {
+                GitHubError::graphql_errors(
+                    eyre!("failed to get open pull requests by {author}"),
+                    errors,
+                )
+            })?;
+
+            pull_requests.extend(
+                search
+                    .nodes
+                    .into_iter()
+                    .filter_map(SearchResultItem::into_pull_request),
+            );
+
+            if search.page_info.has_next_page {
+                cursor = search.page_info.end_cursor;
+            } else {
+                return Ok(pull_requests);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cynic::QueryBuilder;
+    use indoc::indoc;
+
+    use super::{GetOpenPullRequests, GetOpenPullRequestsVariables};
+
+    #[test]
+    fn get_open_pull_requests_output() {
+        const GET_OPEN_PULL_REQUESTS_QUERY: &str = indoc! {r#"
+            query GetOpenPullRequests($query: String!, $cursor: String) {
+              search(first: 100, after: $cursor, type: ISSUE, query: $query) {
+                nodes {
+                  __typename
+                  ... on PullRequest {
+                    number
+                    title
+                    url
+                    createdAt
+                    isDraft
+                    reviewDecision
+                    labels(first: 20) {
+                      nodes {
+                        name
+                      }
+                    }
+                    commits(last: 1) {
+                      nodes {
+                        commit {
+                          statusCheckRollup {
+                            state
+                          }
+                        }
+                      }
+                    }
+                  }
+                }
+                pageInfo {
+                  endCursor
+                  hasNextPage
+                }
+              }
+            }
+        "#};
+
+        let operation = GetOpenPullRequests::build(GetOpenPullRequestsVariables {
+            query: "",
+            cursor: None,
+        });
+
+        assert_eq!(operation.query, GET_OPEN_PULL_REQUESTS_QUERY);
+    }
+}
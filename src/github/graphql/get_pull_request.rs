@@ -0,0 +1,209 @@
+use std::{io::Write, num::NonZeroU32};
+
+use color_eyre::eyre::eyre;
+use cynic::{GraphQlResponse, QueryBuilder};
+use owo_colors::OwoColorize;
+use url::Url;
+
+use super::{
+    super::{GitHubError, MICROSOFT, WINGET_PKGS, client::GitHub},
+    create_ref::Ref,
+    get_repository_info::RepositoryOwner,
+    github_schema as schema,
+    types::PullRequestState,
+};
+use crate::terminal::{Hyperlinkable, SUPPORTS_HYPERLINKS};
+
+#[derive(cynic::QueryVariables)]
+pub struct GetPullRequestVariables<'a> {
+    pub owner: &'a str,
+    pub name: &'a str,
+    pub number: i32,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Query", variables = "GetPullRequestVariables")]
+pub struct GetPullRequest {
+    #[arguments(owner: $owner, name: $name)]
+    pub repository: Option<Repository>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(variables = "GetPullRequestVariables")]
+pub struct Repository {
+    #[arguments(number: $number)]
+    pub pull_request: Option<PullRequest>,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequest>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequest {
+    pub id: cynic::Id,
+    pub number: i32,
+    pub title: String,
+    pub body: String,
+    pub url: Url,
+    pub state: PullRequestState,
+    pub head_ref_name: String,
+    pub head_repository_owner: Option<RepositoryOwner>,
+    pub head_ref: Option<Ref>,
+    pub repository: PullRequestRepository,
+    /// A winget-pkgs pull request only changes the files of a single version, so the first page of
+    /// files is all of them
+    #[arguments(first: 100)]
+    pub files: Option<PullRequestChangedFileConnection>,
+}
+
+impl PullRequest {
+    /// Returns the login of the user that owns the repository the pull request was opened from.
+    #[inline]
+    pub fn head_owner(&self) -> Option<&str> {
+        self.head_repository_owner
+            .as_ref()
+            .map(|owner| owner.login.as_str())
+    }
+
+    /// Returns the paths of the files that the pull request adds.
+    pub fn added_files(&self) -> impl Iterator<Item = &str> {
+        self.files
+            .iter()
+            .filter_map(|files| files.nodes.as_ref())
+            .flatten()
+            .flatten()
+            .filter(|file| file.change_type == PatchStatus::Added)
+            .map(|file| file.path.as_str())
+    }
+
+    pub fn print_success(&self) {
+        let mut stdout = anstream::stdout();
+
+        let _ = writeln!(
+            stdout,
+            "{} updated {}",
+            "Successfully".green(),
+            format_args!(
+                "{repository_name_with_owner}#{number}",
+                repository_name_with_owner = self.repository.name_with_owner,
+                number = self.number
+            )
+            .hyperlink(&self.url)
+        );
+
+        // If the terminal doesn't support hyperlinks, print the pull request's URL on a new line
+        if !*SUPPORTS_HYPERLINKS {
+            let _ = writeln!(stdout, "{}", self.url);
+        }
+    }
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Repository")]
+pub struct PullRequestRepository {
+    pub name_with_owner: String,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequestchangedfileconnection>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequestChangedFileConnection {
+    pub nodes: Option<Vec<Option<PullRequestChangedFile>>>,
+}
+
+/// <https://docs.github.com/graphql/reference/objects#pullrequestchangedfile>
+#[derive(cynic::QueryFragment)]
+pub struct PullRequestChangedFile {
+    pub path: String,
+    pub change_type: PatchStatus,
+}
+
+/// <https://docs.github.com/graphql/reference/enums#patchstatus>
+#[derive(cynic::Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatchStatus {
+    Added,
+    Changed,
+    Copied,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+impl GitHub {
+    /// Returns the pull request to winget-pkgs with the given number, if it exists.
+    pub async fn get_pull_request(
+        &self,
+        number: NonZeroU32,
+    ) -> Result<Option<PullRequest>, GitHubError> {
+        let GraphQlResponse { data, errors } = self
+            .run_graphql(GetPullRequest::build(GetPullRequestVariables {
+                owner: MICROSOFT,
+                name: WINGET_PKGS,
+                number: i32::try_from(number.get()).unwrap_or(i32::MAX),
+            }))
+            .await?;
+
+        data.and_then(|data| data.repository)
+            .map(|repository| repository.pull_request)
+            .ok_or_else(|| {
+                GitHubError::graphql_errors(
+                    eyre!("failed to get pull request #{number} from {MICROSOFT}/{WINGET_PKGS}"),
+                    errors,
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cynic::QueryBuilder;
+    use indoc::indoc;
+
+    use super::{
+        super::super::{MICROSOFT, WINGET_PKGS},
+        GetPullRequest, GetPullRequestVariables,
+    };
+
+    #[test]
+    fn get_pull_request_output() {
+        const GET_PULL_REQUEST_QUERY: &str = indoc! {"
+            query GetPullRequest($owner: String!, $name: String!, $number: Int!) {
+              repository(owner: $owner, name: $name) {
+                pullRequest(number: $number) {
+                  id
+                  number
+                  title
+                  body
+                  url
+                  state
+                  headRefName
+                  headRepositoryOwner {
+                    login
+                  }
+                  headRef {
+                    id
+                    name
+                    target {
+                      oid
+                    }
+                  }
+                  repository {
+                    nameWithOwner
+                  }
+                  files(first: 100) {
+                    nodes {
+                      path
+                      changeType
+                    }
+                  }
+                }
+              }
+            }
+        "};
+
+        let operation = GetPullRequest::build(GetPullRequestVariables {
+            owner: MICROSOFT,
+            name: WINGET_PKGS,
+            number: 0,
+        });
+
+        assert_eq!(operation.query, GET_PULL_REQUEST_QUERY);
+    }
+}
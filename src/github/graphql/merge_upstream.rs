@@ -1,7 +1,7 @@
 use color_eyre::eyre::eyre;
-use cynic::{GraphQlResponse, Id, MutationBuilder, http::ReqwestExt};
+use cynic::{GraphQlResponse, Id, MutationBuilder};
 
-use super::{GRAPHQL_URL, github_schema as schema, types::GitObjectId};
+use super::{github_schema as schema, types::GitObjectId};
 use crate::github::{GitHubError, client::GitHub};
 
 #[derive(cynic::QueryVariables)]
@@ -56,8 +56,6 @@ pub async fn merge_upstream(
         force: bool,
     ) -> Result<(), GitHubError> {
         let GraphQlResponse { data, errors } = self
-            .0
-            .post(GRAPHQL_URL)
             .run_graphql(UpdateRef::build(MergeUpstreamVariables::new(
                 branch_ref_id,
                 upstream_target_oid,
@@ -8,10 +8,13 @@
 pub mod get_directory_content_with_text;
 pub mod get_existing_pull_request;
 mod get_file_content;
+pub mod get_open_pull_requests;
+pub mod get_pull_request;
 pub mod get_repository_info;
 pub mod merge_upstream;
 mod schema;
 pub mod types;
+pub mod update_pull_request;
 pub mod update_refs;
 
 pub use get_file_content::GetFileContent;
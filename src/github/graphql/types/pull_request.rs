@@ -5,7 +5,7 @@
 /// The possible states of a pull request.
 ///
 /// See <https://docs.github.com/graphql/reference/enums#pullrequeststate>.
-#[derive(cynic::Enum, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(cynic::Enum, Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum PullRequestState {
     /// A pull request that has been closed without being merged.
     Closed,
@@ -0,0 +1,91 @@
+use color_eyre::eyre::eyre;
+use cynic::{GraphQlResponse, MutationBuilder};
+
+use super::{
+    super::{GitHubError, client::GitHub},
+    github_schema as schema,
+};
+
+#[derive(cynic::QueryVariables)]
+pub struct UpdatePullRequestVariables<'a> {
+    pub input: UpdatePullRequestInput<'a>,
+}
+
+/// <https://docs.github.com/graphql/reference/mutations#updatepullrequest>
+#[derive(cynic::QueryFragment)]
+#[cynic(graphql_type = "Mutation", variables = "UpdatePullRequestVariables")]
+pub struct UpdatePullRequest {
+    #[arguments(input: $input)]
+    pub update_pull_request: Option<UpdatePullRequestPayload>,
+}
+
+#[derive(cynic::QueryFragment)]
+pub struct UpdatePullRequestPayload {
+    /// A unique identifier for the client performing the mutation.
+    #[expect(dead_code)]
+    pub client_mutation_id: Option<String>,
+}
+
+/// <https://docs.github.com/graphql/reference/input-objects#updatepullrequestinput>
+#[derive(cynic::InputObject)]
+pub struct UpdatePullRequestInput<'a> {
+    pub pull_request_id: &'a cynic::Id,
+    pub title: &'a str,
+    pub body: &'a str,
+}
+
+impl GitHub {
+    /// Changes the title and body of a pull request.
+    pub async fn update_pull_request(
+        &self,
+        pull_request_id: &cynic::Id,
+        title: &str,
+        body: &str,
+    ) -> Result<(), GitHubError> {
+        let GraphQlResponse { data, errors } = self
+            .run_graphql(UpdatePullRequest::build(UpdatePullRequestVariables {
+                input: UpdatePullRequestInput {
+                    pull_request_id,
+                    title,
+                    body,
+                },
+            }))
+            .await?;
+
+        data.and_then(|data| data.update_pull_request)
+            .map(|_| ())
+            .ok_or_else(|| {
+                GitHubError::graphql_errors(eyre!("failed to update pull request"), errors)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cynic::{Id, MutationBuilder};
+    use indoc::indoc;
+
+    use super::{UpdatePullRequest, UpdatePullRequestInput, UpdatePullRequestVariables};
+
+    #[test]
+    fn update_pull_request_output() {
+        const UPDATE_PULL_REQUEST_MUTATION: &str = indoc! {"
+            mutation UpdatePullRequest($input: UpdatePullRequestInput!) {
+              updatePullRequest(input: $input) {
+                clientMutationId
+              }
+            }
+        "};
+
+        let id = Id::new("");
+        let operation = UpdatePullRequest::build(UpdatePullRequestVariables {
+            input: UpdatePullRequestInput {
+                pull_request_id: &id,
+                title: "",
+                body: "",
+            },
+        });
+
+        assert_eq!(operation.query, UPDATE_PULL_REQUEST_MUTATION);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod client;
 mod error;
 pub mod graphql;
+mod rate_limit;
 mod rest;
 pub mod utils;
 
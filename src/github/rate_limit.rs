@@ -0,0 +1,360 @@
+use std::{
+    str::FromStr,
+    sync::LazyLock,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, HeaderName, RETRY_AFTER},
+};
+use tokio::{sync::Mutex, time::sleep};
+use tracing::{debug, warn};
+
+const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+
+const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+
+const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+const X_RATELIMIT_RESOURCE: HeaderName = HeaderName::from_static("x-ratelimit-resource");
+
+/// The number of times a rate limited request is retried before its response is returned as is
+pub const MAX_RATE_LIMIT_RETRIES: u8 = 5;
+
+/// GitHub asks to wait at least a minute before retrying when a secondary rate limit doesn't say
+/// how long to wait for
+///
+/// <https://docs.github.com/en/rest/using-the-rest-api/rate-limits-for-the-rest-api#exceeding-the-rate-limit>
+pub const SECONDARY_RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
+
+/// Once the remaining budget falls to this, requests are spread out over the time left until it
+/// resets instead of spending what's left all at once
+const LOW_BUDGET_THRESHOLD: u32 = 100;
+
+/// The budgets that GitHub tracks separately
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RateLimitResource {
+    Core,
+    GraphQl,
+}
+
+impl RateLimitResource {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Core => "core",
+            Self::GraphQl => "graphql",
+        }
+    }
+}
+
+impl FromStr for RateLimitResource {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "core" => Ok(Self::Core),
+            "graphql" => Ok(Self::GraphQl),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The rate limit budget that GitHub reports in the headers of a response
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Budget {
+    resource: RateLimitResource,
+    limit: u32,
+    remaining: u32,
+    reset_after: Duration,
+}
+
+impl Budget {
+    fn from_headers(headers: &HeaderMap, now: SystemTime) -> Option<Self> {
+        let reset_at = UNIX_EPOCH + Duration::from_secs(header_value(headers, &X_RATELIMIT_RESET)?);
+
+        Some(Self {
+            resource: header_value(headers, &X_RATELIMIT_RESOURCE)
+                .unwrap_or(RateLimitResource::Core),
+            limit: header_value(headers, &X_RATELIMIT_LIMIT)?,
+            remaining: header_value(headers, &X_RATELIMIT_REMAINING)?,
+            reset_after: reset_at.duration_since(now).unwrap_or_default(),
+        })
+    }
+}
+
+fn header_value<T: FromStr>(headers: &HeaderMap, name: &HeaderName) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[derive(Default)]
+struct ResourceBudget {
+    remaining: Option<u32>,
+    reset_at: Option<Instant>,
+    next_request_at: Option<Instant>,
+    is_low: bool,
+}
+
+impl ResourceBudget {
+    /// Reserves one request from the budget and returns when it may be sent.
+    fn reserve(&mut self, earliest: Instant) -> Instant {
+        let (Some(remaining), Some(reset_at)) = (self.remaining, self.reset_at) else {
+            return earliest;
+        };
+
+        if reset_at <= earliest {
+            // The budget has reset since it was last reported
+            *self = Self::default();
+            return earliest;
+        }
+
+        if remaining == 0 {
+            return reset_at;
+        }
+
+        let send_at = self
+            .next_request_at
+            .map_or(earliest, |next| next.max(earliest));
+        if remaining <= LOW_BUDGET_THRESHOLD {
+            let spacing = reset_at.saturating_duration_since(send_at) / (remaining + 1);
+            self.next_request_at = Some(send_at + spacing);
+        }
+
+        // Count the request straight away so that concurrent requests see the smaller budget
+        self.remaining = Some(remaining - 1);
+        send_at
+    }
+}
+
+#[derive(Default)]
+struct GovernorState {
+    core: ResourceBudget,
+    graphql: ResourceBudget,
+    /// Every request waits until this after GitHub rate limits one of them
+    paused_until: Option<Instant>,
+}
+
+impl GovernorState {
+    const fn budget_mut(&mut self, resource: RateLimitResource) -> &mut ResourceBudget {
+        match resource {
+            RateLimitResource::Core => &mut self.core,
+            RateLimitResource::GraphQl => &mut self.graphql,
+        }
+    }
+}
+
+/// Paces the requests that share a GitHub token by the rate limit budget that GitHub reports
+/// back, so that concurrent requests slow down as the budget runs out instead of failing.
+#[derive(Default)]
+pub struct RateLimitGovernor {
+    state: Mutex<GovernorState>,
+}
+
+impl RateLimitGovernor {
+    /// Waits until a request to the given resource may be sent.
+    pub async fn wait(&self, resource: RateLimitResource) {
+        let send_at = {
+            let mut state = self.state.lock().await;
+            let now = Instant::now();
+            let earliest = state.paused_until.map_or(now, |paused| paused.max(now));
+            state.budget_mut(resource).reserve(earliest)
+        };
+
+        let wait_time = send_at.saturating_duration_since(Instant::now());
+        if !wait_time.is_zero() {
+            debug!(
+                resource = resource.as_str(),
+                "Waiting {wait_time:?} for the GitHub rate limit"
+            );
+            sleep(wait_time).await;
+        }
+    }
+
+    /// Records the budget that GitHub reported in the headers of a response.
+    pub async fn observe(&self, headers: &HeaderMap) {
+        let Some(budget) = Budget::from_headers(headers, SystemTime::now()) else {
+            return;
+        };
+
+        debug!(
+            resource = budget.resource.as_str(),
+            remaining = budget.remaining,
+            limit = budget.limit,
+            "GitHub rate limit budget resets in {:?}",
+            budget.reset_after
+        );
+
+        let mut state = self.state.lock().await;
+        let resource_budget = state.budget_mut(budget.resource);
+        resource_budget.remaining = Some(budget.remaining);
+        resource_budget.reset_at = Some(Instant::now() + budget.reset_after);
+
+        let is_low = budget.remaining <= LOW_BUDGET_THRESHOLD;
+        if is_low && !resource_budget.is_low {
+            warn!(
+                "Only {} of {} GitHub {} requests are left until the rate limit resets in {:?}. Slowing down requests",
+                budget.remaining,
+                budget.limit,
+                budget.resource.as_str(),
+                budget.reset_after
+            );
+        }
+        resource_budget.is_low = is_low;
+    }
+
+    /// Holds back every request for the given duration.
+    pub async fn pause(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut state = self.state.lock().await;
+        if state.paused_until.is_none_or(|paused| paused < until) {
+            warn!("GitHub rate limited a request. Pausing requests for {delay:?}");
+            state.paused_until = Some(until);
+        }
+    }
+}
+
+/// The governor that every GitHub client in the process paces its requests with.
+///
+/// GitHub tracks the budget per token rather than per client, so clients created separately, such
+/// as by each package that autoupdate runs, have to share one governor to see the same budget.
+pub static RATE_LIMIT_GOVERNOR: LazyLock<RateLimitGovernor> =
+    LazyLock::new(RateLimitGovernor::default);
+
+/// Returns how long to wait before retrying a response that GitHub rate limited, or `None` if the
+/// response wasn't rate limited.
+///
+/// <https://docs.github.com/en/rest/using-the-rest-api/best-practices-for-using-the-rest-api#handle-rate-limit-errors-appropriately>
+pub fn retry_delay(
+    status: StatusCode,
+    headers: &HeaderMap,
+    attempt: u8,
+    now: SystemTime,
+) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    if let Some(retry_after) = header_value(headers, &RETRY_AFTER) {
+        return Some(Duration::from_secs(retry_after));
+    }
+
+    if let Some(budget) = Budget::from_headers(headers, now)
+        && budget.remaining == 0
+    {
+        return Some(budget.reset_after);
+    }
+
+    // A forbidden response without any rate limit headers is either a permissions error or a
+    // secondary rate limit, which only its body tells apart
+    (status == StatusCode::TOO_MANY_REQUESTS).then(|| secondary_delay(attempt))
+}
+
+/// Returns how long to wait before retrying a forbidden response whose body says that a secondary
+/// rate limit was exceeded, or `None` if it is a permissions error.
+pub fn secondary_rate_limit_delay(body: &str, attempt: u8) -> Option<Duration> {
+    body.to_ascii_lowercase()
+        .contains("secondary rate limit")
+        .then(|| secondary_delay(attempt))
+}
+
+/// Backs off exponentially from the delay that GitHub asks for after a secondary rate limit.
+fn secondary_delay(attempt: u8) -> Duration {
+    SECONDARY_RATE_LIMIT_DELAY * 2u32.pow(attempt.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use reqwest::{
+        StatusCode,
+        header::{HeaderMap, HeaderValue, RETRY_AFTER},
+    };
+    use rstest::rstest;
+
+    use super::{
+        Budget, RateLimitResource, ResourceBudget, SECONDARY_RATE_LIMIT_DELAY, X_RATELIMIT_LIMIT,
+        X_RATELIMIT_REMAINING, X_RATELIMIT_RESET, X_RATELIMIT_RESOURCE, retry_delay,
+        secondary_rate_limit_delay,
+    };
+
+    const NOW: u64 = 1_800_000_000;
+
+    fn headers(remaining: u32, reset_after: u64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(5000));
+        headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(remaining));
+        headers.insert(X_RATELIMIT_RESET, HeaderValue::from(NOW + reset_after));
+        headers.insert(X_RATELIMIT_RESOURCE, HeaderValue::from_static("graphql"));
+        headers
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW)
+    }
+
+    #[test]
+    fn parses_budget_from_headers() {
+        assert_eq!(
+            Budget::from_headers(&headers(4321, 600), now()),
+            Some(Budget {
+                resource: RateLimitResource::GraphQl,
+                limit: 5000,
+                remaining: 4321,
+                reset_after: Duration::from_secs(600),
+            })
+        );
+        assert_eq!(Budget::from_headers(&HeaderMap::new(), now()), None);
+    }
+
+    #[rstest]
+    #[case::retry_after(StatusCode::FORBIDDEN, Some(30), 10, Some(Duration::from_secs(30)))]
+    #[case::exhausted(StatusCode::FORBIDDEN, None, 0, Some(Duration::from_secs(600)))]
+    #[case::secondary(StatusCode::TOO_MANY_REQUESTS, None, 10, Some(SECONDARY_RATE_LIMIT_DELAY * 4))]
+    #[case::permissions(StatusCode::FORBIDDEN, None, 10, None)]
+    #[case::success(StatusCode::OK, Some(30), 0, None)]
+    fn retry_delays(
+        #[case] status: StatusCode,
+        #[case] retry_after: Option<u32>,
+        #[case] remaining: u32,
+        #[case] expected: Option<Duration>,
+    ) {
+        let mut headers = headers(remaining, 600);
+        if let Some(retry_after) = retry_after {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+
+        assert_eq!(retry_delay(status, &headers, 2, now()), expected);
+    }
+
+    #[rstest]
+    #[case::secondary(
+        r#"{"message":"You have exceeded a secondary rate limit. Please wait a few minutes before you try again."}"#,
+        Some(SECONDARY_RATE_LIMIT_DELAY * 4)
+    )]
+    #[case::permissions(
+        r#"{"message":"Resource not accessible by personal access token"}"#,
+        None
+    )]
+    fn secondary_rate_limit_delays(#[case] body: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(secondary_rate_limit_delay(body, 2), expected);
+    }
+
+    #[test]
+    fn spreads_out_a_low_budget_until_it_resets() {
+        let now = Instant::now();
+        let reset_at = now + Duration::from_secs(40);
+        let mut budget = ResourceBudget {
+            remaining: Some(3),
+            reset_at: Some(reset_at),
+            ..ResourceBudget::default()
+        };
+
+        assert_eq!(budget.reserve(now), now);
+        assert_eq!(budget.reserve(now), now + Duration::from_secs(10));
+        assert_eq!(budget.reserve(now), now + Duration::from_secs(20));
+        assert_eq!(budget.reserve(now), reset_at);
+        assert_eq!(budget.reserve(reset_at), reset_at);
+        assert_eq!(budget.remaining, None);
+    }
+}
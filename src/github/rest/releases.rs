@@ -30,11 +30,12 @@ pub async fn get_releases(
         let endpoint = format!("{REST_API_URL}/repos/{owner}/{repo}/releases?per_page=30");
 
         let response = self
-            .0
-            .get(endpoint)
-            .header(ACCEPT, GITHUB_JSON_MIME)
-            .header(X_GITHUB_API_VERSION, REST_API_VERSION)
-            .send()
+            .send(
+                self.0
+                    .get(endpoint)
+                    .header(ACCEPT, GITHUB_JSON_MIME)
+                    .header(X_GITHUB_API_VERSION, REST_API_VERSION),
+            )
             .await?
             .error_for_status()?;
 
@@ -50,11 +51,12 @@ pub async fn get_release_by_tag(
         let endpoint = format!("{REST_API_URL}/repos/{owner}/{repo}/releases/tags/{tag}");
 
         let response = self
-            .0
-            .get(endpoint)
-            .header(ACCEPT, GITHUB_JSON_MIME)
-            .header(X_GITHUB_API_VERSION, REST_API_VERSION)
-            .send()
+            .send(
+                self.0
+                    .get(endpoint)
+                    .header(ACCEPT, GITHUB_JSON_MIME)
+                    .header(X_GITHUB_API_VERSION, REST_API_VERSION),
+            )
             .await?
             .error_for_status()?;
 
@@ -123,11 +123,12 @@ async fn get_git_tree(
         };
 
         let response = self
-            .0
-            .get(endpoint)
-            .header(ACCEPT, GITHUB_JSON_MIME)
-            .header(X_GITHUB_API_VERSION, REST_API_VERSION)
-            .send()
+            .send(
+                self.0
+                    .get(endpoint)
+                    .header(ACCEPT, GITHUB_JSON_MIME)
+                    .header(X_GITHUB_API_VERSION, REST_API_VERSION),
+            )
             .await?
             .error_for_status()?;
 
@@ -166,11 +167,12 @@ async fn get_all_versions(
         );
 
         let response = self
-            .0
-            .get(endpoint)
-            .header(ACCEPT, GITHUB_JSON_MIME)
-            .header(X_GITHUB_API_VERSION, REST_API_VERSION)
-            .send()
+            .send(
+                self.0
+                    .get(endpoint)
+                    .header(ACCEPT, GITHUB_JSON_MIME)
+                    .header(X_GITHUB_API_VERSION, REST_API_VERSION),
+            )
             .await?
             .error_for_status()?;
 
@@ -1,6 +1,7 @@
 mod commit_title;
 mod package_path;
 pub mod pull_request;
+mod template;
 
 use std::{env, fmt::Write, num::NonZeroU32};
 
@@ -10,6 +11,7 @@
 use itertools::Itertools;
 pub use package_path::PackagePath;
 use rand::RngExt;
+pub use template::{PullRequestTemplate, TemplateValues};
 use uuid::Uuid;
 use winget_types::{
     LanguageTag, Manifest, ManifestType, PackageIdentifier, PackageVersion, url::DecodedUrl,
@@ -74,6 +76,33 @@ pub fn pull_request_body(
     alternative_text: Option<&str>,
     created_with: Option<&str>,
     created_with_url: Option<&DecodedUrl>,
+) -> String {
+    let mut body = String::new();
+    if let Some(alternative_text) = alternative_text {
+        let _ = writeln!(body, "### {alternative_text}");
+    } else {
+        let _ = writeln!(
+            body,
+            "### {}",
+            pull_request_headline(created_with, created_with_url)
+        );
+    }
+
+    if !issue_resolves.is_empty() {
+        let _ = writeln!(body);
+        for issue in issue_resolves.iter().sorted_unstable() {
+            let _ = writeln!(body, "- Resolves #{issue}");
+        }
+    }
+
+    body
+}
+
+/// Returns the headline crediting the tool that created the pull request, ending in a rocket or,
+/// occasionally, a random fruit emoji.
+pub fn pull_request_headline(
+    created_with: Option<&str>,
+    created_with_url: Option<&DecodedUrl>,
 ) -> String {
     const FRUITS: [&str; 16] = [
         "apple",
@@ -94,43 +123,31 @@ pub fn pull_request_body(
         "watermelon",
     ];
 
-    let mut body = String::new();
-    if let Some(alternative_text) = alternative_text {
-        let _ = writeln!(body, "### {alternative_text}");
+    let mut rng = rand::rng();
+
+    let emoji = if rng.random_ratio(1, 50) {
+        FRUITS[rng.random_range(0..FRUITS.len())]
     } else {
-        let mut rng = rand::rng();
-
-        let emoji = if rng.random_ratio(1, 50) {
-            FRUITS[rng.random_range(0..FRUITS.len())]
-        } else {
-            "rocket"
-        };
-
-        body.push_str("### Pull request has been created with ");
-
-        if let (Some(tool_name), Some(tool_url)) = (created_with, created_with_url) {
-            let _ = write!(body, "[{tool_name}]({tool_url})");
-        } else {
-            let _ = write!(
-                body,
-                "[{}]({}) v{}",
-                crate_name!(),
-                env!("CARGO_PKG_REPOSITORY"),
-                crate_version!()
-            );
-        }
+        "rocket"
+    };
 
-        let _ = writeln!(body, " :{emoji}:");
-    }
+    let mut headline = String::from("Pull request has been created with ");
 
-    if !issue_resolves.is_empty() {
-        let _ = writeln!(body);
-        for issue in issue_resolves.iter().sorted_unstable() {
-            let _ = writeln!(body, "- Resolves #{issue}");
-        }
+    if let (Some(tool_name), Some(tool_url)) = (created_with, created_with_url) {
+        let _ = write!(headline, "[{tool_name}]({tool_url})");
+    } else {
+        let _ = write!(
+            headline,
+            "[{}]({}) v{}",
+            crate_name!(),
+            env!("CARGO_PKG_REPOSITORY"),
+            crate_version!()
+        );
     }
 
-    body
+    let _ = write!(headline, " :{emoji}:");
+
+    headline
 }
 
 pub fn branch_name(
@@ -0,0 +1,312 @@
+use std::{fmt::Write, fs, num::NonZeroU32, str::FromStr};
+
+use bon::Builder;
+use camino::Utf8Path;
+use color_eyre::eyre::{Result, WrapErr};
+use itertools::Itertools;
+use serde::Deserialize;
+use thiserror::Error;
+use winget_types::{PackageIdentifier, PackageVersion, url::DecodedUrl};
+
+use crate::update_state::UpdateState;
+
+/// Commit title and pull request body templates, loaded from a YAML file such as:
+///
+/// ```yaml
+/// commit_title: "{update_state}: {identifier} version {version}"
+/// pull_request_body: |
+///   ### {headline}
+///
+///   Source: {source_url} ({strategy})
+///
+///   {release_notes}
+///
+///   {resolves}
+/// ```
+///
+/// Templates that are omitted fall back to Komac's default commit title and pull request body.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PullRequestTemplate {
+    commit_title: Option<String>,
+    pull_request_body: Option<String>,
+}
+
+impl PullRequestTemplate {
+    /// Reads and validates a template file. This is intended to be used as a clap value parser.
+    pub fn from_path(path: &str) -> Result<Self> {
+        let path = Utf8Path::new(path);
+        fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {path}"))?
+            .parse::<Self>()
+            .wrap_err_with(|| format!("Invalid pull request template in {path}"))
+    }
+
+    /// Renders the commit title template, if one was given.
+    pub fn commit_title(&self, values: &TemplateValues) -> Option<String> {
+        self.commit_title
+            .as_deref()
+            .map(|template| render(template, values).trim().to_owned())
+    }
+
+    /// Renders the pull request body template, if one was given.
+    pub fn pull_request_body(&self, values: &TemplateValues) -> Option<String> {
+        self.pull_request_body
+            .as_deref()
+            .map(|template| render(template, values))
+    }
+}
+
+impl FromStr for PullRequestTemplate {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let template = serde_yaml::from_str::<Self>(s)?;
+
+        for (key, text) in [
+            ("commit_title", &template.commit_title),
+            ("pull_request_body", &template.pull_request_body),
+        ] {
+            if let Some(placeholder) = text
+                .iter()
+                .flat_map(|text| placeholders(text))
+                .find(|placeholder| !Placeholder::NAMES.contains(placeholder))
+            {
+                return Err(TemplateError::UnknownPlaceholder {
+                    key,
+                    placeholder: placeholder.to_owned(),
+                });
+            }
+        }
+
+        Ok(template)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(
+        "Unknown placeholder `{{{placeholder}}}` in {key}. Valid placeholders are: {}",
+        Placeholder::NAMES.iter().map(|name| format!("{{{name}}}")).join(", ")
+    )]
+    UnknownPlaceholder {
+        key: &'static str,
+        placeholder: String,
+    },
+}
+
+/// The values that can be substituted into a [`PullRequestTemplate`].
+#[derive(Builder)]
+pub struct TemplateValues<'a> {
+    identifier: &'a PackageIdentifier,
+    version: &'a PackageVersion,
+    update_state: UpdateState,
+    headline: &'a str,
+    strategy: Option<&'a str>,
+    source_url: Option<&'a DecodedUrl>,
+    release_notes: Option<&'a str>,
+    #[builder(default)]
+    issue_resolves: &'a [NonZeroU32],
+}
+
+struct Placeholder;
+
+impl Placeholder {
+    const IDENTIFIER: &'static str = "identifier";
+    const VERSION: &'static str = "version";
+    const UPDATE_STATE: &'static str = "update_state";
+    const HEADLINE: &'static str = "headline";
+    const STRATEGY: &'static str = "strategy";
+    const SOURCE_URL: &'static str = "source_url";
+    const RELEASE_NOTES: &'static str = "release_notes";
+    const RESOLVES: &'static str = "resolves";
+
+    const NAMES: [&'static str; 8] = [
+        Self::IDENTIFIER,
+        Self::VERSION,
+        Self::UPDATE_STATE,
+        Self::HEADLINE,
+        Self::STRATEGY,
+        Self::SOURCE_URL,
+        Self::RELEASE_NOTES,
+        Self::RESOLVES,
+    ];
+}
+
+/// Returns the names of all the `{placeholder}`s in a template.
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| {
+        let (name, _) = part.split_once('}')?;
+        (!name.is_empty()
+            && name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_'))
+        .then_some(name)
+    })
+}
+
+fn render(template: &str, values: &TemplateValues) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest[1..].find(['{', '}']).map(|index| index + 1) else {
+            break;
+        };
+
+        // A placeholder can only start at the last brace before the closing one
+        if rest.as_bytes()[end] == b'{' {
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let name = &rest[1..end];
+        match name {
+            Placeholder::IDENTIFIER => output.push_str(values.identifier.as_str()),
+            Placeholder::VERSION => output.push_str(values.version.as_str()),
+            Placeholder::UPDATE_STATE => output.push_str(values.update_state.as_str()),
+            Placeholder::HEADLINE => output.push_str(values.headline),
+            Placeholder::STRATEGY => output.push_str(values.strategy.unwrap_or_default()),
+            Placeholder::SOURCE_URL => {
+                if let Some(source_url) = values.source_url {
+                    let _ = write!(output, "{source_url}");
+                }
+            }
+            Placeholder::RELEASE_NOTES => output.push_str(values.release_notes.unwrap_or_default()),
+            Placeholder::RESOLVES => output.push_str(
+                &values
+                    .issue_resolves
+                    .iter()
+                    .sorted_unstable()
+                    .map(|issue| format!("- Resolves #{issue}"))
+                    .join("\n"),
+            ),
+            _ => output.push_str(&rest[..=end]),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use indoc::indoc;
+    use rstest::rstest;
+    use winget_types::{PackageIdentifier, PackageVersion, url::DecodedUrl};
+
+    use super::{PullRequestTemplate, TemplateError, TemplateValues};
+    use crate::update_state::UpdateState;
+
+    fn render_with(template: &str) -> (Option<String>, Option<String>) {
+        let template = template.parse::<PullRequestTemplate>().unwrap();
+        let identifier = "Package.Identifier".parse::<PackageIdentifier>().unwrap();
+        let version = "1.2.3".parse::<PackageVersion>().unwrap();
+        let source_url = "https://example.com/releases"
+            .parse::<DecodedUrl>()
+            .unwrap();
+        let issue_resolves = [NonZeroU32::new(20).unwrap(), NonZeroU32::new(10).unwrap()];
+        let values = TemplateValues::builder()
+            .identifier(&identifier)
+            .version(&version)
+            .update_state(UpdateState::NewVersion)
+            .headline("Created with Komac")
+            .strategy("github-releases")
+            .source_url(&source_url)
+            .release_notes("Fixed a bug")
+            .issue_resolves(&issue_resolves)
+            .build();
+
+        (
+            template.commit_title(&values),
+            template.pull_request_body(&values),
+        )
+    }
+
+    #[test]
+    fn commit_title_template() {
+        let (commit_title, body) =
+            render_with(r#"commit_title: "{update_state}: {identifier} {version} [{strategy}]""#);
+
+        assert_eq!(
+            commit_title.as_deref(),
+            Some("New version: Package.Identifier 1.2.3 [github-releases]")
+        );
+        assert_eq!(body, None);
+    }
+
+    #[test]
+    fn pull_request_body_template() {
+        let (_, body) = render_with(indoc! {"
+            pull_request_body: |
+              ### {headline}
+
+              {release_notes}
+
+              Source: {source_url}
+
+              {resolves}
+              - [ ] Checked
+        "});
+
+        assert_eq!(
+            body.as_deref(),
+            Some(indoc! {"
+                ### Created with Komac
+
+                Fixed a bug
+
+                Source: https://example.com/releases
+
+                - Resolves #10
+                - Resolves #20
+                - [ ] Checked
+            "})
+        );
+    }
+
+    #[rstest]
+    #[case("{ not a placeholder }", "{ not a placeholder }")]
+    #[case("{}", "{}")]
+    #[case("unclosed {identifier", "unclosed {identifier")]
+    #[case("{x {identifier}", "{x Package.Identifier")]
+    #[case("{{identifier}}", "{Package.Identifier}")]
+    fn non_placeholders_are_kept(#[case] text: &str, #[case] expected: &str) {
+        let (commit_title, _) = render_with(&format!("commit_title: '{text}'"));
+
+        assert_eq!(commit_title.as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn unknown_placeholder() {
+        let error = "pull_request_body: '{changelog}'"
+            .parse::<PullRequestTemplate>()
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            TemplateError::UnknownPlaceholder {
+                key: "pull_request_body",
+                ref placeholder,
+            } if placeholder == "changelog"
+        ));
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(matches!(
+            "title: '{identifier}'".parse::<PullRequestTemplate>(),
+            Err(TemplateError::Yaml(_))
+        ));
+    }
+}
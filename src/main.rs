@@ -10,12 +10,15 @@
 use crate::{
     commands::{
         analyze::Analyze,
+        audit_hashes::AuditHashes,
         autoupdate::AutoUpdate,
         cleanup::Cleanup,
         compare_installers::CompareInstallers,
         complete::Complete,
         list_versions::ListVersions,
         new_version::NewVersion,
+        pull_requests::PullRequests,
+        recipes::commands::{RecipesArgs, RecipesCommands},
         remove_dead_versions::RemoveDeadVersions,
         remove_version::RemoveVersion,
         show_version::ShowVersion,
@@ -55,17 +58,22 @@ async fn main() -> Result<()> {
         Commands::Update(update_version) => update_version.run().await,
         Commands::AutoUpdate(autoupdate) => autoupdate.run().await,
         Commands::Cleanup(cleanup) => cleanup.run().await,
+        Commands::PullRequests(pull_requests) => pull_requests.run().await,
         Commands::Remove(remove_version) => remove_version.run().await,
         Commands::Token(token_args) => match token_args.command {
             TokenCommands::Remove(remove_token) => remove_token.run(),
             TokenCommands::Update(update_token) => update_token.run().await,
         },
+        Commands::Recipes(recipes_args) => match recipes_args.command {
+            RecipesCommands::Check(check_recipes) => check_recipes.run().await,
+        },
         Commands::List(list_versions) => list_versions.run().await,
         Commands::Show(show_version) => show_version.run().await,
         Commands::Sync(sync_fork) => sync_fork.run().await,
         Commands::Complete(complete) => complete.run(),
         Commands::Analyze(analyse) => analyse.run(),
         Commands::CompareInstallers(compare) => compare.run().await,
+        Commands::AuditHashes(audit_hashes) => audit_hashes.run().await,
         Commands::RemoveDeadVersions(remove_dead_versions) => remove_dead_versions.run().await,
         Commands::Submit(submit) => submit.run().await,
     }?;
@@ -117,13 +125,16 @@ enum Commands {
     AutoUpdate(AutoUpdate),
     Remove(RemoveVersion),
     Cleanup(Cleanup),
+    PullRequests(PullRequests),
     Token(TokenArgs),
+    Recipes(RecipesArgs),
     List(ListVersions),
     Show(ShowVersion),
     Sync(SyncFork),
     Complete(Complete),
     Analyze(Analyze),
     CompareInstallers(CompareInstallers),
+    AuditHashes(AuditHashes),
     RemoveDeadVersions(RemoveDeadVersions),
     Submit(Submit),
 }
@@ -10,9 +10,10 @@
 use clap::{crate_name, crate_version};
 use const_format::concatc;
 use owo_colors::{OwoColorize, Style, colors::css::SlateGrey};
+pub use release_notes::{ReleaseNotesRules, markdown_to_release_notes};
 use serde::Serialize;
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
-pub use url::Url;
+pub use url::{InstallerOverrides, Url};
 use winget_types::{
     Manifest,
     installer::InstallerManifest,
@@ -21,6 +22,7 @@
 };
 
 pub mod manifest;
+mod release_notes;
 mod url;
 
 pub struct Manifests {
@@ -0,0 +1,448 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_with::{DisplayFromStr, serde_as};
+use winget_types::locale::ReleaseNotes;
+
+/// The maximum number of characters that the manifest schema allows in release notes
+pub const RELEASE_NOTES_MAX_LENGTH: usize = 10_000;
+
+/// Ends release notes that were cut short to fit in the schema limit
+const ELLIPSIS: &str = "…";
+
+/// Headings of the sections that are generated alongside the changes themselves, such as GitHub's
+/// list of first-time contributors
+static BOILERPLATE_SECTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:new\s+)?contributors\b|^full\s+changelog\b|^(?:assets|downloads?|(?:sha-?256\s+)?checksums?)$",
+    )
+    .unwrap()
+});
+
+/// Lines that link to the full changelog rather than describe a change
+static BOILERPLATE_LINE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^full\s+changelog\b").unwrap());
+
+/// Rules that remove content from release notes, on top of the boilerplate that's always removed.
+///
+/// ```yaml
+/// release-notes:
+///   exclude-sections:
+///     - '^Dependencies$'
+///   exclude-lines:
+///     - '^Bump '
+/// ```
+#[serde_as]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReleaseNotesRules {
+    /// Sections whose heading matches any of these patterns are removed with their subsections.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub exclude_sections: Vec<Regex>,
+
+    /// Lines whose text, without any list marker, matches any of these patterns are removed.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub exclude_lines: Vec<Regex>,
+}
+
+impl ReleaseNotesRules {
+    fn excludes_section(&self, heading: &str) -> bool {
+        BOILERPLATE_SECTION_REGEX.is_match(heading)
+            || self
+                .exclude_sections
+                .iter()
+                .any(|pattern| pattern.is_match(heading))
+    }
+
+    fn excludes_line(&self, line: &str) -> bool {
+        BOILERPLATE_LINE_REGEX.is_match(line)
+            || self
+                .exclude_lines
+                .iter()
+                .any(|pattern| pattern.is_match(line))
+    }
+}
+
+/// A heading and the lines under it, up to the next heading.
+#[derive(Debug, Default)]
+struct Section {
+    /// The level of the heading, or 0 for the lines before the first heading.
+    level: usize,
+    heading: Option<String>,
+    lines: Vec<String>,
+}
+
+impl Section {
+    fn render(&self) -> String {
+        let lines = self
+            .lines
+            .iter()
+            .map(String::as_str)
+            .skip_while(|line| line.is_empty())
+            .fold(Vec::new(), |mut lines, line| {
+                // Collapse runs of blank lines
+                if !(line.is_empty() && lines.last().is_some_and(|last: &&str| last.is_empty())) {
+                    lines.push(line);
+                }
+                lines
+            });
+
+        self.heading
+            .as_deref()
+            .into_iter()
+            .chain(lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_owned()
+    }
+}
+
+/// Converts Markdown release notes into the plain text that manifests store.
+///
+/// Boilerplate sections and lines, and anything excluded by the rules, are removed. Headings lose
+/// their markers, nested lists keep their hierarchy as two-space indentation, and links, emphasis
+/// and tables are stripped. Release notes over the schema limit are cut at the last section that
+/// fits and end with an ellipsis.
+pub fn markdown_to_release_notes(
+    markdown: &str,
+    rules: &ReleaseNotesRules,
+) -> Option<ReleaseNotes> {
+    let sections = parse_sections(markdown, rules);
+
+    let rendered = sections
+        .iter()
+        .enumerate()
+        .filter(|(index, section)| {
+            // Keep an empty heading only if it's the parent of the section after it
+            !section.lines.iter().all(String::is_empty)
+                || (section.heading.is_some()
+                    && sections
+                        .get(index + 1)
+                        .is_some_and(|next| next.level > section.level))
+        })
+        .map(|(_, section)| section.render())
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>();
+
+    ReleaseNotes::new(join_within_limit(&rendered, RELEASE_NOTES_MAX_LENGTH)).ok()
+}
+
+fn parse_sections(markdown: &str, rules: &ReleaseNotesRules) -> Vec<Section> {
+    static COMMENT_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+    static HEADING_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(#{1,6})[ \t]+(.*?)(?:[ \t]+#+)?[ \t]*$").unwrap());
+    static LIST_ITEM_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^([ \t]*)([-*+]|\d+[.)])[ \t]+(.*)$").unwrap());
+    static THEMATIC_BREAK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(?:(?:-[ \t]*){3,}|(?:\*[ \t]*){3,}|(?:_[ \t]*){3,})$").unwrap()
+    });
+
+    let markdown = COMMENT_REGEX.replace_all(markdown, "");
+
+    let mut sections = vec![Section::default()];
+    let mut list_indents = Vec::new();
+    let mut in_code_block = false;
+    let mut excluded_level = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if let Some(captures) = HEADING_REGEX.captures(trimmed).filter(|_| !in_code_block) {
+            list_indents.clear();
+
+            let level = captures[1].len();
+            if excluded_level.is_some_and(|excluded| level > excluded) {
+                continue;
+            }
+
+            let heading = clean_inline(&captures[2]);
+            excluded_level = rules.excludes_section(&heading).then_some(level);
+            if excluded_level.is_none() {
+                sections.push(Section {
+                    level,
+                    heading: Some(heading),
+                    lines: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        if excluded_level.is_some() {
+            continue;
+        }
+
+        let lines = &mut sections.last_mut().unwrap_or_else(|| unreachable!()).lines;
+
+        if in_code_block {
+            lines.push(line.trim_end().to_owned());
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+
+        if is_table_line(trimmed) || THEMATIC_BREAK_REGEX.is_match(trimmed) {
+            continue;
+        }
+
+        let line = if let Some(captures) = LIST_ITEM_REGEX.captures(line) {
+            let indent = indent_width(&captures[1]);
+            while list_indents.last().is_some_and(|&last| last > indent) {
+                list_indents.pop();
+            }
+            if list_indents.last() != Some(&indent) {
+                list_indents.push(indent);
+            }
+
+            let text = clean_inline(&captures[3]);
+            if text.is_empty() || rules.excludes_line(&text) {
+                continue;
+            }
+
+            let marker = match &captures[2] {
+                "-" | "*" | "+" => "-",
+                ordered => ordered,
+            };
+            format!("{}{marker} {text}", "  ".repeat(list_indents.len() - 1))
+        } else {
+            if indent_width(line) == 0 {
+                list_indents.clear();
+            }
+
+            let text = clean_inline(trimmed);
+            if text.is_empty() || rules.excludes_line(&text) {
+                continue;
+            }
+
+            // A line that continues a list item lines up with the item's text
+            format!("{}{text}", "  ".repeat(list_indents.len()))
+        };
+
+        lines.push(line);
+    }
+
+    sections
+}
+
+/// Removes the inline Markdown syntax and HTML tags from a line of text.
+fn clean_inline(text: &str) -> String {
+    static IMAGE_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+    static LINK_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\[([^\]]*)\](?:\([^)]*\)|\[[^\]]*\])").unwrap());
+    static AUTOLINK_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"<(https?://[^>\s]+)>").unwrap());
+    static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"</?[A-Za-z][^>]*>").unwrap());
+    static EMPHASIS_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\*\*|__|~~|`|\*([^\s*][^*]*?)\*").unwrap());
+
+    let text = IMAGE_REGEX.replace_all(text, "$1");
+    let text = LINK_REGEX.replace_all(&text, "$1");
+    let text = AUTOLINK_REGEX.replace_all(&text, "$1");
+    let text = TAG_REGEX.replace_all(&text, "");
+    let text = EMPHASIS_REGEX.replace_all(&text, "$1");
+
+    html_escape::decode_html_entities(text.trim()).into_owned()
+}
+
+/// Returns the width of the leading whitespace of a line, counting a tab as four spaces.
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .map_while(|char| match char {
+            ' ' => Some(1),
+            '\t' => Some(4),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Returns whether a line is part of a Markdown table, or a table that was rendered from HTML.
+fn is_table_line(line: &str) -> bool {
+    line.starts_with('|') || line.contains('│') || line.starts_with('─')
+}
+
+/// Joins as many sections as fit within the maximum length, ending with an ellipsis if any were
+/// left out. A first section that doesn't fit on its own is cut at the last line that fits.
+fn join_within_limit(sections: &[String], max_length: usize) -> String {
+    const SEPARATOR: &str = "\n\n";
+
+    let joined = sections.join(SEPARATOR);
+    if joined.chars().count() <= max_length {
+        return joined;
+    }
+
+    let budget = max_length.saturating_sub(SEPARATOR.len() + ELLIPSIS.chars().count());
+    let mut text = String::new();
+    let mut length = 0;
+
+    for section in sections {
+        let separator_length = if text.is_empty() { 0 } else { SEPARATOR.len() };
+        let section_length = section.chars().count();
+        if length + separator_length + section_length > budget {
+            break;
+        }
+
+        if !text.is_empty() {
+            text.push_str(SEPARATOR);
+        }
+        text.push_str(section);
+        length += separator_length + section_length;
+    }
+
+    if text.is_empty() {
+        text.push_str(truncate_lines(&sections[0], budget));
+    }
+
+    text.push_str(SEPARATOR);
+    text.push_str(ELLIPSIS);
+    text
+}
+
+/// Truncates text to at most the given number of characters, cutting at the end of the last line
+/// that fits.
+fn truncate_lines(text: &str, max_length: usize) -> &str {
+    let Some((end, _)) = text.char_indices().nth(max_length) else {
+        return text;
+    };
+
+    text[..end]
+        .rfind('\n')
+        .map_or(&text[..end], |line_end| text[..line_end].trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use regex::Regex;
+
+    use super::{
+        ELLIPSIS, ReleaseNotesRules, join_within_limit, markdown_to_release_notes, truncate_lines,
+    };
+
+    fn convert(markdown: &str, rules: &ReleaseNotesRules) -> String {
+        let release_notes = markdown_to_release_notes(markdown, rules).unwrap();
+        AsRef::<str>::as_ref(&release_notes).to_owned()
+    }
+
+    #[test]
+    fn removes_github_boilerplate() {
+        let markdown = indoc! {"
+            <!-- Release notes generated using configuration in .github/release.yml -->
+
+            ## What's Changed
+            * Fix **crash** on start by @octocat in https://github.com/example/app/pull/1
+            * Add [dark mode](https://example.com/dark-mode) by @octocat in https://github.com/example/app/pull/2
+
+            ## New Contributors
+            * @octocat made their first contribution in https://github.com/example/app/pull/1
+
+            **Full Changelog**: https://github.com/example/app/compare/v1.2.2...v1.2.3
+
+            | Asset | SHA256 |
+            | --- | --- |
+            | app.msi | abc123 |
+        "};
+
+        assert_eq!(
+            convert(markdown, &ReleaseNotesRules::default()),
+            indoc! {"
+                What's Changed
+                - Fix crash on start by @octocat in https://github.com/example/app/pull/1
+                - Add dark mode by @octocat in https://github.com/example/app/pull/2"}
+        );
+    }
+
+    #[test]
+    fn preserves_list_hierarchy() {
+        let markdown = indoc! {"
+            ### Features
+            - Settings
+                * Add `--quiet` option
+                * Remember window size
+                    1. On Windows
+            - Faster startup
+        "};
+
+        assert_eq!(
+            convert(markdown, &ReleaseNotesRules::default()),
+            indoc! {"
+                Features
+                - Settings
+                  - Add --quiet option
+                  - Remember window size
+                    1. On Windows
+                - Faster startup"}
+        );
+    }
+
+    #[test]
+    fn applies_filtering_rules() {
+        let markdown = indoc! {"
+            ## Features
+            - Add dark mode
+            - Bump dependency from 1.0 to 1.1
+
+            ## Dependencies
+            - Update runtime
+        "};
+
+        let rules = ReleaseNotesRules {
+            exclude_sections: vec![Regex::new("^Dependencies$").unwrap()],
+            exclude_lines: vec![Regex::new("^Bump ").unwrap()],
+        };
+
+        assert_eq!(convert(markdown, &rules), "Features\n- Add dark mode");
+    }
+
+    #[test]
+    fn keeps_parent_headings_of_nested_sections() {
+        let markdown = indoc! {"
+            ## What's Changed
+            ### Fixes
+            - Crash on start
+            ## Empty
+        "};
+
+        assert_eq!(
+            convert(markdown, &ReleaseNotesRules::default()),
+            "What's Changed\n\nFixes\n- Crash on start"
+        );
+    }
+
+    #[test]
+    fn truncates_at_section_boundaries() {
+        let sections = ["First\n- one".to_owned(), "Second\n- two".to_owned()];
+
+        assert_eq!(
+            join_within_limit(&sections, 100),
+            "First\n- one\n\nSecond\n- two"
+        );
+        assert_eq!(
+            join_within_limit(&sections, 20),
+            format!("First\n- one\n\n{ELLIPSIS}")
+        );
+        assert_eq!(
+            join_within_limit(&sections, 8),
+            format!("First\n\n{ELLIPSIS}")
+        );
+    }
+
+    #[test]
+    fn truncates_at_line_end() {
+        let text = "first line\nsecond line\nthird line";
+
+        assert_eq!(truncate_lines(text, 100), text);
+        assert_eq!(truncate_lines(text, 15), "first line");
+        assert_eq!(truncate_lines("no line breaks", 5), "no li");
+    }
+}
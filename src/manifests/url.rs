@@ -4,19 +4,40 @@
     str::FromStr,
 };
 
-use url::ParseError;
-use winget_types::{installer::Architecture, url::DecodedUrl};
+use serde::{
+    Deserialize,
+    de::{
+        IntoDeserializer,
+        value::{Error as ValueError, StrDeserializer},
+    },
+};
+use thiserror::Error;
+use winget_types::{
+    LanguageTag,
+    installer::{Architecture, Installer, InstallerType, Scope},
+    url::DecodedUrl,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct Url {
     inner: DecodedUrl,
-    override_architecture: Option<Architecture>,
+    overrides: InstallerOverrides,
 }
 
 impl Url {
     #[inline]
     pub const fn override_architecture(&self) -> Option<Architecture> {
-        self.override_architecture
+        self.overrides.architecture
+    }
+
+    #[inline]
+    pub const fn overrides(&self) -> &InstallerOverrides {
+        &self.overrides
+    }
+
+    #[inline]
+    pub const fn overrides_mut(&mut self) -> &mut InstallerOverrides {
+        &mut self.overrides
     }
 
     #[inline]
@@ -59,14 +80,18 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 impl FromStr for Url {
-    type Err = ParseError;
+    type Err = UrlParseError;
 
+    /// Parses a URL with an optional `|` suffix of installer overrides.
+    ///
+    /// The suffix is either a bare architecture (`url|x64`) or a comma-separated list of
+    /// `key=value` pairs (`url|arch=x64,scope=user,type=wix,locale=de-DE`).
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (url, architecture) = s.rsplit_once('|').unwrap_or((s, ""));
+        let (url, overrides) = s.rsplit_once('|').unwrap_or((s, ""));
 
         Ok(Self {
             inner: url.parse()?,
-            override_architecture: architecture.parse().ok(),
+            overrides: overrides.parse()?,
         })
     }
 }
@@ -75,7 +100,204 @@ impl From<DecodedUrl> for Url {
     fn from(url: DecodedUrl) -> Self {
         Self {
             inner: url,
-            override_architecture: None,
+            overrides: InstallerOverrides::default(),
         }
     }
 }
+
+/// Installer properties that were explicitly set for a URL and take precedence over both the
+/// analysed installer and the previous installer it is matched with.
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct InstallerOverrides {
+    pub architecture: Option<Architecture>,
+    pub scope: Option<Scope>,
+    pub installer_type: Option<InstallerType>,
+    pub locale: Option<LanguageTag>,
+}
+
+impl InstallerOverrides {
+    const ARCHITECTURE: &'static str = "arch";
+    const SCOPE: &'static str = "scope";
+    const TYPE: &'static str = "type";
+    const LOCALE: &'static str = "locale";
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.architecture.is_none()
+            && self.scope.is_none()
+            && self.installer_type.is_none()
+            && self.locale.is_none()
+    }
+
+    /// Sets every overridden property on the given installer.
+    pub fn apply(&self, installer: &mut Installer) {
+        if let Some(architecture) = self.architecture {
+            installer.architecture = architecture;
+        }
+        if let Some(scope) = self.scope {
+            installer.scope = Some(scope);
+        }
+        if let Some(installer_type) = self.installer_type {
+            installer.r#type = Some(installer_type);
+        }
+        if let Some(locale) = &self.locale {
+            installer.locale = Some(locale.clone());
+        }
+    }
+
+    /// Returns `true` if every overridden property is equal to the given installer's property.
+    pub fn matches(&self, installer: &Installer) -> bool {
+        self.architecture
+            .is_none_or(|architecture| installer.architecture == architecture)
+            && self
+                .scope
+                .is_none_or(|scope| installer.scope == Some(scope))
+            && self
+                .installer_type
+                .is_none_or(|installer_type| installer.r#type == Some(installer_type))
+            && self
+                .locale
+                .as_ref()
+                .is_none_or(|locale| installer.locale.as_ref() == Some(locale))
+    }
+
+    /// Deserializes a value the same way it would be deserialized from a manifest, so that
+    /// `type=wix` and `scope=user` use the same casing as `InstallerType: wix` and `Scope: user`.
+    fn deserialize_value<T, F>(value: &str, invalid_value: F) -> Result<T, UrlParseError>
+    where
+        T: for<'de> Deserialize<'de>,
+        F: FnOnce() -> UrlParseError,
+    {
+        let deserializer: StrDeserializer<'_, ValueError> = value.into_deserializer();
+        T::deserialize(deserializer).map_err(|_| invalid_value())
+    }
+}
+
+impl FromStr for InstallerOverrides {
+    type Err = UrlParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = Self::default();
+
+        // A suffix without any key=value pairs is a bare architecture, as in `url|x64`
+        if !s.contains('=') {
+            overrides.architecture = s.parse().ok();
+            return Ok(overrides);
+        }
+
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| UrlParseError::InvalidOverride(pair.to_owned()))?;
+
+            let invalid_value = || UrlParseError::InvalidOverrideValue {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            };
+
+            match key {
+                Self::ARCHITECTURE => {
+                    overrides.architecture = Some(value.parse().map_err(|_| invalid_value())?);
+                }
+                Self::SCOPE => {
+                    overrides.scope = Some(Self::deserialize_value(value, invalid_value)?);
+                }
+                Self::TYPE => {
+                    overrides.installer_type = Some(Self::deserialize_value(value, invalid_value)?);
+                }
+                Self::LOCALE => {
+                    overrides.locale = Some(value.parse().map_err(|_| invalid_value())?);
+                }
+                _ => return Err(UrlParseError::UnknownOverride(key.to_owned())),
+            }
+        }
+
+        Ok(overrides)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UrlParseError {
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    #[error(
+        "Invalid installer override `{0}`. Overrides must be in the format `key=value`, such as `arch=x64`"
+    )]
+    InvalidOverride(String),
+    #[error(
+        "Unknown installer override `{0}`. Valid overrides are `arch`, `scope`, `type` and `locale`"
+    )]
+    UnknownOverride(String),
+    #[error("`{value}` is not a valid value for the `{key}` installer override")]
+    InvalidOverrideValue { key: String, value: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use winget_types::installer::{Architecture, InstallerType, Scope};
+
+    use super::{Url, UrlParseError};
+
+    const URL: &str = "https://www.example.com/file.exe";
+
+    #[rstest]
+    #[case(URL, None)]
+    #[case("https://www.example.com/file.exe|x64", Some(Architecture::X64))]
+    #[case("https://www.example.com/file.exe|arm64", Some(Architecture::Arm64))]
+    #[case("https://www.example.com/file.exe|invalid", None)]
+    fn legacy_architecture_suffix(#[case] input: &str, #[case] expected: Option<Architecture>) {
+        let url = input.parse::<Url>().unwrap();
+        assert_eq!(url.as_str(), URL);
+        assert_eq!(url.override_architecture(), expected);
+    }
+
+    #[test]
+    fn key_value_overrides() {
+        let url = "https://www.example.com/file.exe|arch=x64,scope=user,type=wix,locale=de-DE"
+            .parse::<Url>()
+            .unwrap();
+
+        let overrides = url.overrides();
+        assert_eq!(url.as_str(), URL);
+        assert_eq!(overrides.architecture, Some(Architecture::X64));
+        assert_eq!(overrides.scope, Some(Scope::User));
+        assert_eq!(overrides.installer_type, Some(InstallerType::Wix));
+        assert_eq!(
+            overrides
+                .locale
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("de-DE")
+        );
+    }
+
+    #[test]
+    fn partial_key_value_overrides() {
+        let url = "https://www.example.com/file.exe|scope=machine"
+            .parse::<Url>()
+            .unwrap();
+
+        let overrides = url.overrides();
+        assert_eq!(overrides.architecture, None);
+        assert_eq!(overrides.scope, Some(Scope::Machine));
+        assert_eq!(overrides.installer_type, None);
+        assert_eq!(overrides.locale, None);
+    }
+
+    #[rstest]
+    #[case("https://www.example.com/file.exe|arch=x64,foo=bar")]
+    #[case("https://www.example.com/file.exe|arch=x64,scope")]
+    #[case("https://www.example.com/file.exe|scope=everyone")]
+    #[case("https://www.example.com/file.exe|type=unknown")]
+    fn invalid_overrides(#[case] input: &str) {
+        assert!(matches!(
+            input.parse::<Url>(),
+            Err(UrlParseError::UnknownOverride(_)
+                | UrlParseError::InvalidOverride(_)
+                | UrlParseError::InvalidOverrideValue { .. })
+        ));
+    }
+}
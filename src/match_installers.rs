@@ -1,11 +1,21 @@
 use std::collections::HashMap;
 
 use camino::Utf8Path;
-use winget_types::installer::{Architecture, Installer, Scope, VALID_FILE_EXTENSIONS};
+use winget_types::{
+    installer::{Architecture, Installer, Scope, VALID_FILE_EXTENSIONS},
+    url::DecodedUrl,
+};
+
+use crate::manifests::InstallerOverrides;
+
+/// The score given to a new installer whose explicit overrides all match the previous installer.
+/// This is higher than any score that can be reached by inference alone.
+const OVERRIDE_MATCH_SCORE: u8 = 10;
 
 pub fn match_installers(
     previous_installers: Vec<Installer>,
     new_installers: &[Installer],
+    overrides: &HashMap<DecodedUrl, InstallerOverrides>,
 ) -> HashMap<Installer, Installer> {
     let found_architectures = new_installers
         .iter()
@@ -29,9 +39,30 @@ pub fn match_installers(
             let mut max_score = 0;
             let mut best_match = None;
 
-            for new_installer in new_installers {
+            // New installers with explicit overrides can only replace previous installers that
+            // have the same properties, unless no new installer is left to choose from
+            let candidates = new_installers
+                .iter()
+                .filter(|new_installer| {
+                    overrides
+                        .get(&new_installer.url)
+                        .is_none_or(|overrides| overrides.matches(&previous_installer))
+                })
+                .collect::<Vec<_>>();
+            let candidates = if candidates.is_empty() {
+                new_installers.iter().collect()
+            } else {
+                candidates
+            };
+
+            for new_installer in candidates {
                 let installer_url = &new_installer.url;
                 let mut score = 0;
+                if overrides.get(installer_url).is_some_and(|overrides| {
+                    !overrides.is_empty() && overrides.matches(&previous_installer)
+                }) {
+                    score += OVERRIDE_MATCH_SCORE;
+                }
                 if new_installer.architecture == previous_installer.architecture {
                     score += 1;
                 }
@@ -84,11 +115,11 @@ mod tests {
     use std::{collections::HashMap, str::FromStr};
 
     use winget_types::{
-        installer::{Architecture, Installer, Scope},
+        installer::{Architecture, Installer, InstallerType, Scope},
         url::DecodedUrl,
     };
 
-    use crate::match_installers::match_installers;
+    use crate::{manifests::InstallerOverrides, match_installers::match_installers};
 
     #[test]
     fn test_vscodium() {
@@ -139,7 +170,67 @@ fn test_vscodium() {
             (previous_machine_x64, installer_x64),
         ]);
         assert_eq!(
-            match_installers(previous_installers, &new_installers),
+            match_installers(previous_installers, &new_installers, &HashMap::new()),
+            expected
+        );
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let previous_user = Installer {
+            architecture: Architecture::X64,
+            r#type: Some(InstallerType::Wix),
+            scope: Some(Scope::User),
+            url: DecodedUrl::from_str("https://www.example.com/app-user.msi").unwrap(),
+            ..Installer::default()
+        };
+        let previous_machine = Installer {
+            scope: Some(Scope::Machine),
+            url: DecodedUrl::from_str("https://www.example.com/app-machine.msi").unwrap(),
+            ..previous_user.clone()
+        };
+
+        // Neither URL contains a hint about its scope, so only the overrides can tell them apart
+        let new_first = Installer {
+            architecture: Architecture::X64,
+            r#type: Some(InstallerType::Wix),
+            scope: Some(Scope::Machine),
+            url: DecodedUrl::from_str("https://www.example.com/2.0/a.msi").unwrap(),
+            ..Installer::default()
+        };
+        let new_second = Installer {
+            scope: Some(Scope::User),
+            url: DecodedUrl::from_str("https://www.example.com/2.0/b.msi").unwrap(),
+            ..new_first.clone()
+        };
+
+        let overrides = HashMap::from([
+            (
+                new_first.url.clone(),
+                InstallerOverrides {
+                    scope: Some(Scope::Machine),
+                    ..InstallerOverrides::default()
+                },
+            ),
+            (
+                new_second.url.clone(),
+                InstallerOverrides {
+                    scope: Some(Scope::User),
+                    ..InstallerOverrides::default()
+                },
+            ),
+        ]);
+
+        let expected = HashMap::from([
+            (previous_user.clone(), new_second.clone()),
+            (previous_machine.clone(), new_first.clone()),
+        ]);
+        assert_eq!(
+            match_installers(
+                vec![previous_user, previous_machine],
+                &[new_first, new_second],
+                &overrides
+            ),
             expected
         );
     }
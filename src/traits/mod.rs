@@ -10,7 +10,7 @@
 use winget_types::{
     Manifest, ManifestVersion, PackageVersion,
     installer::Architecture,
-    locale::{DefaultLocaleManifest, LocaleManifest, ReleaseNotes},
+    locale::{DefaultLocaleManifest, LocaleManifest},
     url::ReleaseNotesUrl,
 };
 
@@ -122,8 +122,8 @@ fn decorate_image(&mut self, _src: &str, title: &str) -> (String, Self::Annotati
         (title.to_string(), ())
     }
 
-    fn header_prefix(&self, _level: usize) -> String {
-        String::new()
+    fn header_prefix(&self, level: usize) -> String {
+        format!("{} ", "#".repeat(level))
     }
 
     fn quote_prefix(&self) -> String {
@@ -147,22 +147,18 @@ fn finalise(&mut self, _links: Vec<String>) -> Vec<TaggedLine<()>> {
     }
 }
 
-pub trait FromHtml {
-    fn from_html(html: &Html) -> Option<Self>
-    where
-        Self: Sized;
-}
-
-impl FromHtml for ReleaseNotes {
-    fn from_html(html: &Html) -> Option<Self> {
-        // Strings that have whitespace before newlines get escaped and treated as literal strings
-        // in YAML so this regex identifies any amount of whitespace and duplicate newlines
-        static NEWLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+\n").unwrap());
-
-        html2text::from_read_with_decorator(html.as_bytes(), usize::MAX, GitHubHtmlDecorator)
-            .ok()
-            .and_then(|text| Self::new(NEWLINE_REGEX.replace_all(&text, "\n")).ok())
-    }
+/// Renders HTML as Markdown-like text for [`markdown_to_release_notes`] to convert into release
+/// notes.
+///
+/// [`markdown_to_release_notes`]: crate::manifests::markdown_to_release_notes
+pub fn html_to_text(html: &Html) -> Option<String> {
+    // Strings that have whitespace before newlines get escaped and treated as literal strings
+    // in YAML so this regex identifies any amount of whitespace and duplicate newlines
+    static NEWLINE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+\n").unwrap());
+
+    html2text::from_read_with_decorator(html.as_bytes(), usize::MAX, GitHubHtmlDecorator)
+        .ok()
+        .map(|text| NEWLINE_REGEX.replace_all(&text, "\n").into_owned())
 }
 
 pub trait LocaleExt {